@@ -15,10 +15,10 @@ enum CounterMsg {
 
 #[derive(Debug)]
 enum CounterOutput {
-    SendFront(DynamicIndex),
-    MoveUp(DynamicIndex),
-    MoveDown(DynamicIndex),
-    Remove(DynamicIndex),
+    SendFront,
+    MoveUp,
+    MoveDown,
+    Remove,
 }
 
 #[relm4::factory]
@@ -56,31 +56,31 @@ impl FactoryComponent for Counter {
             #[name(move_up_button)]
             gtk::Button {
                 set_label: "Up",
-                connect_clicked[sender, index] => move |_| {
-                    sender.output(CounterOutput::MoveUp(index.clone())).unwrap();
+                connect_clicked[sender] => move |_| {
+                    sender.output(CounterOutput::MoveUp).unwrap();
                 }
             },
 
             #[name(move_down_button)]
             gtk::Button {
                 set_label: "Down",
-                connect_clicked[sender, index] => move |_| {
-                    sender.output(CounterOutput::MoveDown(index.clone())).unwrap();
+                connect_clicked[sender] => move |_| {
+                    sender.output(CounterOutput::MoveDown).unwrap();
                 }
             },
 
             #[name(to_front_button)]
             gtk::Button {
                 set_label: "To Start",
-                connect_clicked[sender, index] => move |_| {
-                    sender.output(CounterOutput::SendFront(index.clone())).unwrap();
+                connect_clicked[sender] => move |_| {
+                    sender.output(CounterOutput::SendFront).unwrap();
                 }
             },
 
             gtk::Button {
                 set_label: "Remove",
-                connect_clicked[sender, index] => move |_| {
-                    sender.output(CounterOutput::Remove(index.clone())).unwrap();
+                connect_clicked[sender] => move |_| {
+                    sender.output(CounterOutput::Remove).unwrap();
                 }
             }
         }
@@ -161,15 +161,14 @@ impl SimpleComponent for App {
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
-        let counters =
-            FactoryVecDeque::builder()
-                .launch_default()
-                .forward(sender.input_sender(), |msg| match msg {
-                    CounterOutput::SendFront(index) => AppMsg::SendFront(index),
-                    CounterOutput::MoveUp(index) => AppMsg::MoveUp(index),
-                    CounterOutput::MoveDown(index) => AppMsg::MoveDown(index),
-                    CounterOutput::Remove(index) => AppMsg::Remove(index),
-                });
+        let counters = FactoryVecDeque::builder()
+            .launch_default()
+            .forward_with_index(sender.input_sender(), |index, msg| match msg {
+                CounterOutput::SendFront => AppMsg::SendFront(index),
+                CounterOutput::MoveUp => AppMsg::MoveUp(index),
+                CounterOutput::MoveDown => AppMsg::MoveDown(index),
+                CounterOutput::Remove => AppMsg::Remove(index),
+            });
 
         let model = App {
             created_widgets: counter,
@@ -183,6 +182,17 @@ impl SimpleComponent for App {
     }
 
     fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        // Outputs can arrive after their counter was removed,
+        // for example if a button was clicked twice.
+        if let AppMsg::SendFront(index)
+        | AppMsg::MoveDown(index)
+        | AppMsg::MoveUp(index)
+        | AppMsg::Remove(index) = &msg
+            && index.is_removed()
+        {
+            return;
+        }
+
         let mut counters_guard = self.counters.guard();
         match msg {
             AppMsg::AddCounter => {
@@ -0,0 +1,197 @@
+use gtk::prelude::{BoxExt, ButtonExt, GtkWindowExt, OrientableExt, WidgetExt};
+use relm4::factory::{FactoryComponent, FactorySender, FactoryTree, TreeIndex};
+use relm4::{ComponentParts, ComponentSender, RelmApp, RelmWidgetExt, SimpleComponent};
+
+#[derive(Debug)]
+struct Node {
+    name: String,
+    expanded: bool,
+}
+
+#[derive(Debug)]
+enum NodeMsg {
+    SetExpanded(bool),
+}
+
+#[derive(Debug)]
+enum NodeOutput {
+    Toggle(TreeIndex),
+    AddChild(TreeIndex),
+    Remove(TreeIndex),
+}
+
+#[relm4::factory]
+impl FactoryComponent for Node {
+    type Init = String;
+    type Input = NodeMsg;
+    type Output = NodeOutput;
+    type CommandOutput = ();
+    type ParentWidget = gtk::ListBox;
+    type Index = TreeIndex;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Horizontal,
+            set_spacing: 5,
+
+            gtk::Button {
+                add_css_class: "flat",
+                #[watch]
+                set_icon_name: if self.expanded {
+                    "pan-down-symbolic"
+                } else {
+                    "pan-end-symbolic"
+                },
+                connect_clicked[sender, index] => move |_| {
+                    sender.output(NodeOutput::Toggle(index.clone())).unwrap();
+                }
+            },
+
+            gtk::Label {
+                set_hexpand: true,
+                set_xalign: 0.0,
+                set_label: &self.name,
+            },
+
+            gtk::Button {
+                set_icon_name: "list-add-symbolic",
+                connect_clicked[sender, index] => move |_| {
+                    sender.output(NodeOutput::AddChild(index.clone())).unwrap();
+                }
+            },
+
+            gtk::Button {
+                set_icon_name: "edit-delete-symbolic",
+                connect_clicked[sender, index] => move |_| {
+                    sender.output(NodeOutput::Remove(index.clone())).unwrap();
+                }
+            },
+        }
+    }
+
+    fn init_model(name: Self::Init, _index: &TreeIndex, _sender: FactorySender<Self>) -> Self {
+        Self {
+            name,
+            expanded: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: FactorySender<Self>) {
+        match msg {
+            NodeMsg::SetExpanded(expanded) => self.expanded = expanded,
+        }
+    }
+}
+
+struct App {
+    created_nodes: usize,
+    nodes: FactoryTree<Node>,
+}
+
+#[derive(Debug)]
+enum AppMsg {
+    AddRoot,
+    Toggle(TreeIndex),
+    AddChild(TreeIndex),
+    Remove(TreeIndex),
+}
+
+#[relm4::component]
+impl SimpleComponent for App {
+    type Init = ();
+    type Input = AppMsg;
+    type Output = ();
+
+    view! {
+        gtk::Window {
+            set_title: Some("Factory tree example"),
+            set_default_size: (300, 400),
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_spacing: 5,
+                set_margin_all: 5,
+
+                gtk::Button {
+                    set_label: "Add node",
+                    connect_clicked => AppMsg::AddRoot,
+                },
+
+                gtk::ScrolledWindow {
+                    set_vexpand: true,
+
+                    #[local_ref]
+                    node_list -> gtk::ListBox {
+                        set_selection_mode: gtk::SelectionMode::None,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let nodes = FactoryTree::builder()
+            .launch(gtk::ListBox::default())
+            .forward(sender.input_sender(), |output| match output {
+                NodeOutput::Toggle(index) => AppMsg::Toggle(index),
+                NodeOutput::AddChild(index) => AppMsg::AddChild(index),
+                NodeOutput::Remove(index) => AppMsg::Remove(index),
+            });
+
+        let model = App {
+            created_nodes: 0,
+            nodes,
+        };
+
+        let node_list = model.nodes.widget();
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        // Outputs can arrive after their node was removed,
+        // for example if a button was clicked twice.
+        if let AppMsg::Toggle(index) | AppMsg::AddChild(index) | AppMsg::Remove(index) = &msg
+            && index.is_removed()
+        {
+            return;
+        }
+
+        match msg {
+            AppMsg::AddRoot => {
+                self.created_nodes += 1;
+                self.nodes
+                    .append(&[], format!("Node {}", self.created_nodes));
+            }
+            AppMsg::Toggle(index) => {
+                let path = index.current_path();
+                let expanded = !self.nodes.is_expanded(&path);
+                self.nodes.set_expanded(&path, expanded);
+                self.nodes.send(&path, NodeMsg::SetExpanded(expanded));
+            }
+            AppMsg::AddChild(index) => {
+                let path = index.current_path();
+                self.created_nodes += 1;
+                self.nodes
+                    .append(&path, format!("Node {}", self.created_nodes));
+
+                // Show the new child.
+                self.nodes.set_expanded(&path, true);
+                self.nodes.send(&path, NodeMsg::SetExpanded(true));
+            }
+            AppMsg::Remove(index) => {
+                self.nodes.remove(&index.current_path());
+            }
+        }
+    }
+}
+
+fn main() {
+    let app = RelmApp::new("relm4.example.factory_tree");
+    app.run::<App>(());
+}
@@ -138,7 +138,9 @@ impl FactoryComponent for GamePage {
     }
 
     fn init_model(value: Self::Init, _index: &DynamicIndex, sender: FactorySender<Self>) -> Self {
-        GAME_STATE.subscribe(sender.input_sender(), |_| CounterMsg::Update);
+        GAME_STATE
+            .subscribe(sender.input_sender(), |_| CounterMsg::Update)
+            .drop_on_shutdown(sender.shutdown_receiver());
         Self { id: value }
     }
 
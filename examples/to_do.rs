@@ -15,7 +15,7 @@ enum TaskInput {
 
 #[derive(Debug)]
 enum TaskOutput {
-    Delete(DynamicIndex),
+    Delete,
 }
 
 #[relm4::factory]
@@ -50,8 +50,8 @@ impl FactoryComponent for Task {
                 set_icon_name: "edit-delete",
                 set_margin_all: 12,
 
-                connect_clicked[sender, index] => move |_| {
-                    sender.output(TaskOutput::Delete(index.clone())).unwrap();
+                connect_clicked[sender] => move |_| {
+                    sender.output(TaskOutput::Delete).unwrap();
                 }
             }
         }
@@ -129,7 +129,10 @@ impl SimpleComponent for App {
     fn update(&mut self, msg: AppMsg, _sender: ComponentSender<Self>) {
         match msg {
             AppMsg::DeleteEntry(index) => {
-                self.tasks.guard().remove(index.current_index());
+                // The task might be gone already if the button was clicked twice.
+                if !index.is_removed() {
+                    self.tasks.guard().remove(index.current_index());
+                }
             }
             AppMsg::AddEntry(name) => {
                 self.tasks.guard().push_back(name);
@@ -142,12 +145,11 @@ impl SimpleComponent for App {
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
-        let tasks =
-            FactoryVecDeque::builder()
-                .launch_default()
-                .forward(sender.input_sender(), |output| match output {
-                    TaskOutput::Delete(index) => AppMsg::DeleteEntry(index),
-                });
+        let tasks = FactoryVecDeque::builder()
+            .launch_default()
+            .forward_with_index(sender.input_sender(), |index, output| match output {
+                TaskOutput::Delete => AppMsg::DeleteEntry(index),
+            });
 
         let model = App { tasks };
 
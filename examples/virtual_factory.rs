@@ -0,0 +1,154 @@
+use gtk::prelude::{BoxExt, ButtonExt, GtkWindowExt, OrientableExt, WidgetExt};
+use relm4::factory::{VirtualFactory, VirtualFactoryComponent, VirtualFactorySender};
+use relm4::{ComponentParts, ComponentSender, RelmApp, RelmWidgetExt, SimpleComponent};
+
+#[derive(Debug)]
+struct Counter {
+    id: u32,
+    value: u8,
+}
+
+#[derive(Debug, Clone)]
+enum CounterMsg {
+    Increment,
+    Decrement,
+}
+
+struct CounterWidgets {
+    label: gtk::Label,
+}
+
+impl VirtualFactoryComponent for Counter {
+    type ParentWidget = gtk::ListView;
+    type Input = CounterMsg;
+    type Output = ();
+    type Init = u32;
+    type Root = gtk::Box;
+    type Widgets = CounterWidgets;
+
+    fn init_model(id: Self::Init, _sender: VirtualFactorySender<Self>) -> Self {
+        Self { id, value: 0 }
+    }
+
+    fn init_root() -> Self::Root {
+        gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(10)
+            .margin_start(5)
+            .margin_end(5)
+            .build()
+    }
+
+    // The widgets are created once per visible row and reused for other counters.
+    fn init_widgets(root: &Self::Root, sender: VirtualFactorySender<Self>) -> Self::Widgets {
+        let label = gtk::Label::builder().hexpand(true).xalign(0.0).build();
+
+        let increment = gtk::Button::with_label("+");
+        increment.connect_clicked({
+            let sender = sender.clone();
+            move |_| sender.input(CounterMsg::Increment)
+        });
+
+        let decrement = gtk::Button::with_label("-");
+        decrement.connect_clicked(move |_| sender.input(CounterMsg::Decrement));
+
+        root.append(&label);
+        root.append(&increment);
+        root.append(&decrement);
+
+        CounterWidgets { label }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: VirtualFactorySender<Self>) {
+        match msg {
+            CounterMsg::Increment => self.value = self.value.wrapping_add(1),
+            CounterMsg::Decrement => self.value = self.value.wrapping_sub(1),
+        }
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: VirtualFactorySender<Self>) {
+        widgets
+            .label
+            .set_label(&format!("Counter {}: {}", self.id, self.value));
+    }
+}
+
+struct App {
+    counters: VirtualFactory<Counter>,
+}
+
+#[derive(Debug)]
+enum AppMsg {
+    IncrementAll,
+    IncrementFirst,
+}
+
+#[relm4::component]
+impl SimpleComponent for App {
+    type Init = u32;
+    type Input = AppMsg;
+    type Output = ();
+
+    view! {
+        gtk::Window {
+            set_title: Some("Virtual factory example"),
+            set_default_size: (300, 500),
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_spacing: 5,
+                set_margin_all: 5,
+
+                gtk::Button {
+                    set_label: "Increment all",
+                    connect_clicked => AppMsg::IncrementAll,
+                },
+
+                gtk::Button {
+                    set_label: "Increment first",
+                    connect_clicked => AppMsg::IncrementFirst,
+                },
+
+                gtk::ScrolledWindow {
+                    set_vexpand: true,
+
+                    #[local_ref]
+                    counter_list -> gtk::ListView {}
+                }
+            }
+        }
+    }
+
+    fn init(
+        count: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let mut counters = VirtualFactory::builder().launch_default().detach();
+        counters.extend(0..count);
+
+        let model = App { counters };
+
+        let counter_list = model.counters.widget();
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            AppMsg::IncrementAll => self.counters.broadcast(CounterMsg::Increment),
+            AppMsg::IncrementFirst => {
+                if let Some(counter) = self.counters.get(0) {
+                    let mut counter = counter.borrow_mut();
+                    counter.value = counter.value.wrapping_add(1);
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    let app = RelmApp::new("relm4.example.virtual_factory");
+    app.run::<App>(50_000);
+}
@@ -28,13 +28,14 @@
 )]
 
 use proc_macro::TokenStream;
-use syn::{ItemImpl, parse_macro_input};
+use syn::{DeriveInput, ItemImpl, parse_macro_input};
 
 mod additional_fields;
 mod args;
 mod attrs;
 mod component;
 mod menu;
+mod observable;
 mod view;
 mod visitors;
 mod widgets;
@@ -636,6 +637,47 @@ pub fn widget_template(attributes: TokenStream, input: TokenStream) -> TokenStre
     widget_template::generate_tokens(visibility, item_impl).into()
 }
 
+/// Derive macro that implements `relm4::shared_state::Observable`
+/// and generates methods that track changes of each field.
+///
+/// Subscribers of shared state types can use these changes to only be notified
+/// if the fields they are interested in changed.
+///
+/// # Example
+///
+/// ```
+/// use relm4::shared_state::{Changes, Observable};
+///
+/// #[derive(Default, relm4_macros::Observable)]
+/// struct Document {
+///     title: String,
+///     #[observable(no_eq)]
+///     text: String,
+///     #[observable(skip)]
+///     cache: Vec<u8>,
+///     #[observable(changes)]
+///     changes: Changes,
+/// }
+///
+/// let mut document = Document::default();
+///
+/// // Setting an equal value doesn't mark the field as changed.
+/// document.set_title(String::new());
+/// assert!(!document.changed(Document::title()));
+///
+/// document.get_mut_text().push_str("Lorem ipsum");
+/// assert!(document.changed(Document::text()));
+/// assert!(!document.changed(Document::title()));
+///
+/// document.reset_changes();
+/// assert!(document.changes().is_empty());
+/// ```
+#[proc_macro_derive(Observable, attributes(observable))]
+pub fn observable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    observable::generate_tokens(input).into()
+}
+
 #[cfg(test)]
 #[rustversion::all(stable, since(1.72))]
 mod test {
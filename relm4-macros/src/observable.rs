@@ -0,0 +1,195 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Field, Fields, Ident};
+
+/// The maximum number of fields that can be observed.
+const MAX_FIELDS: usize = 64;
+
+#[derive(Default)]
+struct FieldAttrs {
+    changes: bool,
+    skip: bool,
+    no_eq: bool,
+}
+
+impl FieldAttrs {
+    fn parse(field: &Field) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        for attr in &field.attrs {
+            if !attr.path().is_ident("observable") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("changes") {
+                    attrs.changes = true;
+                } else if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                } else if meta.path.is_ident("no_eq") {
+                    attrs.no_eq = true;
+                } else {
+                    return Err(meta.error("expected `changes`, `skip` or `no_eq`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+pub(super) fn generate_tokens(input: DeriveInput) -> TokenStream2 {
+    match try_generate_tokens(input) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn try_generate_tokens(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let DeriveInput {
+        ident,
+        vis,
+        generics,
+        data,
+        ..
+    } = input;
+
+    let fields = match data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            fields => {
+                return Err(Error::new(
+                    fields.span(),
+                    "`Observable` can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                ident.span(),
+                "`Observable` can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut changes_field: Option<Ident> = None;
+    let mut methods = TokenStream2::new();
+    let mut index: usize = 0;
+
+    for field in &fields {
+        let attrs = FieldAttrs::parse(field)?;
+        let name = field.ident.as_ref().unwrap();
+
+        if attrs.changes {
+            if changes_field.is_some() {
+                return Err(Error::new(
+                    name.span(),
+                    "only one field can be marked with `#[observable(changes)]`",
+                ));
+            }
+            changes_field = Some(name.clone());
+            continue;
+        }
+        if attrs.skip {
+            continue;
+        }
+        if index == MAX_FIELDS {
+            return Err(Error::new(
+                name.span(),
+                "`Observable` supports at most 64 observed fields",
+            ));
+        }
+
+        let ty = &field.ty;
+        let bit = index as u32;
+        index += 1;
+
+        let get = format_ident!("get_{}", name);
+        let get_mut = format_ident!("get_mut_{}", name);
+        let set = format_ident!("set_{}", name);
+        let update = format_ident!("update_{}", name);
+
+        let set_body = if attrs.no_eq {
+            quote! {
+                self.#name = value;
+                self.__relm4_mark_changed(Self::#name());
+            }
+        } else {
+            quote! {
+                if self.#name != value {
+                    self.#name = value;
+                    self.__relm4_mark_changed(Self::#name());
+                }
+            }
+        };
+
+        let name_str = name.to_string();
+        let flag_doc =
+            format!("Returns the [`Changes`](relm4::shared_state::Changes) of `{name_str}`.");
+        let get_doc = format!("Get an immutable reference to `{name_str}`.");
+        let get_mut_doc =
+            format!("Get a mutable reference to `{name_str}` and mark it as changed.");
+        let set_doc = format!("Set the value of `{name_str}` and mark it as changed.");
+        let update_doc = format!("Update `{name_str}` with a function and mark it as changed.");
+
+        methods.extend(quote_spanned! { name.span() =>
+            #[doc = #flag_doc]
+            #[must_use]
+            #vis const fn #name() -> ::relm4::shared_state::Changes {
+                ::relm4::shared_state::Changes::field(#bit)
+            }
+
+            #[doc = #get_doc]
+            #[must_use]
+            #vis fn #get(&self) -> &#ty {
+                &self.#name
+            }
+
+            #[doc = #get_mut_doc]
+            #vis fn #get_mut(&mut self) -> &mut #ty {
+                self.__relm4_mark_changed(Self::#name());
+                &mut self.#name
+            }
+
+            #[doc = #set_doc]
+            #vis fn #set(&mut self, value: #ty) {
+                #set_body
+            }
+
+            #[doc = #update_doc]
+            #vis fn #update<F: ::std::ops::FnOnce(&mut #ty)>(&mut self, f: F) {
+                self.__relm4_mark_changed(Self::#name());
+                f(&mut self.#name);
+            }
+        });
+    }
+
+    let Some(changes_field) = changes_field else {
+        return Err(Error::new(
+            ident.span(),
+            "`Observable` requires a field of type `Changes` marked with `#[observable(changes)]`",
+        ));
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #methods
+
+            fn __relm4_mark_changed(&mut self, changes: ::relm4::shared_state::Changes) {
+                self.#changes_field.insert(changes);
+            }
+        }
+
+        impl #impl_generics ::relm4::shared_state::Observable for #ident #ty_generics #where_clause {
+            fn changes(&self) -> ::relm4::shared_state::Changes {
+                self.#changes_field
+            }
+
+            fn set_changes(&mut self, changes: ::relm4::shared_state::Changes) {
+                self.#changes_field = changes;
+            }
+        }
+    })
+}
@@ -0,0 +1,92 @@
+use relm4::SharedState;
+use relm4::shared_state::{Changes, Observable};
+
+#[derive(Debug, Default, relm4_macros::Observable)]
+struct Document {
+    title: String,
+    #[observable(no_eq)]
+    text: String,
+    #[observable(skip)]
+    cache: Vec<u8>,
+    #[observable(changes)]
+    changes: Changes,
+}
+
+#[derive(Default, relm4_macros::Observable)]
+struct Generic<T: Clone + PartialEq> {
+    value: T,
+    #[observable(changes)]
+    changes: Changes,
+}
+
+#[test]
+fn field_changes() {
+    assert_ne!(Document::title(), Document::text());
+    assert!(Document::title().intersects(Document::title() | Document::text()));
+
+    let mut document = Document::default();
+    assert!(document.changes().is_empty());
+
+    // Setting an equal value doesn't mark the field as changed.
+    document.set_title(String::new());
+    assert!(!document.changed(Document::title()));
+
+    document.set_title("Draft".into());
+    assert!(document.changed(Document::title()));
+    assert!(!document.changed(Document::text()));
+    assert_eq!(document.get_title(), "Draft");
+
+    // Fields with `no_eq` are always marked as changed.
+    document.reset_changes();
+    document.set_text(String::new());
+    assert!(document.changed(Document::text()));
+
+    document.reset_changes();
+    document.get_mut_text().push_str("Lorem");
+    document.update_text(|text| text.push_str(" ipsum"));
+    assert_eq!(document.get_text(), "Lorem ipsum");
+    assert_eq!(document.changes(), Document::text());
+
+    // Skipped fields are accessed directly.
+    document.cache.push(1);
+    assert_eq!(document.changes(), Document::text());
+
+    document.mark_all_changed();
+    assert!(document.changed(Document::title()));
+    document.reset_changes();
+    assert!(document.changes().is_empty());
+}
+
+#[test]
+fn generic_fields() {
+    let mut generic = Generic::<u8>::default();
+    generic.set_value(0);
+    assert!(!generic.changed(Generic::<u8>::value()));
+
+    generic.set_value(5);
+    assert!(generic.changed(Generic::<u8>::value()));
+    assert_eq!(*generic.get_value(), 5);
+}
+
+static DOCUMENT: SharedState<Document> = SharedState::new();
+
+#[test]
+fn subscribe_fields() {
+    let (sender, receiver) = relm4::channel();
+    let _subscription = DOCUMENT.subscribe_fields(&sender, Document::title(), |document| {
+        document.get_title().clone()
+    });
+
+    // Changes of other fields don't send a message,
+    // so the first message contains the new title.
+    DOCUMENT.write().set_text("Lorem ipsum".into());
+    DOCUMENT.write().set_title("Draft".into());
+    assert_eq!(receiver.recv_sync().unwrap(), "Draft");
+
+    // The changes are reset after the subscribers were notified,
+    // so setting an equal title doesn't send a message.
+    assert!(DOCUMENT.read().changes().is_empty());
+    DOCUMENT.write().set_title("Draft".into());
+    DOCUMENT.write().set_title("Final".into());
+    assert_eq!(receiver.recv_sync().unwrap(), "Final");
+}
@@ -0,0 +1,31 @@
+#![allow(dead_code)]
+
+use relm4::shared_state::Changes;
+
+#[derive(relm4_macros::Observable)]
+struct MissingChanges {
+    title: String,
+}
+
+#[derive(relm4_macros::Observable)]
+enum NotAStruct {
+    Variant,
+}
+
+#[derive(relm4_macros::Observable)]
+struct UnknownAttribute {
+    #[observable(ignore)]
+    title: String,
+    #[observable(changes)]
+    changes: Changes,
+}
+
+#[derive(relm4_macros::Observable)]
+struct DuplicateChanges {
+    #[observable(changes)]
+    changes: Changes,
+    #[observable(changes)]
+    other_changes: Changes,
+}
+
+fn main() {}
@@ -0,0 +1,113 @@
+use std::cmp::Reverse;
+use std::time::Duration;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use relm4::factory::{DynamicIndex, FactoryComponent, FactorySender, FactoryVecDeque};
+
+// Large enough to make quadratic rendering noticeable.
+const ELEMENTS: usize = 2000;
+
+#[derive(Debug)]
+struct Row {
+    value: usize,
+}
+
+impl FactoryComponent for Row {
+    type ParentWidget = gtk::Box;
+    type CommandOutput = ();
+    type Input = ();
+    type Output = ();
+    type Init = usize;
+    type Root = gtk::Label;
+    type Widgets = ();
+    type Index = DynamicIndex;
+
+    fn init_model(value: Self::Init, _index: &DynamicIndex, _sender: FactorySender<Self>) -> Self {
+        Self { value }
+    }
+
+    fn init_root(&self) -> Self::Root {
+        gtk::Label::new(Some(&self.value.to_string()))
+    }
+
+    fn init_widgets(
+        &mut self,
+        _index: &DynamicIndex,
+        _root: Self::Root,
+        _returned_widget: &gtk::Widget,
+        _sender: FactorySender<Self>,
+    ) -> Self::Widgets {
+    }
+}
+
+/// Creates a rendered factory with all values in a scrambled order.
+fn scrambled_factory() -> FactoryVecDeque<Row> {
+    let mut factory = FactoryVecDeque::builder()
+        .launch(gtk::Box::default())
+        .detach();
+
+    // 7919 is prime, so this visits every value exactly once.
+    factory.extend((0..ELEMENTS).map(|i| (i * 7919) % ELEMENTS));
+    factory
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .warm_up_time(Duration::from_millis(100))
+        .sample_size(20);
+    targets = benchmark
+}
+criterion_main!(benches);
+
+fn benchmark(c: &mut Criterion) {
+    gtk::init().unwrap();
+
+    c.bench_function("factory_push_back", |b| {
+        b.iter_batched(
+            || {
+                FactoryVecDeque::<Row>::builder()
+                    .launch(gtk::Box::default())
+                    .detach()
+            },
+            |mut factory| {
+                factory.extend(0..ELEMENTS);
+                factory
+            },
+            BatchSize::PerIteration,
+        );
+    });
+
+    c.bench_function("factory_sort_reverse", |b| {
+        b.iter_batched(
+            scrambled_factory,
+            |mut factory| {
+                factory.guard().sort_by_key(|row| Reverse(row.value));
+                factory
+            },
+            BatchSize::PerIteration,
+        );
+    });
+
+    c.bench_function("factory_sort", |b| {
+        b.iter_batched(
+            scrambled_factory,
+            |mut factory| {
+                factory.guard().sort_by_key(|row| row.value);
+                factory
+            },
+            BatchSize::PerIteration,
+        );
+    });
+
+    c.bench_function("factory_remove_every_other", |b| {
+        b.iter_batched(
+            scrambled_factory,
+            |mut factory| {
+                factory.guard().retain(|row| row.value % 2 == 0);
+                factory
+            },
+            BatchSize::PerIteration,
+        );
+    });
+}
@@ -1,12 +1,14 @@
 //! A module for convenient abstractions over gtk-rs.
 
 pub mod drawing;
+pub mod window_state;
 
 #[cfg(feature = "libadwaita")]
 #[cfg_attr(docsrs, doc(cfg(feature = "libadwaita")))]
 mod toaster;
 
 pub use drawing::{DrawContext, DrawHandler};
+pub use window_state::{WindowState, WindowStatePersistence, WindowStateStorage};
 
 #[cfg(feature = "libadwaita")]
 #[cfg_attr(docsrs, doc(cfg(feature = "libadwaita")))]
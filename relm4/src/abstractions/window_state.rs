@@ -0,0 +1,355 @@
+//! Utility to save and restore the size and state of a [`gtk::Window`].
+//!
+//! Create a [`WindowStatePersistence`] for your window, store it in your model
+//! and the default size, the maximized and the fullscreen state of the window
+//! will be restored on startup and saved whenever they change.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk::prelude::{GtkWindowExt, ObjectExt, SettingsExt};
+use gtk::{gio, glib};
+
+#[cfg(feature = "serde")]
+use std::path::PathBuf;
+
+/// The size and state of a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowState {
+    /// The default width of the window.
+    pub width: i32,
+    /// The default height of the window.
+    pub height: i32,
+    /// Whether the window is maximized.
+    pub maximized: bool,
+    /// Whether the window is fullscreen.
+    pub fullscreen: bool,
+}
+
+impl WindowState {
+    /// Read the current state of a window.
+    #[must_use]
+    pub fn from_window(window: &impl AsRef<gtk::Window>) -> Self {
+        let window = window.as_ref();
+        let (width, height) = window.default_size();
+
+        Self {
+            width,
+            height,
+            maximized: window.is_maximized(),
+            fullscreen: window.is_fullscreen(),
+        }
+    }
+
+    /// Apply the state to a window.
+    pub fn apply(&self, window: &impl AsRef<gtk::Window>) {
+        let window = window.as_ref();
+
+        if self.width > 0 && self.height > 0 {
+            window.set_default_size(self.width, self.height);
+        }
+        if self.maximized {
+            window.maximize();
+        }
+        if self.fullscreen {
+            window.fullscreen();
+        }
+    }
+}
+
+/// The storage backend used by [`WindowStatePersistence`].
+#[derive(Debug, Clone)]
+pub enum WindowStateStorage {
+    /// Store the window state in a [`gio::Settings`] schema.
+    ///
+    /// The schema must contain the integer keys `window-width` and `window-height`
+    /// and the boolean keys `is-maximized` and `is-fullscreen`.
+    Settings(gio::Settings),
+    /// Store the window state as JSON file at the given path.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    File(PathBuf),
+}
+
+impl WindowStateStorage {
+    const WIDTH_KEY: &'static str = "window-width";
+    const HEIGHT_KEY: &'static str = "window-height";
+    const MAXIMIZED_KEY: &'static str = "is-maximized";
+    const FULLSCREEN_KEY: &'static str = "is-fullscreen";
+
+    /// Load the stored window state.
+    ///
+    /// Returns [`None`] if no state was stored yet or the stored state couldn't be read.
+    #[must_use]
+    pub fn load(&self) -> Option<WindowState> {
+        match self {
+            Self::Settings(settings) => Some(WindowState {
+                width: settings.int(Self::WIDTH_KEY),
+                height: settings.int(Self::HEIGHT_KEY),
+                maximized: settings.boolean(Self::MAXIMIZED_KEY),
+                fullscreen: settings.boolean(Self::FULLSCREEN_KEY),
+            }),
+            #[cfg(feature = "serde")]
+            Self::File(path) => {
+                let bytes = std::fs::read(path).ok()?;
+                serde_json::from_slice(&bytes)
+                    .map_err(|err| {
+                        tracing::error!("Couldn't parse window state file: {}", err);
+                    })
+                    .ok()
+            }
+        }
+    }
+
+    /// Store the window state.
+    ///
+    /// If the state can't be stored a [`tracing::error`] message will be emitted.
+    pub fn save(&self, state: &WindowState) {
+        match self {
+            Self::Settings(settings) => {
+                let result = settings
+                    .set_int(Self::WIDTH_KEY, state.width)
+                    .and_then(|()| settings.set_int(Self::HEIGHT_KEY, state.height))
+                    .and_then(|()| settings.set_boolean(Self::MAXIMIZED_KEY, state.maximized))
+                    .and_then(|()| settings.set_boolean(Self::FULLSCREEN_KEY, state.fullscreen));
+
+                if let Err(err) = result {
+                    tracing::error!("Couldn't save window state: {}", err);
+                }
+            }
+            #[cfg(feature = "serde")]
+            Self::File(path) => {
+                let result = serde_json::to_vec_pretty(state)
+                    .map_err(std::io::Error::other)
+                    .and_then(|bytes| crate::file_util::write_atomically(path, &bytes));
+
+                if let Err(err) = result {
+                    tracing::error!("Couldn't save window state: {}", err);
+                }
+            }
+        }
+    }
+}
+
+struct Inner {
+    window: glib::WeakRef<gtk::Window>,
+    storage: WindowStateStorage,
+    state: Cell<WindowState>,
+    delay: Cell<Duration>,
+    pending_save: RefCell<Option<glib::SourceId>>,
+}
+
+impl Inner {
+    /// Update the stored state from the window and
+    /// schedule a debounced save.
+    fn update(self: &Rc<Self>) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+
+        let mut state = self.state.get();
+        state.maximized = window.is_maximized();
+        state.fullscreen = window.is_fullscreen();
+
+        // Keep the size of the window in its normal state,
+        // so it can be restored after unmaximizing.
+        if !state.maximized && !state.fullscreen {
+            let (width, height) = window.default_size();
+            state.width = width;
+            state.height = height;
+        }
+
+        if state == self.state.get() {
+            return;
+        }
+        self.state.set(state);
+
+        if let Some(source) = self.pending_save.take() {
+            source.remove();
+        }
+
+        let weak = Rc::downgrade(self);
+        let source = glib::timeout_add_local_once(self.delay.get(), move || {
+            if let Some(inner) = weak.upgrade() {
+                // The source is removed automatically after it ran.
+                inner.pending_save.take();
+                inner.storage.save(&inner.state.get());
+            }
+        });
+        *self.pending_save.borrow_mut() = Some(source);
+    }
+
+    fn flush(&self) {
+        if let Some(source) = self.pending_save.take() {
+            source.remove();
+            self.storage.save(&self.state.get());
+        }
+    }
+}
+
+/// Saves and restores the default size, the maximized and
+/// the fullscreen state of a [`gtk::Window`].
+///
+/// The stored state is applied to the window on creation.
+/// Afterwards, changes are saved after a short delay, so resizing the window
+/// doesn't cause a write on every frame.
+/// Pending changes are saved when the window is closed or when this type is dropped,
+/// for example in the [`shutdown`](crate::Component::shutdown) method of a component.
+/// Dropping this type also stops tracking the window, so store it as long as
+/// the state should be saved.
+///
+/// # Example
+///
+/// ```no_run
+/// # use relm4::abstractions::{WindowStatePersistence, WindowStateStorage};
+/// # use relm4::gtk::gio;
+/// # gtk::init().unwrap();
+/// let window = gtk::Window::new();
+/// let settings = gio::Settings::new("org.relm4.Example");
+///
+/// // Store this in the model of your component.
+/// let window_state = WindowStatePersistence::new(&window, WindowStateStorage::Settings(settings));
+/// ```
+pub struct WindowStatePersistence {
+    inner: Rc<Inner>,
+    handlers: Vec<glib::SignalHandlerId>,
+}
+
+impl fmt::Debug for WindowStatePersistence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WindowStatePersistence")
+            .field("window", &self.inner.window.upgrade())
+            .field("storage", &self.inner.storage)
+            .field("state", &self.inner.state.get())
+            .field("delay", &self.inner.delay.get())
+            .finish()
+    }
+}
+
+impl WindowStatePersistence {
+    /// The default delay used to debounce saving the state.
+    pub const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+
+    /// Restore the state of the window from the given storage
+    /// and save all future changes to it.
+    #[must_use]
+    pub fn new(window: &impl AsRef<gtk::Window>, storage: WindowStateStorage) -> Self {
+        let window = window.as_ref();
+
+        let state = if let Some(state) = storage.load() {
+            state.apply(window);
+            state
+        } else {
+            WindowState::from_window(window)
+        };
+
+        let inner = Rc::new(Inner {
+            window: window.downgrade(),
+            storage,
+            state: Cell::new(state),
+            delay: Cell::new(Self::DEFAULT_DELAY),
+            pending_save: RefCell::default(),
+        });
+
+        let rt_inner = inner.clone();
+        let width_handler = window.connect_default_width_notify(move |_| rt_inner.update());
+        let rt_inner = inner.clone();
+        let height_handler = window.connect_default_height_notify(move |_| rt_inner.update());
+        let rt_inner = inner.clone();
+        let maximized_handler = window.connect_maximized_notify(move |_| rt_inner.update());
+        let rt_inner = inner.clone();
+        let fullscreen_handler = window.connect_fullscreened_notify(move |_| rt_inner.update());
+        let rt_inner = inner.clone();
+        let close_handler = window.connect_close_request(move |_| {
+            rt_inner.flush();
+            glib::Propagation::Proceed
+        });
+
+        Self {
+            inner,
+            handlers: vec![
+                width_handler,
+                height_handler,
+                maximized_handler,
+                fullscreen_handler,
+                close_handler,
+            ],
+        }
+    }
+
+    /// Set the delay used to debounce saving the state.
+    ///
+    /// By default, this is [`Self::DEFAULT_DELAY`].
+    #[must_use]
+    pub fn with_delay(self, delay: Duration) -> Self {
+        self.inner.delay.set(delay);
+        self
+    }
+
+    /// The last known state of the window.
+    #[must_use]
+    pub fn state(&self) -> WindowState {
+        self.inner.state.get()
+    }
+
+    /// Save pending changes immediately.
+    pub fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+impl Drop for WindowStatePersistence {
+    fn drop(&mut self) {
+        self.flush();
+
+        // Dropping the handlers also drops the references to the state they hold.
+        if let Some(window) = self.inner.window.upgrade() {
+            for handler in self.handlers.drain(..) {
+                window.disconnect(handler);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use super::{WindowState, WindowStateStorage};
+
+    #[test]
+    fn json_round_trip() {
+        let dir = std::env::temp_dir().join(format!("relm4-window-state-{}", std::process::id()));
+        let path = dir.join("nested").join("window-state.json");
+        let storage = WindowStateStorage::File(path.clone());
+
+        assert_eq!(storage.load(), None);
+
+        let state = WindowState {
+            width: 800,
+            height: 600,
+            maximized: true,
+            fullscreen: false,
+        };
+        storage.save(&state);
+        assert_eq!(storage.load(), Some(state));
+
+        let state = WindowState {
+            width: 1024,
+            fullscreen: true,
+            ..state
+        };
+        storage.save(&state);
+        assert_eq!(storage.load(), Some(state));
+
+        // No temporary files are left behind.
+        let files: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, ["window-state.json"]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
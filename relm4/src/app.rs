@@ -2,11 +2,15 @@ use gtk::glib;
 use gtk::prelude::{ApplicationExt, ApplicationExtManual, Cast, GtkApplicationExt, IsA, WidgetExt};
 use std::fmt::Debug;
 
-use crate::component::{AsyncComponent, AsyncComponentBuilder, AsyncComponentController};
+use crate::component::{
+    AsyncComponent, AsyncComponentBuilder, AsyncComponentController, AsyncConnector,
+    AsyncController, Connector, Controller,
+};
 use crate::runtime_util::shutdown_all;
 use crate::{Component, ComponentBuilder, ComponentController, MessageBroker, RUNTIME};
 
 use std::cell::Cell;
+use std::rc::Rc;
 
 /// An app that runs the main application.
 #[derive(Debug)]
@@ -145,10 +149,96 @@ impl<M: Debug + 'static> RelmApp<M> {
     }
 
     /// Runs the application, returns once the application is closed.
-    pub fn run<C>(self, payload: C::Init)
+    ///
+    /// The returned [`glib::ExitCode`] is the one set by [`relm4::set_exit_code()`]
+    /// or, if no exit code was set, the exit code returned by [`gtk::Application`].
+    /// Use [`run_with_exit_code()`](Self::run_with_exit_code) to let the
+    /// root component decide the exit code with its output instead.
+    ///
+    /// [`relm4::set_exit_code()`]: crate::set_exit_code
+    pub fn run<C>(self, payload: C::Init) -> glib::ExitCode
+    where
+        C: Component<Input = M>,
+        C::Root: AsRef<gtk::Window>,
+    {
+        self.run_component(payload, Connector::<C>::detach)
+    }
+
+    /// Runs the application, returns once the application is closed.
+    ///
+    /// The application quits as soon as the root component sends an output,
+    /// which is converted into the returned [`glib::ExitCode`].
+    /// This allows the root component to report the result of the
+    /// application in a typed way, for example with an output like `Quit(u8)`
+    /// that implements [`Into<glib::ExitCode>`].
+    ///
+    /// If the application is closed without an output, the exit code is the same
+    /// as the one returned by [`run()`](Self::run).
+    pub fn run_with_exit_code<C>(self, payload: C::Init) -> glib::ExitCode
+    where
+        C: Component<Input = M>,
+        C::Root: AsRef<gtk::Window>,
+        C::Output: Into<glib::ExitCode>,
+    {
+        let app = self.app.clone();
+        let exit_code = Rc::new(Cell::new(None));
+        let output_exit_code = exit_code.clone();
+
+        let app_exit_code = self.run_component(payload, move |connector: Connector<C>| {
+            connector.connect_receiver(move |_, output| {
+                output_exit_code.set(Some(output.into()));
+                app.quit();
+            })
+        });
+
+        exit_code.take().unwrap_or(app_exit_code)
+    }
+
+    /// Runs the application, returns once the application is closed.
+    ///
+    /// The returned [`glib::ExitCode`] is the one set by [`relm4::set_exit_code()`]
+    /// or, if no exit code was set, the exit code returned by [`gtk::Application`].
+    /// Use [`run_async_with_exit_code()`](Self::run_async_with_exit_code) to let the
+    /// root component decide the exit code with its output instead.
+    ///
+    /// [`relm4::set_exit_code()`]: crate::set_exit_code
+    pub fn run_async<C>(self, payload: C::Init) -> glib::ExitCode
+    where
+        C: AsyncComponent<Input = M>,
+        C::Root: AsRef<gtk::Window>,
+    {
+        self.run_async_component(payload, AsyncConnector::<C>::detach)
+    }
+
+    /// Runs the application, returns once the application is closed.
+    ///
+    /// This is the async equivalent of [`run_with_exit_code()`](Self::run_with_exit_code).
+    pub fn run_async_with_exit_code<C>(self, payload: C::Init) -> glib::ExitCode
+    where
+        C: AsyncComponent<Input = M>,
+        C::Root: AsRef<gtk::Window>,
+        C::Output: Into<glib::ExitCode>,
+    {
+        let app = self.app.clone();
+        let exit_code = Rc::new(Cell::new(None));
+        let output_exit_code = exit_code.clone();
+
+        let app_exit_code =
+            self.run_async_component(payload, move |connector: AsyncConnector<C>| {
+                connector.connect_receiver(move |_, output| {
+                    output_exit_code.set(Some(output.into()));
+                    app.quit();
+                })
+            });
+
+        exit_code.take().unwrap_or(app_exit_code)
+    }
+
+    fn run_component<C, F>(self, payload: C::Init, connect: F) -> glib::ExitCode
     where
         C: Component<Input = M>,
         C::Root: AsRef<gtk::Window>,
+        F: FnOnce(Connector<C>) -> Controller<C> + 'static,
     {
         let Self {
             app,
@@ -157,10 +247,10 @@ impl<M: Debug + 'static> RelmApp<M> {
             visible,
         } = self;
 
-        let payload = Cell::new(Some(payload));
+        let payload = Cell::new(Some((payload, connect)));
 
         app.connect_startup(move |app| {
-            if let Some(payload) = payload.take() {
+            if let Some((payload, connect)) = payload.take() {
                 let builder = ComponentBuilder::<C>::default();
 
                 let connector = match broker {
@@ -171,7 +261,7 @@ impl<M: Debug + 'static> RelmApp<M> {
                 // Run late initialization for transient windows for example.
                 crate::late_initialization::run_late_init();
 
-                let mut controller = connector.detach();
+                let mut controller = connect(connector);
                 let window = controller.widget();
                 app.add_window(window.as_ref());
 
@@ -179,43 +269,26 @@ impl<M: Debug + 'static> RelmApp<M> {
             }
         });
 
-        app.connect_activate(move |app| {
-            if let Some(window) = app.active_window()
-                && visible
-            {
-                window.set_visible(true);
-            }
-        });
-
-        let _guard = RUNTIME.enter();
-        if let Some(args) = args {
-            app.run_with_args(&args);
-        } else {
-            app.run();
-        }
-
-        // Make sure everything is shut down
-        shutdown_all();
-        glib::MainContext::ref_thread_default().iteration(true);
+        Self::run_app(&app, args, visible)
     }
 
-    /// Runs the application, returns once the application is closed.
-    pub fn run_async<C>(self, payload: C::Init)
+    fn run_async_component<C, F>(self, payload: C::Init, connect: F) -> glib::ExitCode
     where
         C: AsyncComponent<Input = M>,
         C::Root: AsRef<gtk::Window>,
+        F: FnOnce(AsyncConnector<C>) -> AsyncController<C> + 'static,
     {
         let Self {
             app,
             broker,
             args,
-            visible: set_visible,
+            visible,
         } = self;
 
-        let payload = Cell::new(Some(payload));
+        let payload = Cell::new(Some((payload, connect)));
 
         app.connect_startup(move |app| {
-            if let Some(payload) = payload.take() {
+            if let Some((payload, connect)) = payload.take() {
                 let builder = AsyncComponentBuilder::<C>::default();
 
                 let connector = match broker {
@@ -226,7 +299,7 @@ impl<M: Debug + 'static> RelmApp<M> {
                 // Run late initialization for transient windows for example.
                 crate::late_initialization::run_late_init();
 
-                let mut controller = connector.detach();
+                let mut controller = connect(connector);
                 let window = controller.widget();
                 app.add_window(window.as_ref());
 
@@ -234,23 +307,31 @@ impl<M: Debug + 'static> RelmApp<M> {
             }
         });
 
+        Self::run_app(&app, args, visible)
+    }
+
+    fn run_app(app: &gtk::Application, args: Option<Vec<String>>, visible: bool) -> glib::ExitCode {
         app.connect_activate(move |app| {
             if let Some(window) = app.active_window()
-                && set_visible
+                && visible
             {
                 window.set_visible(true);
             }
         });
 
         let _guard = RUNTIME.enter();
-        if let Some(args) = args {
-            app.run_with_args(&args);
+        let exit_code = if let Some(args) = args {
+            app.run_with_args(&args)
         } else {
-            app.run();
-        }
+            app.run()
+        };
 
         // Make sure everything is shut down
         shutdown_all();
+        #[cfg(feature = "serde")]
+        crate::shared_state::flush_persistent_states();
         glib::MainContext::ref_thread_default().iteration(true);
+
+        crate::take_exit_code().unwrap_or(exit_code)
     }
 }
@@ -3,14 +3,100 @@
 
 //! Contains various flavors of channels to send messages between components and workers.
 
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::future::Future;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use crate::component::AsyncComponent;
 use crate::factory::{AsyncFactoryComponent, FactoryComponent};
 use crate::{Component, Sender, ShutdownReceiver};
 
+type ForwardFn<Output> = Arc<dyn Fn(Output) -> Result<(), Output> + Send + Sync>;
+type SenderFn<Output> = Box<dyn Fn() -> Sender<Output> + Send + Sync>;
+
+/// Receives the outputs of a component.
+pub(crate) enum OutputTarget<Output> {
+    /// The outputs are sent through a channel.
+    Sender(Sender<Output>),
+    /// The outputs are passed to a function, e.g. to attach the index of a factory element.
+    ///
+    /// The channel returned by `output_sender()` is only created if it's actually used.
+    Forward {
+        forward: ForwardFn<Output>,
+        make_sender: SenderFn<Output>,
+        sender: OnceLock<Sender<Output>>,
+    },
+}
+
+impl<Output> OutputTarget<Output>
+where
+    Output: Send + 'static,
+{
+    /// Passes all outputs to `forward`, which returns the output
+    /// if it can't be delivered.
+    pub(crate) fn forward<F>(forward: F) -> Self
+    where
+        F: Fn(Output) -> Result<(), Output> + Send + Sync + 'static,
+    {
+        let forward: ForwardFn<Output> = Arc::new(forward);
+
+        Self::Forward {
+            forward: forward.clone(),
+            make_sender: Box::new(move || {
+                let (sender, receiver) = crate::channel();
+                let forward = forward.clone();
+                crate::spawn(async move {
+                    while let Some(output) = receiver.recv().await {
+                        if forward(output).is_err() {
+                            break;
+                        }
+                    }
+                });
+                sender
+            }),
+            sender: OnceLock::new(),
+        }
+    }
+}
+
+impl<Output> OutputTarget<Output> {
+    fn sender(&self) -> &Sender<Output> {
+        match self {
+            Self::Sender(sender) => sender,
+            Self::Forward {
+                make_sender,
+                sender,
+                ..
+            } => sender.get_or_init(make_sender),
+        }
+    }
+
+    fn send(&self, message: Output) -> Result<(), Output> {
+        match self {
+            Self::Sender(sender) => sender.send(message),
+            Self::Forward { forward, .. } => forward(message),
+        }
+    }
+}
+
+impl<Output> From<Sender<Output>> for OutputTarget<Output> {
+    fn from(sender: Sender<Output>) -> Self {
+        Self::Sender(sender)
+    }
+}
+
+impl<Output> Debug for OutputTarget<Output> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sender(sender) => f.debug_tuple("Sender").field(sender).finish(),
+            Self::Forward { sender, .. } => f
+                .debug_struct("Forward")
+                .field("sender", sender)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
 // Contains senders used by components and factories internally.
 #[derive(Debug)]
 struct ComponentSenderInner<Input, Output, CommandOutput>
@@ -21,7 +107,7 @@ where
     /// Emits component inputs.
     input: Sender<Input>,
     /// Emits component outputs.
-    output: Sender<Output>,
+    output: OutputTarget<Output>,
     /// Emits command outputs.
     command: Sender<CommandOutput>,
     shutdown: ShutdownReceiver,
@@ -47,7 +133,7 @@ where
     /// [`output()`][Self::output] is more concise.
     #[must_use]
     fn output_sender(&self) -> &Sender<Output> {
-        &self.output
+        self.output.sender()
     }
 
     /// Retrieve the sender for command output messages.
@@ -140,14 +226,14 @@ macro_rules! sender_impl {
         impl<C: $trait> $name<C> {
             pub(crate) fn new(
                 input: Sender<C::Input>,
-                output: Sender<C::Output>,
+                output: impl Into<OutputTarget<C::Output>>,
                 command: Sender<C::CommandOutput>,
                 shutdown: ShutdownReceiver,
             ) -> Self {
                 Self {
                     shared: Arc::new(ComponentSenderInner {
                         input,
-                        output,
+                        output: output.into(),
                         command,
                         shutdown,
                     }),
@@ -181,6 +267,14 @@ macro_rules! sender_impl {
                 self.shared.command_sender()
             }
 
+            /// Retrieve a receiver for the shutdown signal of the component.
+            ///
+            /// Useful to bind tasks or subscriptions to the lifetime of the component.
+            #[must_use]
+            pub fn shutdown_receiver(&self) -> ShutdownReceiver {
+                self.shared.shutdown.clone()
+            }
+
             /// Emit an input to the component.
             pub fn input(&self, message: C::Input) {
                 self.shared.input(message);
@@ -2,6 +2,7 @@ mod component;
 /// Cancellation mechanism used by Relm4.
 pub mod shutdown;
 
+pub(crate) use component::OutputTarget;
 pub use component::{AsyncComponentSender, AsyncFactorySender, ComponentSender, FactorySender};
 
 // Copyright 2022 System76 <info@system76.com>
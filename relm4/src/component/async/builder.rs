@@ -4,7 +4,9 @@
 
 use super::super::MessageBroker;
 use super::{AsyncComponent, AsyncComponentParts, AsyncConnector};
+use crate::abstractions::{WindowStatePersistence, WindowStateStorage};
 use crate::channel::AsyncComponentSender;
+use crate::context::Context;
 use crate::{
     GuardedReceiver, Receiver, RelmContainerExt, RelmWidgetExt, RuntimeSenders, Sender,
     late_initialization,
@@ -21,6 +23,8 @@ pub struct AsyncComponentBuilder<C: AsyncComponent> {
     /// The root widget of the component.
     pub root: C::Root,
     priority: glib::Priority,
+    window_state: Option<WindowStatePersistence>,
+    context: Context,
 
     pub(super) component: PhantomData<C>,
 }
@@ -31,6 +35,8 @@ impl<C: AsyncComponent> Default for AsyncComponentBuilder<C> {
         Self {
             root: C::init_root(),
             priority: glib::Priority::default(),
+            window_state: None,
+            context: Context::new_child(),
             component: PhantomData,
         }
     }
@@ -60,6 +66,15 @@ impl<C: AsyncComponent> AsyncComponentBuilder<C> {
         self.priority = priority;
         self
     }
+
+    /// Provide a value to this component and all components launched inside of it.
+    ///
+    /// The value can be retrieved with [`context::lookup()`](crate::context::lookup).
+    #[must_use]
+    pub fn provide_context<T: 'static>(self, value: T) -> Self {
+        self.context.provide(value);
+        self
+    }
 }
 
 impl<C: AsyncComponent> AsyncComponentBuilder<C>
@@ -101,6 +116,19 @@ where
 
         self
     }
+
+    /// Restore the default size, the maximized and the fullscreen state
+    /// of the component's root window from the given storage
+    /// and save all future changes to it.
+    ///
+    /// Pending changes are saved when the component is shut down.
+    /// Use [`WindowStatePersistence`] directly if you need to
+    /// [`flush`](WindowStatePersistence::flush) changes manually.
+    #[must_use]
+    pub fn persist_window_state(mut self, storage: WindowStateStorage) -> Self {
+        self.window_state = Some(WindowStatePersistence::new(&self.root, storage));
+        self
+    }
 }
 
 impl<C: AsyncComponent> AsyncComponentBuilder<C>
@@ -165,7 +193,13 @@ impl<C: AsyncComponent> AsyncComponentBuilder<C> {
         input_sender: Sender<C::Input>,
         input_receiver: Receiver<C::Input>,
     ) -> AsyncConnector<C> {
-        let Self { root, priority, .. } = self;
+        let Self {
+            root,
+            priority,
+            window_state,
+            context,
+            ..
+        } = self;
         let temp_widgets = C::init_loading_widgets(root.clone());
 
         let RuntimeSenders {
@@ -192,7 +226,7 @@ impl<C: AsyncComponent> AsyncComponentBuilder<C> {
         // Spawns the component's service. It will receive both `Self::Input` and
         // `Self::CommandOutput` messages. It will spawn commands as requested by
         // updates, and send `Self::Output` messages externally.
-        crate::spawn_local_with_priority(priority, async move {
+        crate::spawn_local_with_priority(priority, context.scope(async move {
             let mut state = C::init(payload, rt_root.clone(), component_sender.clone()).await;
             drop(temp_widgets);
 
@@ -247,13 +281,16 @@ impl<C: AsyncComponent> AsyncComponentBuilder<C> {
 
                         model.shutdown(widgets, output_sender);
 
+                        // Save pending changes and stop tracking the window.
+                        drop(window_state);
+
                         shutdown_notifier.shutdown();
 
                         return;
                     }
                 );
             }
-        });
+        }));
 
         // Give back a type for controlling the component service.
         AsyncConnector {
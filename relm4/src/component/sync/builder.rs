@@ -4,6 +4,8 @@
 
 use super::super::MessageBroker;
 use super::{Component, ComponentParts, Connector, StateWatcher};
+use crate::abstractions::{WindowStatePersistence, WindowStateStorage};
+use crate::context::Context;
 use crate::{
     ComponentSender, GuardedReceiver, Receiver, RelmContainerExt, RelmWidgetExt, RuntimeSenders,
     Sender, late_initialization,
@@ -22,6 +24,8 @@ pub struct ComponentBuilder<C: Component> {
     /// The root widget of the component.
     pub root: C::Root,
     priority: glib::Priority,
+    window_state: Option<WindowStatePersistence>,
+    pub(crate) context: Context,
 
     pub(super) component: PhantomData<C>,
 }
@@ -32,6 +36,8 @@ impl<C: Component> Default for ComponentBuilder<C> {
         Self {
             root: C::init_root(),
             priority: glib::Priority::default(),
+            window_state: None,
+            context: Context::new_child(),
             component: PhantomData,
         }
     }
@@ -61,6 +67,15 @@ impl<C: Component> ComponentBuilder<C> {
         self.priority = priority;
         self
     }
+
+    /// Provide a value to this component and all components launched inside of it.
+    ///
+    /// The value can be retrieved with [`context::lookup()`](crate::context::lookup).
+    #[must_use]
+    pub fn provide_context<T: 'static>(self, value: T) -> Self {
+        self.context.provide(value);
+        self
+    }
 }
 
 impl<C: Component> ComponentBuilder<C>
@@ -102,6 +117,19 @@ where
 
         self
     }
+
+    /// Restore the default size, the maximized and the fullscreen state
+    /// of the component's root window from the given storage
+    /// and save all future changes to it.
+    ///
+    /// Pending changes are saved when the component is shut down.
+    /// Use [`WindowStatePersistence`] directly if you need to
+    /// [`flush`](WindowStatePersistence::flush) changes manually.
+    #[must_use]
+    pub fn persist_window_state(mut self, storage: WindowStateStorage) -> Self {
+        self.window_state = Some(WindowStatePersistence::new(&self.root, storage));
+        self
+    }
 }
 
 impl<C: Component> ComponentBuilder<C>
@@ -166,7 +194,13 @@ impl<C: Component> ComponentBuilder<C> {
         input_sender: Sender<C::Input>,
         input_receiver: Receiver<C::Input>,
     ) -> Connector<C> {
-        let Self { root, priority, .. } = self;
+        let Self {
+            root,
+            priority,
+            window_state,
+            context,
+            ..
+        } = self;
 
         let RuntimeSenders {
             output_sender,
@@ -191,11 +225,10 @@ impl<C: Component> ComponentBuilder<C> {
         );
 
         // Constructs the initial model and view with the initial payload.
-        let state = Rc::new(RefCell::new(C::init(
-            payload,
-            root.clone(),
-            component_sender.clone(),
-        )));
+        let state =
+            Rc::new(RefCell::new(context.enter(|| {
+                C::init(payload, root.clone(), component_sender.clone())
+            })));
         let watcher = StateWatcher {
             state,
             notifier,
@@ -208,7 +241,7 @@ impl<C: Component> ComponentBuilder<C> {
         // Spawns the component's service. It will receive both `Self::Input` and
         // `Self::CommandOutput` messages. It will spawn commands as requested by
         // updates, and send `Self::Output` messages externally.
-        crate::spawn_local_with_priority(priority, async move {
+        crate::spawn_local_with_priority(priority, context.scope(async move {
             let mut notifier = GuardedReceiver::new(notifier_receiver);
             let mut cmd = GuardedReceiver::new(cmd_receiver);
             let mut input = GuardedReceiver::new(input_receiver);
@@ -270,13 +303,16 @@ impl<C: Component> ComponentBuilder<C> {
 
                         model.shutdown(widgets, output_sender);
 
+                        // Save pending changes and stop tracking the window.
+                        drop(window_state);
+
                         shutdown_notifier.shutdown();
 
                         return;
                     }
                 );
             }
-        });
+        }));
 
         // Give back a type for controlling the component service.
         Connector {
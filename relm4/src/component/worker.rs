@@ -74,7 +74,7 @@ where
     /// Starts a worker on a separate thread,
     /// passing ownership to a future attached to a [gtk::glib::MainContext].
     pub fn detach_worker(self, payload: C::Init) -> WorkerHandle<C> {
-        let Self { root, .. } = self;
+        let Self { root, context, .. } = self;
 
         // Used for all events to be processed by this component's internal service.
         let (input_sender, input_receiver) = crate::channel::<C::Input>();
@@ -98,7 +98,8 @@ where
             shutdown_recipient,
         );
 
-        let mut state = C::init(payload, root, component_sender.clone());
+        // The worker runs on a different thread, so the context is only available in `init`.
+        let mut state = context.enter(|| C::init(payload, root, component_sender.clone()));
 
         thread::spawn(move || {
             let context = glib::MainContext::thread_default().unwrap_or_default();
@@ -0,0 +1,184 @@
+//! Scoped state that is shared with a subtree of components.
+//!
+//! Types like [`SharedState`](crate::SharedState) or [`Reducer`](crate::Reducer)
+//! are usually stored in `static` variables, which makes them global.
+//! Contexts allow a component to provide a value, for example an [`Rc`]
+//! containing a [`SharedState`](crate::SharedState), that is only visible
+//! to the component itself and all components launched inside of it.
+//!
+//! Each component and factory component has its own scope
+//! that inherits all values of the scope it was launched in.
+//! Values can be looked up by type in the `init` and `update` methods
+//! of components and factory components.
+//!
+//! # Example
+//!
+//! ```
+//! use std::rc::Rc;
+//! use relm4::SharedState;
+//!
+//! #[derive(Default)]
+//! struct Document {
+//!     text: String,
+//! }
+//!
+//! // Inside the `init` method of the parent component.
+//! relm4::context::provide(Rc::new(SharedState::<Document>::new()));
+//!
+//! // Inside any component launched by the parent component.
+//! let document: Rc<SharedState<Document>> = relm4::context::lookup().unwrap();
+//! document.write().text.push_str("Hello");
+//! ```
+//!
+//! Values can also be provided for a single component and its children
+//! with [`ComponentBuilder::provide_context()`](crate::component::ComponentBuilder::provide_context).
+
+use std::any::{Any, TypeId, type_name};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::rc::Rc;
+
+thread_local! {
+    /// The scope of the component that is currently initialized or updated.
+    static CURRENT: RefCell<Option<Context>> = const { RefCell::new(None) };
+    /// The scope used outside of components.
+    static ROOT: Context = Context::default();
+}
+
+/// Provided values stored together with their type name for debugging.
+type Values = HashMap<TypeId, (&'static str, Box<dyn Any>)>;
+
+#[derive(Default)]
+struct Scope {
+    parent: Option<Context>,
+    values: RefCell<Values>,
+}
+
+/// A scope that holds the provided values of a component.
+#[derive(Clone, Default)]
+pub(crate) struct Context(Rc<Scope>);
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = self.0.values.borrow();
+        f.debug_struct("Context")
+            .field(
+                "values",
+                &values.values().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .field("parent", &self.0.parent)
+            .finish()
+    }
+}
+
+impl Context {
+    /// Returns the scope of the component that is currently initialized or updated.
+    pub(crate) fn current() -> Self {
+        CURRENT
+            .with(|current| current.borrow().clone())
+            .unwrap_or_else(|| ROOT.with(Clone::clone))
+    }
+
+    /// Create a new scope that inherits all values from the current scope.
+    pub(crate) fn new_child() -> Self {
+        Self(Rc::new(Scope {
+            parent: Some(Self::current()),
+            values: RefCell::default(),
+        }))
+    }
+
+    /// Provide a value for this scope and all its children.
+    pub(crate) fn provide<T: 'static>(&self, value: T) {
+        self.0
+            .values
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), (type_name::<T>(), Box::new(value)));
+    }
+
+    fn lookup<T: Clone + 'static>(&self) -> Option<T> {
+        let mut scope = Some(self);
+        while let Some(context) = scope {
+            if let Some((_, value)) = context.0.values.borrow().get(&TypeId::of::<T>()) {
+                return value.downcast_ref::<T>().cloned();
+            }
+            scope = context.0.parent.as_ref();
+        }
+        None
+    }
+
+    /// Run a function while this is the current scope.
+    pub(crate) fn enter<R>(&self, func: impl FnOnce() -> R) -> R {
+        struct Reset(Option<Context>);
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT.with(|current| *current.borrow_mut() = previous);
+            }
+        }
+
+        let previous = CURRENT.with(|current| current.borrow_mut().replace(self.clone()));
+        let _reset = Reset(previous);
+        func()
+    }
+
+    /// Wrap a future so that this is the current scope whenever it is polled.
+    pub(crate) fn scope<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+        let mut future = Box::pin(future);
+        std::future::poll_fn(move |cx| self.enter(|| future.as_mut().poll(cx)))
+    }
+}
+
+/// Provide a value to the current component and all components launched inside of it.
+///
+/// Outside of components, the value is provided to all components of the current thread.
+/// Providing a value of the same type again replaces the previous value.
+pub fn provide<T: 'static>(value: T) {
+    Context::current().provide(value);
+}
+
+/// Look up a value by its type.
+///
+/// The scope of the current component is searched first, then
+/// the scopes of the components it was launched in.
+/// Returns [`None`] if no value of this type was provided.
+#[must_use]
+pub fn lookup<T: Clone + 'static>() -> Option<T> {
+    Context::current().lookup()
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::{Context, lookup, provide};
+
+    #[test]
+    fn nested_scopes() {
+        let parent = Context::new_child();
+        parent.provide(Rc::new(1_u8));
+        parent.provide("parent");
+
+        let child = parent.enter(Context::new_child);
+        child.provide("child");
+
+        child.enter(|| {
+            assert_eq!(lookup::<Rc<u8>>().as_deref(), Some(&1));
+            assert_eq!(lookup::<&str>(), Some("child"));
+            assert_eq!(lookup::<u16>(), None);
+
+            // Values provided later are visible as well.
+            parent.provide(2_u16);
+            assert_eq!(lookup::<u16>(), Some(2));
+        });
+
+        parent.enter(|| {
+            provide(3_u32);
+            assert_eq!(lookup::<&str>(), Some("parent"));
+        });
+        assert_eq!(lookup::<u32>(), None);
+        assert_eq!(child.enter(lookup::<u32>), Some(3));
+    }
+}
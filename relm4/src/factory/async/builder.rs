@@ -6,6 +6,7 @@ use super::future_data::AsyncData;
 use super::{AsyncFactoryComponent, AsyncFactoryHandle};
 
 use crate::channel::AsyncFactorySender;
+use crate::context::Context;
 use crate::factory::{DataGuard, DynamicIndex, FactoryView};
 use crate::runtime_util::GuardedReceiver;
 use crate::shutdown::ShutdownSender;
@@ -18,6 +19,7 @@ pub(super) struct AsyncFactoryBuilder<C: AsyncFactoryComponent> {
     input_receiver: Receiver<C::Input>,
     cmd_receiver: Receiver<C::CommandOutput>,
     shutdown_notifier: ShutdownSender,
+    context: Context,
 }
 
 impl<C: AsyncFactoryComponent> AsyncFactoryBuilder<C>
@@ -40,6 +42,9 @@ where
 
         let root_widget = C::init_root();
 
+        // Elements inherit the context of the component that created them.
+        let context = Context::new_child();
+
         Self {
             init,
             root_widget,
@@ -47,6 +52,7 @@ where
             input_receiver,
             cmd_receiver,
             shutdown_notifier,
+            context,
         }
     }
 
@@ -63,6 +69,7 @@ where
             cmd_receiver,
             shutdown_notifier,
             init,
+            context,
         } = self;
 
         // Gets notifications when a component's model and view is updated externally.
@@ -72,7 +79,7 @@ where
 
         let loading_widgets = C::init_loading_widgets(root_widget.clone());
 
-        let future_receiver = {
+        let (future_receiver, abort_handle) = {
             let index = index.clone();
             let (future_sender, future_receiver) = crate::channel();
 
@@ -85,18 +92,24 @@ where
                 input_receiver,
                 cmd_receiver,
                 notifier_receiver,
+                context: context.clone(),
             };
 
-            crate::spawn_local(async move {
+            // The initialization is aborted if the element is removed before it finished.
+            let (future, abort_handle) = futures::future::abortable(async move {
                 let data = C::init_model(init, &index, component_sender).await;
                 drop(loading_widgets);
                 let data_guard = future_data.start_runtime(data);
                 let _ = future_sender.send(data_guard);
             });
-            future_receiver
+
+            crate::spawn_local(context.scope(async move {
+                let _ = future.await;
+            }));
+            (future_receiver, abort_handle)
         };
 
-        let data = AsyncData::new(future_receiver);
+        let data = AsyncData::new(future_receiver, abort_handle);
 
         // Give back a type for controlling the component service.
         AsyncFactoryHandle {
@@ -131,6 +144,7 @@ struct FutureData<C: AsyncFactoryComponent> {
     input_receiver: Receiver<C::Input>,
     cmd_receiver: Receiver<C::CommandOutput>,
     notifier_receiver: Receiver<()>,
+    context: Context,
 }
 
 impl<C: AsyncFactoryComponent> FutureData<C> {
@@ -144,13 +158,17 @@ impl<C: AsyncFactoryComponent> FutureData<C> {
             cmd_receiver,
             input_receiver,
             notifier_receiver,
+            context,
         } = self;
 
         let mut data = Box::new(data);
         let widgets =
             Box::new(data.init_widgets(&index, root, &returned_widget, component_sender.clone()));
 
-        let output_sender = component_sender.output_sender().clone();
+        let output_sender = {
+            let component_sender = component_sender.clone();
+            move || component_sender.output_sender().clone()
+        };
 
         // Spawns the component's service. It will receive both `Self::Input` and
         // `Self::CommandOutput` messages. It will spawn commands as requested by
@@ -160,45 +178,47 @@ impl<C: AsyncFactoryComponent> FutureData<C> {
             widgets,
             shutdown_notifier,
             output_sender,
-            |mut model, mut widgets| async move {
-                let mut notifier = GuardedReceiver::new(notifier_receiver);
-                let mut cmd = GuardedReceiver::new(cmd_receiver);
-                let mut input = GuardedReceiver::new(input_receiver);
-                loop {
-                    futures::select!(
-                        // Performs the model update, checking if the update requested a command.
-                        // Runs that command asynchronously in the background using tokio.
-                        message = input => {
-                            let span = info_span!(
-                                "update_with_view",
-                                input=?message,
-                                component=any::type_name::<C>(),
-                                id=model.id(),
-                            );
-                            let _enter = span.enter();
-
-                            model.update_with_view(&mut widgets, message, component_sender.clone()).await;
-                        }
-
-                        // Handles responses from a command.
-                        message = cmd => {
-                            let span = info_span!(
-                                "update_cmd_with_view",
-                                cmd_output=?message,
-                                component=any::type_name::<C>(),
-                                id=model.id(),
-                            );
-                            let _enter = span.enter();
-
-                            model.update_cmd_with_view(&mut widgets, message, component_sender.clone()).await;
-                        }
-
-                        // Triggered when the model and view have been updated externally.
-                        _ = notifier => {
-                            model.update_view(&mut widgets, component_sender.clone());
-                        }
-                    );
-                }
+            |mut model, mut widgets| {
+                context.scope(async move {
+                    let mut notifier = GuardedReceiver::new(notifier_receiver);
+                    let mut cmd = GuardedReceiver::new(cmd_receiver);
+                    let mut input = GuardedReceiver::new(input_receiver);
+                    loop {
+                        futures::select!(
+                            // Performs the model update, checking if the update requested a command.
+                            // Runs that command asynchronously in the background using tokio.
+                            message = input => {
+                                let span = info_span!(
+                                    "update_with_view",
+                                    input=?message,
+                                    component=any::type_name::<C>(),
+                                    id=model.id(),
+                                );
+                                let _enter = span.enter();
+
+                                model.update_with_view(&mut widgets, message, component_sender.clone()).await;
+                            }
+
+                            // Handles responses from a command.
+                            message = cmd => {
+                                let span = info_span!(
+                                    "update_cmd_with_view",
+                                    cmd_output=?message,
+                                    component=any::type_name::<C>(),
+                                    id=model.id(),
+                                );
+                                let _enter = span.enter();
+
+                                model.update_cmd_with_view(&mut widgets, message, component_sender.clone()).await;
+                            }
+
+                            // Triggered when the model and view have been updated externally.
+                            _ = notifier => {
+                                model.update_view(&mut widgets, component_sender.clone());
+                            }
+                        );
+                    }
+                })
             },
             C::shutdown,
         )
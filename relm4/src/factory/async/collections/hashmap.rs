@@ -0,0 +1,457 @@
+use crate::{Receiver, Sender};
+
+use crate::factory::r#async::AsyncFactoryBuilder;
+use crate::factory::r#async::AsyncFactoryHandle;
+use crate::factory::r#async::traits::AsyncFactoryComponent;
+use crate::factory::{DynamicIndex, FactoryView};
+
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops;
+
+use gtk::prelude::IsA;
+
+/// Provides mutable access to the model of one element of an [`AsyncFactoryHashMap`].
+///
+/// The view of the element is updated after the guard goes out of scope.
+#[derive(Debug)]
+#[must_use]
+pub struct AsyncFactoryElementGuard<'a, C>
+where
+    C: AsyncFactoryComponent,
+{
+    inner: &'a mut AsyncFactoryHandle<C>,
+}
+
+impl<C> ops::Deref for AsyncFactoryElementGuard<'_, C>
+where
+    C: AsyncFactoryComponent,
+{
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        self.inner.data.get().unwrap()
+    }
+}
+
+impl<C> ops::DerefMut for AsyncFactoryElementGuard<'_, C>
+where
+    C: AsyncFactoryComponent,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner.data.get_mut().unwrap()
+    }
+}
+
+impl<C> Drop for AsyncFactoryElementGuard<'_, C>
+where
+    C: AsyncFactoryComponent,
+{
+    fn drop(&mut self) {
+        self.inner.notifier.send(()).unwrap()
+    }
+}
+
+/// An element of an [`AsyncFactoryHashMap`] together with the
+/// position of its widget.
+#[derive(Debug)]
+struct Element<C: AsyncFactoryComponent> {
+    index: DynamicIndex,
+    handle: AsyncFactoryHandle<C>,
+}
+
+#[derive(Debug)]
+/// A builder-pattern struct for building an [`AsyncFactoryHashMap`].
+pub struct AsyncFactoryHashMapBuilder<K, C: AsyncFactoryComponent, S = RandomState> {
+    hasher: S,
+    component: PhantomData<C>,
+    key: PhantomData<K>,
+}
+
+impl<K, C> Default for AsyncFactoryHashMapBuilder<K, C>
+where
+    C: AsyncFactoryComponent,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, C, S> AsyncFactoryHashMapBuilder<K, C, S>
+where
+    C: AsyncFactoryComponent,
+    C::ParentWidget: Default,
+{
+    /// Launch the factory with a default parent widget.
+    #[must_use]
+    pub fn launch_default(self) -> AsyncFactoryHashMapConnector<K, C, S> {
+        self.launch(Default::default())
+    }
+}
+
+impl<K, C> AsyncFactoryHashMapBuilder<K, C>
+where
+    C: AsyncFactoryComponent,
+{
+    /// Creates a new [`AsyncFactoryHashMapBuilder`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            hasher: RandomState::default(),
+            component: PhantomData,
+            key: PhantomData,
+        }
+    }
+}
+
+impl<K, C, S> AsyncFactoryHashMapBuilder<K, C, S>
+where
+    C: AsyncFactoryComponent,
+{
+    /// Sets a different hasher.
+    pub fn hasher<H: BuildHasher>(self, hasher: H) -> AsyncFactoryHashMapBuilder<K, C, H> {
+        let Self { component, key, .. } = self;
+
+        AsyncFactoryHashMapBuilder {
+            hasher,
+            component,
+            key,
+        }
+    }
+
+    /// Launch the factory.
+    /// This is similar to [`Connector::launch`](crate::component::ComponentBuilder::launch).
+    pub fn launch(self, widget: C::ParentWidget) -> AsyncFactoryHashMapConnector<K, C, S> {
+        let Self { hasher, key, .. } = self;
+
+        let (output_sender, output_receiver) = crate::channel();
+
+        AsyncFactoryHashMapConnector {
+            widget,
+            output_sender,
+            output_receiver,
+            hasher,
+            _key: key,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Second stage of the builder-pattern for building an [`AsyncFactoryHashMap`].
+pub struct AsyncFactoryHashMapConnector<K, C, S = RandomState>
+where
+    C: AsyncFactoryComponent,
+{
+    widget: C::ParentWidget,
+    output_sender: Sender<C::Output>,
+    output_receiver: Receiver<C::Output>,
+    hasher: S,
+    _key: PhantomData<K>,
+}
+
+impl<K, C, S> AsyncFactoryHashMapConnector<K, C, S>
+where
+    C: AsyncFactoryComponent,
+{
+    /// Forwards output events from child components to the designated sender.
+    pub fn forward<F, Msg>(self, sender_: &Sender<Msg>, f: F) -> AsyncFactoryHashMap<K, C, S>
+    where
+        F: Fn(C::Output) -> Msg + Send + 'static,
+        C::Output: Send,
+        Msg: Send + 'static,
+    {
+        let Self {
+            widget,
+            output_sender,
+            output_receiver,
+            hasher,
+            ..
+        } = self;
+
+        let sender_clone = sender_.clone();
+
+        crate::spawn(async move {
+            while let Some(msg) = output_receiver.recv().await {
+                if sender_clone.send(f(msg)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        AsyncFactoryHashMap {
+            widget,
+            output_sender,
+            inner: HashMap::with_hasher(hasher),
+        }
+    }
+
+    /// Ignore output events from child components and just create the [`AsyncFactoryHashMap`].
+    pub fn detach(self) -> AsyncFactoryHashMap<K, C, S> {
+        let Self {
+            widget,
+            output_sender,
+            hasher,
+            ..
+        } = self;
+
+        AsyncFactoryHashMap {
+            widget,
+            output_sender,
+            inner: HashMap::with_hasher(hasher),
+        }
+    }
+}
+
+/// A container similar to [`HashMap`] that can be used to store
+/// values of type [`AsyncFactoryComponent`].
+///
+/// New elements are appended to the parent widget.
+/// The [`DynamicIndex`] passed to the elements is the position of their widget.
+#[derive(Debug)]
+pub struct AsyncFactoryHashMap<K, C: AsyncFactoryComponent, S = RandomState> {
+    widget: C::ParentWidget,
+    output_sender: Sender<C::Output>,
+    inner: HashMap<K, Element<C>, S>,
+}
+
+impl<K, C, S> Drop for AsyncFactoryHashMap<K, C, S>
+where
+    C: AsyncFactoryComponent,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K, C> AsyncFactoryHashMap<K, C, RandomState>
+where
+    C: AsyncFactoryComponent,
+{
+    /// Creates a new [`AsyncFactoryHashMapBuilder`].
+    #[must_use]
+    pub fn builder() -> AsyncFactoryHashMapBuilder<K, C> {
+        AsyncFactoryHashMapBuilder::new()
+    }
+}
+
+impl<K, C, S> AsyncFactoryHashMap<K, C, S>
+where
+    C: AsyncFactoryComponent,
+{
+    /// Returns the number of elements in the [`AsyncFactoryHashMap`].
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the [`AsyncFactoryHashMap`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Send clone of a message to all of the elements.
+    ///
+    /// Elements whose async [`init_model()`] method hasn't returned yet
+    /// receive the message after the initialization.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn broadcast(&self, msg: C::Input)
+    where
+        C::Input: Clone,
+    {
+        self.inner
+            .values()
+            .for_each(|e| e.handle.input.send(msg.clone()).unwrap());
+    }
+
+    /// Returns the widget all components are attached to.
+    pub const fn widget(&self) -> &C::ParentWidget {
+        &self.widget
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    ///
+    /// Each value will be [`Some`] if the async [`init_model()`] method
+    /// of the element returned and otherwise [`None`].
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&K, Option<&C>)> + FusedIterator {
+        self.inner.iter().map(|(k, e)| (k, e.handle.data.get()))
+    }
+
+    /// Returns an iterator over the factory components.
+    ///
+    /// Each item will be [`Some`] if the async [`init_model()`] method
+    /// of the element returned and otherwise [`None`].
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn values(&self) -> impl ExactSizeIterator<Item = Option<&C>> + FusedIterator {
+        self.inner.values().map(|e| e.handle.data.get())
+    }
+
+    /// Returns an iterator over the keys of the hash map.
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = &K> + FusedIterator {
+        self.inner.keys()
+    }
+
+    /// Clears the map, removing all factory components.
+    ///
+    /// The async [`init_model()`] methods of elements that are
+    /// still initializing are cancelled.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn clear(&mut self) {
+        for (_, element) in self.inner.drain() {
+            self.widget.factory_remove(&element.handle.returned_widget);
+
+            // Make sure the component is shutdown properly
+            element.handle.data.into_inner();
+        }
+    }
+}
+
+impl<K, C> AsyncFactoryHashMap<K, C, RandomState>
+where
+    C: AsyncFactoryComponent,
+    <C::ParentWidget as FactoryView>::ReturnedWidget: Clone,
+    K: Hash + Eq,
+{
+    /// Creates an [`AsyncFactoryHashMap`] from a [`Vec`].
+    pub fn from_vec(component_vec: Vec<(K, C::Init)>, widget: C::ParentWidget) -> Self {
+        let mut output = Self::builder().launch(widget).detach();
+        for (key, init) in component_vec {
+            output.insert(key, init);
+        }
+        output
+    }
+}
+
+impl<K, C, S> AsyncFactoryHashMap<K, C, S>
+where
+    C: AsyncFactoryComponent,
+    <C::ParentWidget as FactoryView>::ReturnedWidget: Clone,
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Send a message to one of the elements.
+    ///
+    /// If the async [`init_model()`] method of the element hasn't returned yet,
+    /// the message is processed after the initialization.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is invalid.
+    pub fn send(&self, key: &K, msg: C::Input) {
+        self.inner[key].handle.input.send(msg).unwrap();
+    }
+
+    /// Returns [`true`] if the map contains an element for the given key,
+    /// even if its async [`init_model()`] method hasn't returned yet.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    /// Tries to get an immutable reference to
+    /// the model of one element.
+    ///
+    /// Returns [`None`] if `key` is invalid or the async [`init_model()`] method
+    /// hasn't returned yet.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn get(&self, key: &K) -> Option<&C> {
+        self.inner.get(key).and_then(|e| e.handle.data.get())
+    }
+
+    /// Tries to get a mutable reference to
+    /// the model of one element.
+    ///
+    /// Returns [`None`] if `key` is invalid or the async [`init_model()`] method
+    /// hasn't returned yet.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn get_mut(&mut self, key: &K) -> Option<AsyncFactoryElementGuard<'_, C>> {
+        let element = self.inner.get_mut(key)?;
+        element.handle.data.get_mut()?;
+        Some(AsyncFactoryElementGuard {
+            inner: &mut element.handle,
+        })
+    }
+
+    /// Inserts a new factory component into the map.
+    ///
+    /// If the map did not have this key present, None is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old value is returned.
+    /// The old value is also [`None`] if its async [`init_model()`] method hasn't returned yet.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn insert(&mut self, key: K, init: C::Init) -> Option<C> {
+        let existing = self.remove(&key);
+
+        let index = DynamicIndex::new(self.inner.len());
+        let builder = AsyncFactoryBuilder::<C>::new(init, self.output_sender.clone());
+
+        let position = C::position(index.current_index());
+        let returned_widget = self
+            .widget
+            .factory_append(builder.root_widget.clone(), &position);
+
+        let handle = builder.launch(&index, returned_widget);
+
+        assert!(self.inner.insert(key, Element { index, handle }).is_none());
+
+        existing
+    }
+
+    /// Removes a key from the map, returning the factory component at the key if the key was previously in the map.
+    ///
+    /// If the async [`init_model()`] method of the element hasn't returned yet,
+    /// the initialization is cancelled and [`None`] is returned.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn remove(&mut self, key: &K) -> Option<C> {
+        let element = self.inner.remove(key)?;
+        self.widget.factory_remove(&element.handle.returned_widget);
+
+        // Shift the positions of all elements after the removed one.
+        let removed_index = element.index.current_index();
+        for other in self.inner.values() {
+            if other.index.current_index() > removed_index {
+                other.index.decrement();
+                let position = C::position(other.index.current_index());
+                self.widget
+                    .factory_update_position(&other.handle.returned_widget, &position);
+            }
+        }
+
+        element.handle.data.into_inner()
+    }
+}
+
+impl<K, C, S> AsyncFactoryHashMap<K, C, S>
+where
+    C: AsyncFactoryComponent,
+    K: Hash + Eq,
+    S: BuildHasher,
+    C::ParentWidget: IsA<gtk::Stack>,
+    C::Root: IsA<gtk::Widget>,
+{
+    /// Makes the element at a given key visible in a [`gtk::Stack`].
+    /// Returns [`true`] on success, otherwise [`false`].
+    pub fn set_visible(&self, key: &K) -> bool {
+        if let Some(element) = self.inner.get(key) {
+            self.widget
+                .as_ref()
+                .set_visible_child(element.handle.root_widget.as_ref());
+            true
+        } else {
+            false
+        }
+    }
+}
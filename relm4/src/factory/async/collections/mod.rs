@@ -1,6 +1,11 @@
 //! Containers similar to [`std::collections`] that can be used to store factory data.
 
+mod hashmap;
+#[cfg(test)]
+mod tests;
 mod vec_deque;
+
+pub use hashmap::{AsyncFactoryHashMap, AsyncFactoryHashMapBuilder, AsyncFactoryHashMapConnector};
 pub use vec_deque::{
     AsyncFactoryVecDeque, AsyncFactoryVecDequeBuilder, AsyncFactoryVecDequeConnector,
     AsyncFactoryVecDequeGuard,
@@ -0,0 +1,168 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk::glib::MainContext;
+use gtk::prelude::Cast;
+
+use crate::RelmIterChildrenExt;
+use crate::factory::{AsyncFactoryComponent, AsyncFactorySender, DynamicIndex, FactoryView};
+
+use super::AsyncFactoryHashMap;
+
+type Events = Rc<RefCell<Vec<String>>>;
+
+/// Records whether an initialization was dropped before it finished.
+struct PendingInit {
+    value: u8,
+    events: Events,
+    finished: bool,
+}
+
+impl Drop for PendingInit {
+    fn drop(&mut self) {
+        let event = if self.finished {
+            format!("init {}", self.value)
+        } else {
+            format!("cancelled {}", self.value)
+        };
+        self.events.borrow_mut().push(event);
+    }
+}
+
+#[derive(Debug)]
+struct Item {
+    value: u8,
+}
+
+impl AsyncFactoryComponent for Item {
+    type ParentWidget = gtk::Box;
+    type CommandOutput = ();
+    type Input = u8;
+    type Output = ();
+    type Init = (u8, flume::Receiver<()>, Events);
+    type Root = gtk::Label;
+    type Widgets = ();
+
+    async fn init_model(
+        (value, ready, events): Self::Init,
+        _: &DynamicIndex,
+        _: AsyncFactorySender<Self>,
+    ) -> Self {
+        let mut pending = PendingInit {
+            value,
+            events,
+            finished: false,
+        };
+        let _ = ready.recv_async().await;
+        pending.finished = true;
+
+        Self { value }
+    }
+
+    fn init_root() -> Self::Root {
+        gtk::Label::default()
+    }
+
+    fn init_widgets(
+        &mut self,
+        _: &DynamicIndex,
+        root: Self::Root,
+        _: &<Self::ParentWidget as FactoryView>::ReturnedWidget,
+        _: AsyncFactorySender<Self>,
+    ) -> Self::Widgets {
+        root.set_label(&self.value.to_string());
+    }
+
+    async fn update(&mut self, value: Self::Input, _: AsyncFactorySender<Self>) {
+        self.value = value;
+    }
+}
+
+/// Returns the labels of all children of `container` in widget order.
+fn labels(container: &gtk::Box) -> Vec<String> {
+    container
+        .iter_children()
+        .map(|child| child.downcast::<gtk::Label>().unwrap().label().into())
+        .collect()
+}
+
+fn run_main_loop() {
+    let main_ctx = MainContext::default();
+    while main_ctx.iteration(false) {}
+}
+
+#[gtk::test]
+fn hashmap_pending_init() {
+    let events = Events::default();
+    let mut map = AsyncFactoryHashMap::<char, Item>::builder()
+        .launch_default()
+        .detach();
+
+    let (ready_a, wait_a) = flume::unbounded();
+    let (ready_b, wait_b) = flume::unbounded();
+    assert!(map.insert('a', (1, wait_a, events.clone())).is_none());
+    assert!(map.insert('b', (2, wait_b, events.clone())).is_none());
+    run_main_loop();
+
+    // Pending elements are part of the map, but have no model yet.
+    assert_eq!(map.len(), 2);
+    assert!(map.contains_key(&'a'));
+    assert!(map.get(&'a').is_none());
+    assert_eq!(labels(map.widget()), ["", ""]);
+
+    // Messages sent during the initialization are processed afterwards.
+    map.send(&'a', 3);
+    ready_a.send(()).unwrap();
+    run_main_loop();
+    assert_eq!(map.get(&'a').unwrap().value, 3);
+    assert!(map.get(&'b').is_none());
+    assert_eq!(labels(map.widget()), ["1", ""]);
+    assert_eq!(*events.borrow(), ["init 1"]);
+
+    // Removing a pending element cancels its initialization.
+    assert!(map.remove(&'b').is_none());
+    run_main_loop();
+    assert!(!map.contains_key(&'b'));
+    assert_eq!(labels(map.widget()), ["1"]);
+    assert_eq!(*events.borrow(), ["init 1", "cancelled 2"]);
+    assert!(ready_b.send(()).is_err());
+
+    // Removing an initialized element returns its model.
+    assert_eq!(map.remove(&'a').unwrap().value, 3);
+    assert!(map.is_empty());
+    assert!(labels(map.widget()).is_empty());
+}
+
+#[gtk::test]
+fn hashmap_replace_and_clear_pending_init() {
+    let events = Events::default();
+    let mut map = AsyncFactoryHashMap::<char, Item>::builder()
+        .launch_default()
+        .detach();
+
+    let (_ready_a, wait_a) = flume::unbounded();
+    let (ready_b, wait_b) = flume::unbounded();
+    let (_ready_c, wait_c) = flume::unbounded();
+    map.insert('a', (1, wait_a, events.clone()));
+    run_main_loop();
+
+    // Replacing a pending element cancels its initialization.
+    assert!(map.insert('a', (2, wait_b, events.clone())).is_none());
+    run_main_loop();
+    assert_eq!(*events.borrow(), ["cancelled 1"]);
+
+    ready_b.send(()).unwrap();
+    run_main_loop();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(&'a').unwrap().value, 2);
+    assert_eq!(*events.borrow(), ["cancelled 1", "init 2"]);
+
+    // Clearing the map cancels all pending initializations.
+    map.insert('c', (3, wait_c, events.clone()));
+    run_main_loop();
+    map.clear();
+    run_main_loop();
+    assert!(map.is_empty());
+    assert!(labels(map.widget()).is_empty());
+    assert_eq!(*events.borrow(), ["cancelled 1", "init 2", "cancelled 3"]);
+}
@@ -3,19 +3,23 @@ use crate::{Receiver, Sender};
 use crate::factory::r#async::AsyncFactoryBuilder;
 use crate::factory::r#async::component_storage::AsyncComponentStorage;
 use crate::factory::r#async::traits::AsyncFactoryComponent;
-use crate::factory::{DynamicIndex, FactoryView};
+use crate::factory::reorder::{FactoryMove, Reorder};
+use crate::factory::selection::{self, SelectionChanged};
+use crate::factory::transition::{FactoryTransition, Transition};
+use crate::factory::{DynamicIndex, FactoryView, SelectableFactoryView, WeakDynamicIndex, diff};
 
 use super::{ModelStateValue, RenderedState};
 
-use std::collections::VecDeque;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
 use std::hash::Hash;
 use std::iter::FusedIterator;
 use std::marker::PhantomData;
-use std::ops::Deref;
+use std::ops::{Deref, RangeBounds};
 
 #[cfg(feature = "libadwaita")]
 use gtk::prelude::Cast;
+use gtk::prelude::IsA;
 
 #[cfg(feature = "libadwaita")]
 use std::hash::Hasher;
@@ -137,6 +141,18 @@ where
             .and_then(AsyncComponentStorage::get_mut)
     }
 
+    /// Tries to get a mutable reference to the model
+    /// of the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`None`] if the element was removed or the async [`init_model()`] method
+    /// hasn't returned yet.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn get_mut_weak(&mut self, index: &WeakDynamicIndex) -> Option<&mut C> {
+        let position = self.resolve(index)?;
+        self.get_mut(position)
+    }
+
     /// Provides a mutable reference to the model of the back element.
     ///
     ///  Returns [`None`] if the deque is empty or the async [`init_model()`] method
@@ -187,7 +203,9 @@ where
     ///
     /// [`init_model()`]: AsyncFactoryComponent::init_model
     pub fn remove(&mut self, index: usize) -> Option<C> {
-        self.inner.model_state.remove(index);
+        if let Some(state) = self.inner.model_state.remove(index) {
+            state.index.set_removed();
+        }
         let component = self.inner.components.remove(index);
 
         // Decrement the indexes of the following elements.
@@ -195,13 +213,18 @@ where
             states.index.decrement();
         }
 
-        if let Some(comp) = &component
-            && let Some(widget) = &comp.returned_widget()
-        {
-            self.widget.factory_remove(widget);
-        }
+        component.and_then(|component| self.inner.remove_component(component))
+    }
 
-        component.and_then(AsyncComponentStorage::extract)
+    /// Removes and returns the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`None`] if the element was removed already or the async [`init_model()`] method
+    /// hasn't returned yet.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn remove_weak(&mut self, index: &WeakDynamicIndex) -> Option<C> {
+        let position = self.resolve(index)?;
+        self.remove(position)
     }
 
     /// Appends an element at the end of the [`AsyncFactoryVecDeque`].
@@ -340,18 +363,23 @@ where
     }
 
     /// Remove all components from the [`AsyncFactoryVecDeque`].
+    ///
+    /// The elements are removed without a [`FactoryTransition`].
     pub fn clear(&mut self) {
-        self.inner.model_state.clear();
-
-        for component in self.inner.components.drain(..) {
-            // Remove all widgets
-            if let Some(widget) = component.returned_widget() {
-                self.inner.widget.factory_remove(widget);
-            }
+        for state in self.inner.model_state.drain(..) {
+            state.index.set_removed();
+        }
 
-            // Make sure the component is shutdown properly
-            component.extract();
+        // Remove all widgets right away, including those of elements that are still animated.
+        let transition = self.inner.transition.take();
+        if let Some(transition) = &transition {
+            transition.finish();
         }
+        let components = std::mem::take(&mut self.inner.components);
+        for component in components {
+            self.inner.remove_component(component);
+        }
+        self.inner.transition = transition;
 
         self.inner.rendered_state.clear();
 
@@ -490,6 +518,9 @@ where
             rendered_state: VecDeque::new(),
             // 0 is always an invalid uid
             uid_counter: 1,
+            reorder: None,
+            selection_changed: None,
+            transition: None,
         }
     }
 
@@ -508,6 +539,9 @@ where
             rendered_state: VecDeque::new(),
             // 0 is always an invalid uid
             uid_counter: 1,
+            reorder: None,
+            selection_changed: None,
+            transition: None,
         }
     }
 }
@@ -527,6 +561,9 @@ where
     model_state: VecDeque<ModelStateValue>,
     rendered_state: VecDeque<RenderedState>,
     uid_counter: usize,
+    reorder: Option<Reorder<C::Root>>,
+    selection_changed: Option<SelectionChanged<<C::ParentWidget as FactoryView>::ReturnedWidget>>,
+    transition: Option<Transition<C::Root, <C::ParentWidget as FactoryView>::ReturnedWidget>>,
 }
 
 impl<C: AsyncFactoryComponent> Drop for AsyncFactoryVecDeque<C>
@@ -534,6 +571,9 @@ where
     <C::ParentWidget as FactoryView>::ReturnedWidget: Clone,
 {
     fn drop(&mut self) {
+        // The parent widget is likely destroyed as well, so don't animate the removal.
+        self.transition = None;
+        self.selection_changed = None;
         self.guard().clear();
     }
 }
@@ -566,34 +606,41 @@ where
     fn render_changes(&mut self) {
         let mut first_position_change_idx = None;
 
+        // The previously rendered position of each element.
+        let rendered_positions: HashMap<usize, usize> = self
+            .rendered_state
+            .iter()
+            .enumerate()
+            .map(|(position, rendered)| (rendered.uid, position))
+            .collect();
+        let rendered_positions: Vec<Option<usize>> = self
+            .model_state
+            .iter()
+            .map(|state| rendered_positions.get(&state.uid).copied())
+            .collect();
+        let unmoved = diff::unmoved_elements(&rendered_positions);
+
         let components = &mut self.components;
-        let rendered_state = &mut self.rendered_state;
         for (index, state) in self.model_state.iter().enumerate() {
-            if state.uid == rendered_state.front().map(|r| r.uid).unwrap_or_default() {
-                // Remove item from previously rendered list
-                rendered_state.pop_front();
-
-                if state.changed {
-                    // Update component
-                    components[index].state_change_notify();
-                }
-            } else if let Some(rendered_index) =
-                rendered_state.iter().position(|r| r.uid == state.uid)
-            {
-                if first_position_change_idx.is_none() {
-                    first_position_change_idx = Some(index);
-                }
-
-                // Remove item from previously rendered list
-                rendered_state.remove(rendered_index);
-
-                // Detach and re-attach item
-                let widget = components[index].returned_widget().unwrap();
-                if index == 0 {
-                    self.widget.factory_move_start(widget);
+            if let Some(position) = rendered_positions[index] {
+                if unmoved[index] {
+                    // Elements in front of this one were removed.
+                    if position != index && first_position_change_idx.is_none() {
+                        first_position_change_idx = Some(index);
+                    }
                 } else {
-                    let previous_widget = components[index - 1].returned_widget().unwrap();
-                    self.widget.factory_move_after(widget, previous_widget);
+                    if first_position_change_idx.is_none() {
+                        first_position_change_idx = Some(index);
+                    }
+
+                    // Detach and re-attach item
+                    let widget = components[index].returned_widget().unwrap();
+                    if index == 0 {
+                        self.widget.factory_move_start(widget);
+                    } else {
+                        let previous_widget = components[index - 1].returned_widget().unwrap();
+                        self.widget.factory_move_after(widget, previous_widget);
+                    }
                 }
 
                 if state.changed {
@@ -615,10 +662,25 @@ where
                     self.widget
                         .factory_insert_after(insert_widget, &position, previous_widget)
                 };
-                let component = components.remove(index).unwrap();
-                let dyn_index = &self.model_state[index].index;
-                let component = component.launch(dyn_index, returned_widget).unwrap();
-                components.insert(index, component);
+
+                // Swap the component with the last one instead of shifting
+                // all following components when launching it.
+                let component = components.swap_remove_back(index).unwrap();
+                let component = component.launch(&state.index, returned_widget).unwrap();
+                if let Some(reorder) = &self.reorder {
+                    reorder.attach(component.widget(), &state.index);
+                }
+                if let (Some(selection_changed), Some(returned_widget)) =
+                    (&self.selection_changed, component.returned_widget())
+                {
+                    selection_changed.attach(returned_widget, &state.index);
+                }
+                if let Some(transition) = &self.transition {
+                    transition.enter(component.widget());
+                }
+                components.push_back(component);
+                let last = components.len() - 1;
+                components.swap(index, last);
             }
         }
 
@@ -652,6 +714,22 @@ where
         }
     }
 
+    /// Removes the widget of a component and shuts the component down.
+    ///
+    /// With a [`FactoryTransition`], the widget is only removed
+    /// after the animation is finished.
+    fn remove_component(&self, component: AsyncComponentStorage<C>) -> Option<C> {
+        let (data, widgets) = component.extract();
+        if let Some((root_widget, returned_widget, widgets)) = widgets {
+            if let Some(transition) = &self.transition {
+                transition.exit(&root_widget, returned_widget, widgets);
+            } else {
+                self.widget.factory_remove(&returned_widget);
+            }
+        }
+        data
+    }
+
     /// Returns the number of elements in the [`AsyncFactoryVecDeque`].
     pub fn len(&self) -> usize {
         self.components.len()
@@ -688,6 +766,42 @@ where
             .and_then(AsyncComponentStorage::get)
     }
 
+    /// Returns the current position of the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`None`] if the element was removed or doesn't belong to this factory.
+    #[must_use]
+    pub fn resolve(&self, index: &WeakDynamicIndex) -> Option<usize> {
+        let index = index.upgrade()?;
+        let position = index.current_index();
+        self.model_state
+            .get(position)
+            .filter(|state| state.index.ptr_eq(&index))
+            .map(|_| position)
+    }
+
+    /// Tries to get an immutable reference to the model
+    /// of the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`None`] if the element was removed or the async [`init_model()`] method
+    /// hasn't returned yet.
+    ///
+    /// [`init_model()`]: AsyncFactoryComponent::init_model
+    pub fn get_weak(&self, index: &WeakDynamicIndex) -> Option<&C> {
+        self.resolve(index).and_then(|position| self.get(position))
+    }
+
+    /// Sends a message to the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`false`] if the element was removed.
+    pub fn send_weak(&self, index: &WeakDynamicIndex, msg: C::Input) -> bool {
+        if let Some(position) = self.resolve(index) {
+            self.send(position, msg);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Provides a reference to the model of the back element.
     ///
     /// Returns [`None`] if `index` is invalid or the async [`init_model()`] method
@@ -725,3 +839,170 @@ where
         self.components.iter().map(AsyncComponentStorage::get)
     }
 }
+
+impl<C: AsyncFactoryComponent> AsyncFactoryVecDeque<C>
+where
+    <C::ParentWidget as FactoryView>::ReturnedWidget: Clone,
+    C::Root: IsA<gtk::Widget>,
+{
+    /// Allows dragging elements onto other elements to request moving them.
+    ///
+    /// If an element is dropped on another element, the message returned by `f`
+    /// is sent to `sender`. **The factory isn't changed automatically**,
+    /// because it's owned by your component. The move needs to be applied with [`AsyncFactoryVecDequeGuard::move_to()`].
+    ///
+    /// While an element is dragged over another element, the other element has
+    /// the CSS class `relm4-reorder-drop-target`, which shows a dashed outline by default.
+    ///
+    /// Calling this method again replaces the previous `sender` and `f`.
+    pub fn enable_reorder_requests<Msg, F>(&mut self, sender: &Sender<Msg>, f: F)
+    where
+        Msg: 'static,
+        F: Fn(FactoryMove) -> Msg + 'static,
+    {
+        let reorder = Reorder::new(sender, f);
+
+        // Elements that are already rendered.
+        for (component, state) in self.components.iter().zip(&self.model_state) {
+            if component.returned_widget().is_some() {
+                reorder.attach(component.widget(), &state.index);
+            }
+        }
+
+        self.reorder = Some(reorder);
+    }
+
+    /// Animates elements when they are added or removed.
+    ///
+    /// Removed elements are faded out before their widgets are removed from the parent widget.
+    /// Elements are only animated while the parent widget is shown.
+    /// The widgets of fading elements are removed right away if the parent widget is hidden,
+    /// the factory is cleared or dropped, or the transition is replaced.
+    pub fn set_transition(&mut self, transition: Option<FactoryTransition>)
+    where
+        <C::ParentWidget as FactoryView>::ReturnedWidget: 'static,
+    {
+        self.transition = transition.map(|transition| Transition::new(&self.widget, transition));
+    }
+}
+
+impl<C> AsyncFactoryVecDeque<C>
+where
+    C: AsyncFactoryComponent,
+    C::ParentWidget: SelectableFactoryView,
+    <C::ParentWidget as FactoryView>::ReturnedWidget: Clone,
+{
+    /// Sets how the elements can be selected.
+    ///
+    /// With [`gtk::SelectionMode::Multiple`], clicking an element
+    /// while holding Ctrl toggles its selection and holding Shift
+    /// selects a range of elements.
+    pub fn set_selection_mode(&self, mode: gtk::SelectionMode) {
+        self.widget.factory_set_selection_mode(mode);
+    }
+
+    /// Returns the sorted indices of the selected elements.
+    ///
+    /// Elements that aren't rendered yet are never selected.
+    #[must_use]
+    pub fn selected(&self) -> Vec<usize> {
+        selection::selected_positions(
+            &self.widget,
+            self.components
+                .iter()
+                .map(|component| component.returned_widget()),
+        )
+    }
+
+    /// Returns the [`DynamicIndex`]es of the selected elements.
+    ///
+    /// Unlike the indices returned by [`selected()`](Self::selected),
+    /// they stay valid if the elements are moved.
+    #[must_use]
+    pub fn selected_dynamic_indices(&self) -> Vec<DynamicIndex> {
+        self.selected()
+            .into_iter()
+            .filter_map(|index| self.model_state.get(index))
+            .map(|state| state.index.clone())
+            .collect()
+    }
+
+    /// Selects the element at `index`.
+    ///
+    /// In [`gtk::SelectionMode::Single`] and [`gtk::SelectionMode::Browse`],
+    /// the previously selected element is unselected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn select(&self, index: usize) {
+        if let Some(widget) = self.components[index].returned_widget() {
+            self.widget.factory_select(widget);
+        }
+    }
+
+    /// Unselects the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn unselect(&self, index: usize) {
+        if let Some(widget) = self.components[index].returned_widget() {
+            self.widget.factory_unselect(widget);
+        }
+    }
+
+    /// Selects all elements in `range`.
+    ///
+    /// This only makes sense in [`gtk::SelectionMode::Multiple`].
+    pub fn select_range(&self, range: impl RangeBounds<usize>) {
+        for (index, component) in self.components.iter().enumerate() {
+            if range.contains(&index)
+                && let Some(widget) = component.returned_widget()
+            {
+                self.widget.factory_select(widget);
+            }
+        }
+    }
+
+    /// Selects all elements if the selection mode allows it.
+    pub fn select_all(&self) {
+        self.widget.factory_select_all();
+    }
+
+    /// Unselects all elements.
+    pub fn unselect_all(&self) {
+        self.widget.factory_unselect_all();
+    }
+
+    /// Sends the message returned by `f` to `sender` every time the selection changed.
+    ///
+    /// `f` receives the sorted indices of the selected elements.
+    /// Selected widgets that weren't added by this factory are ignored.
+    /// The selection also changes if selected elements are removed
+    /// from the factory.
+    ///
+    /// While an element is dragged over another element, the other element has
+    /// the CSS class `relm4-reorder-drop-target`, which shows a dashed outline by default.
+    ///
+    /// Calling this method again replaces the previous `sender` and `f`.
+    /// The signal handler is disconnected when the factory is dropped.
+    pub fn connect_selection_changed<Msg, F>(&mut self, sender: &Sender<Msg>, f: F)
+    where
+        Msg: 'static,
+        F: Fn(Vec<usize>) -> Msg + 'static,
+    {
+        // Disconnect the previous handler first.
+        self.selection_changed = None;
+        let selection_changed = SelectionChanged::new(&self.widget, sender, f);
+
+        // Elements that are already rendered.
+        for (component, state) in self.components.iter().zip(&self.model_state) {
+            if let Some(returned_widget) = component.returned_widget() {
+                selection_changed.attach(returned_widget, &state.index);
+            }
+        }
+
+        self.selection_changed = Some(selection_changed);
+    }
+}
@@ -4,6 +4,15 @@ use super::AsyncFactoryBuilder;
 use super::AsyncFactoryHandle;
 use super::traits::AsyncFactoryComponent;
 
+/// The root widget, the returned widget and the widgets of a launched component.
+///
+/// The widgets are missing if the component wasn't initialized yet.
+type LaunchedWidgets<C> = (
+    <C as AsyncFactoryComponent>::Root,
+    <<C as AsyncFactoryComponent>::ParentWidget as FactoryView>::ReturnedWidget,
+    Option<Box<<C as AsyncFactoryComponent>::Widgets>>,
+);
+
 #[derive(Debug)]
 pub(super) enum AsyncComponentStorage<C: AsyncFactoryComponent>
 where
@@ -51,10 +60,19 @@ where
         }
     }
 
-    pub(super) fn extract(self) -> Option<C> {
+    /// Returns the model and the widgets if the component was already launched.
+    pub(super) fn extract(self) -> (Option<C>, Option<LaunchedWidgets<C>>) {
         match self {
-            Self::Builder(_) => None,
-            Self::Final(handle) => handle.data.into_inner(),
+            Self::Builder(_) => (None, None),
+            Self::Final(AsyncFactoryHandle {
+                data,
+                root_widget,
+                returned_widget,
+                ..
+            }) => {
+                let (data, widgets) = data.into_parts().unzip();
+                (data, Some((root_widget, returned_widget, widgets)))
+            }
         }
     }
 
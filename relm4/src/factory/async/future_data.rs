@@ -1,5 +1,6 @@
 use std::cell::RefCell;
 
+use futures::future::AbortHandle;
 use once_cell::unsync::OnceCell;
 
 use super::AsyncFactoryComponent;
@@ -11,13 +12,25 @@ type DataReceiver<T> = RefCell<Option<Receiver<T>>>;
 pub(super) struct AsyncData<C: AsyncFactoryComponent> {
     future: DataReceiver<DataGuard<C, C::Widgets, C::Output>>,
     data: OnceCell<DataGuard<C, C::Widgets, C::Output>>,
+    /// Cancels the async initialization if the data is dropped before it finished.
+    abort_handle: AbortHandle,
+}
+
+impl<C: AsyncFactoryComponent> Drop for AsyncData<C> {
+    fn drop(&mut self) {
+        self.abort_handle.abort();
+    }
 }
 
 impl<C: AsyncFactoryComponent> AsyncData<C> {
-    pub(super) fn new(data: Receiver<DataGuard<C, C::Widgets, C::Output>>) -> Self {
+    pub(super) fn new(
+        data: Receiver<DataGuard<C, C::Widgets, C::Output>>,
+        abort_handle: AbortHandle,
+    ) -> Self {
         Self {
             future: RefCell::new(Some(data)),
             data: OnceCell::new(),
+            abort_handle,
         }
     }
 }
@@ -34,8 +47,16 @@ impl<C: AsyncFactoryComponent> AsyncData<C> {
     }
 
     pub(super) fn into_inner(self) -> Option<C> {
+        self.into_parts().map(|(data, _)| data)
+    }
+
+    /// Returns the model and keeps the widgets alive
+    /// instead of dropping them with the runtime.
+    pub(super) fn into_parts(mut self) -> Option<(C, Box<C::Widgets>)> {
         self.update();
-        self.data.into_inner().map(|g| g.into_inner())
+        std::mem::take(&mut self.data)
+            .into_inner()
+            .map(|g| g.into_parts())
     }
 
     fn update(&self) {
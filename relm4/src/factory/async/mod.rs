@@ -10,6 +10,7 @@ use future_data::AsyncData;
 use handle::AsyncFactoryHandle;
 
 pub use collections::{
+    AsyncFactoryHashMap, AsyncFactoryHashMapBuilder, AsyncFactoryHashMapConnector,
     AsyncFactoryVecDeque, AsyncFactoryVecDequeBuilder, AsyncFactoryVecDequeConnector,
     AsyncFactoryVecDequeGuard,
 };
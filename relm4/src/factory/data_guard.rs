@@ -6,6 +6,7 @@ use gtk::glib;
 use crate::{Sender, shutdown::ShutdownSender};
 
 type DynShutdownFn<C, Widgets, Output> = dyn Fn(&mut C, &mut Widgets, Sender<Output>);
+type DynOutputSenderFn<Output> = dyn FnOnce() -> Sender<Output>;
 
 /// # SAFETY
 ///
@@ -16,7 +17,11 @@ pub(super) struct DataGuard<C, Widgets, Output> {
     data: Box<C>,
     widgets: Box<Widgets>,
     rt_dropper: RuntimeDropper,
-    output_sender: Sender<Output>,
+    /// Returns the output sender passed to the shutdown function.
+    ///
+    /// It's only created on shutdown because forwarding the outputs of
+    /// factory elements might require a channel that isn't used otherwise.
+    output_sender: Box<DynOutputSenderFn<Output>>,
     shutdown_notifier: ShutdownSender,
     shutdown_fn: Box<DynShutdownFn<C, Widgets, Output>>,
 }
@@ -28,7 +33,7 @@ impl<C, Widgets, Output> DataGuard<C, Widgets, Output> {
         data: Box<C>,
         widgets: Box<Widgets>,
         shutdown_notifier: ShutdownSender,
-        output_sender: Sender<Output>,
+        output_sender: impl FnOnce() -> Sender<Output> + 'static,
         f: F,
         shutdown_fn: ShutdownFn,
     ) -> Self
@@ -69,6 +74,7 @@ impl<C, Widgets, Output> DataGuard<C, Widgets, Output> {
         let future = f(runtime_data, runtime_widgets);
         let rt_dropper = RuntimeDropper(Some(crate::spawn_local(future)));
         let shutdown_fn = Box::new(shutdown_fn);
+        let output_sender = Box::new(output_sender);
 
         Self {
             data,
@@ -89,6 +95,15 @@ impl<C, Widgets, Output> DataGuard<C, Widgets, Output> {
     }
 
     pub(super) fn into_inner(self) -> C {
+        self.into_parts().0
+    }
+
+    /// Shuts the runtime down like [`DataGuard::into_inner`],
+    /// but returns the widgets instead of dropping them.
+    ///
+    /// This allows the widgets to outlive the model,
+    /// e.g. while a removed factory element is animated.
+    pub(super) fn into_parts(self) -> (C, Box<Widgets>) {
         let Self {
             mut data,
             mut widgets,
@@ -101,12 +116,11 @@ impl<C, Widgets, Output> DataGuard<C, Widgets, Output> {
         drop(rt_dropper);
         shutdown_notifier.shutdown();
 
-        shutdown_fn(&mut data, &mut widgets, output_sender);
+        shutdown_fn(&mut data, &mut widgets, output_sender());
 
-        drop(widgets);
         drop(shutdown_fn);
 
-        *data
+        (*data, widgets)
     }
 }
 
@@ -116,7 +130,7 @@ impl<C, Widgets, Output> std::fmt::Debug for DataGuard<C, Widgets, Output> {
             .field("data", &"<Data>")
             .field("widgets", &"<Widgets>")
             .field("rt_dropper", &self.rt_dropper)
-            .field("output_sender", &self.output_sender)
+            .field("output_sender", &"<output sender>")
             .field("shutdown_notifier", &self.shutdown_notifier)
             .field("shutdown_fn", &"<shutdown fn>")
             .finish()
@@ -219,7 +233,7 @@ mod test {
             data,
             widgets,
             shutdown_notifier,
-            output_sender,
+            move || output_sender,
             |mut rt_data, _| async move {
                 while (rx.recv_async().await).is_ok() {
                     rt_data.add();
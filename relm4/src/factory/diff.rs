@@ -0,0 +1,82 @@
+//! Helpers for rendering changes of factory collections efficiently.
+
+/// Finds the elements that don't need to be moved when rendering a new order.
+///
+/// `rendered_positions` contains the previously rendered position of each element
+/// in the new order, or [`None`] for new elements.
+/// The elements of the longest increasing subsequence are already in the right
+/// order relative to each other, so moving all other elements after their
+/// predecessor requires the fewest moves.
+///
+/// This runs in `O(n log n)`.
+pub(super) fn unmoved_elements(rendered_positions: &[Option<usize>]) -> Vec<bool> {
+    // The rendered position and index of the smallest last element
+    // of all increasing subsequences with a length of `i + 1`.
+    let mut tails: Vec<(usize, usize)> = Vec::new();
+    let mut predecessors = vec![None; rendered_positions.len()];
+
+    for (index, position) in rendered_positions.iter().enumerate() {
+        let Some(position) = *position else {
+            continue;
+        };
+
+        let length = tails.partition_point(|(tail, _)| *tail < position);
+        if length > 0 {
+            predecessors[index] = Some(tails[length - 1].1);
+        }
+
+        if length == tails.len() {
+            tails.push((position, index));
+        } else {
+            tails[length] = (position, index);
+        }
+    }
+
+    let mut unmoved = vec![false; rendered_positions.len()];
+    let mut next = tails.last().map(|(_, index)| *index);
+    while let Some(index) = next {
+        unmoved[index] = true;
+        next = predecessors[index];
+    }
+    unmoved
+}
+
+#[cfg(test)]
+mod test {
+    use super::unmoved_elements;
+
+    #[test]
+    fn unchanged() {
+        let positions = [Some(0), Some(1), Some(2)];
+        assert_eq!(unmoved_elements(&positions), [true, true, true]);
+    }
+
+    #[test]
+    fn reversed() {
+        let positions = [Some(3), Some(2), Some(1), Some(0)];
+        let unmoved = unmoved_elements(&positions);
+        assert_eq!(unmoved.iter().filter(|unmoved| **unmoved).count(), 1);
+    }
+
+    #[test]
+    fn moved_to_front() {
+        let positions = [Some(3), Some(0), Some(1), Some(2)];
+        assert_eq!(unmoved_elements(&positions), [false, true, true, true]);
+    }
+
+    #[test]
+    fn new_and_removed() {
+        // The element at position 1 was removed.
+        let positions = [None, Some(0), Some(2), None, Some(3)];
+        assert_eq!(
+            unmoved_elements(&positions),
+            [false, true, true, false, true]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert!(unmoved_elements(&[]).is_empty());
+        assert_eq!(unmoved_elements(&[None, None]), [false, false]);
+    }
+}
@@ -1,5 +1,5 @@
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
 
 /// A dynamic index that updates automatically when items are shifted inside a factory container.
 ///
@@ -20,7 +20,14 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 /// where you actually need the index as [`usize`].
 #[derive(Debug)]
 pub struct DynamicIndex {
-    inner: Arc<AtomicUsize>,
+    inner: Arc<DynamicIndexInner>,
+}
+
+#[derive(Debug)]
+struct DynamicIndexInner {
+    index: AtomicUsize,
+    /// Set once the element was removed from its factory.
+    removed: AtomicBool,
 }
 
 impl PartialEq for DynamicIndex {
@@ -45,69 +52,124 @@ impl DynamicIndex {
     /// This value is updated by the factory container and might change after each update function.
     #[must_use]
     pub fn current_index(&self) -> usize {
-        self.inner.load(Ordering::Relaxed)
+        self.inner.index.load(Ordering::Relaxed)
+    }
+
+    /// Returns [`true`] if the element was removed from its factory.
+    ///
+    /// The index of a removed element isn't updated anymore.
+    #[must_use]
+    pub fn is_removed(&self) -> bool {
+        self.inner.removed.load(Ordering::Relaxed)
+    }
+
+    /// Creates a [`WeakDynamicIndex`] for sending in messages.
+    #[must_use]
+    pub fn downgrade(&self) -> WeakDynamicIndex {
+        WeakDynamicIndex {
+            inner: Arc::downgrade(&self.inner),
+        }
     }
 
-    // Creates a [`WeakDynamicIndex`] for sending in messages.
-    // pub fn downgrade(&self) -> WeakDynamicIndex {
-    //     WeakDynamicIndex {
-    //         inner: Arc::downgrade(&self.inner),
-    //     }
-    // }
+    /// Returns [`true`] if both indices belong to the same element.
+    pub(super) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
 
     pub(super) fn increment(&self) {
-        self.inner.fetch_add(1, Ordering::Relaxed);
+        self.inner.index.fetch_add(1, Ordering::Relaxed);
     }
 
     pub(super) fn decrement(&self) {
-        self.inner.fetch_sub(1, Ordering::Relaxed);
+        self.inner.index.fetch_sub(1, Ordering::Relaxed);
     }
 
     pub(super) fn set_value(&self, new_value: usize) {
-        self.inner.store(new_value, Ordering::Relaxed);
+        self.inner.index.store(new_value, Ordering::Relaxed);
+    }
+
+    /// Marks the element as removed, so weak indices can't be upgraded anymore.
+    pub(super) fn set_removed(&self) {
+        self.inner.removed.store(true, Ordering::Relaxed);
     }
 
     pub(super) fn new(index: usize) -> Self {
         Self {
-            inner: Arc::new(AtomicUsize::new(index)),
+            inner: Arc::new(DynamicIndexInner {
+                index: AtomicUsize::new(index),
+                removed: AtomicBool::new(false),
+            }),
         }
     }
 }
 
-// A weak version of [`DynamicIndex`].
-//
-// Use this to send messages to the update function and call [`upgrade`](WeakDynamicIndex::upgrade)
-// to receive the actual [`DynamicIndex`].
-//
-// A weak index is preferred for sending in messages because messages can be stale by the time they
-// are handled and the element already deleted. A weak reference doesn't keep the index alive
-// if the element was deleted which allows you to properly handle invalid indices.
-//
-// # Panics
-//
-// Sending a [`WeakDynamicIndex`] to a different thread and accessing it will panic.
-// #[derive(Debug)]
-// pub struct WeakDynamicIndex {
-//     inner: Weak<Mutex<usize>>,
-// }
-
-// impl Clone for WeakDynamicIndex {
-//     fn clone(&self) -> Self {
-//         WeakDynamicIndex {
-//             inner: self.inner.clone(),
-//         }
-//     }
-// }
-
-// impl WeakDynamicIndex {
-// Attempts to upgrade the [`WeakDynamicIndex`] to a [`DynamicIndex`].
-//
-// Returns [`None`] if the index has since been dropped.
-//     pub fn upgrade(&self) -> Option<DynamicIndex> {
-//         Weak::upgrade(&self.inner).map(|inner| {
-//             DynamicIndex {
-//                 inner,
-//             }
-//         })
-//     }
-// }
+/// A weak version of [`DynamicIndex`] that works as a stable handle of a factory element.
+///
+/// Use this to send messages to the update function and call [`upgrade`](WeakDynamicIndex::upgrade)
+/// to receive the actual [`DynamicIndex`].
+///
+/// A weak index is preferred for sending in messages because messages can be stale by the time they
+/// are handled and the element already deleted. Unlike a [`DynamicIndex`], a weak index
+/// can't be upgraded anymore once the element was removed, which allows you to properly
+/// handle invalid indices instead of targeting the wrong element.
+///
+/// Factory collections such as [`FactoryVecDeque`](super::FactoryVecDeque) can also
+/// access elements directly through a weak index, for example with
+/// [`FactoryVecDeque::send_weak()`](super::FactoryVecDeque::send_weak).
+#[derive(Debug)]
+pub struct WeakDynamicIndex {
+    inner: Weak<DynamicIndexInner>,
+}
+
+impl Clone for WeakDynamicIndex {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl WeakDynamicIndex {
+    /// Attempts to upgrade the [`WeakDynamicIndex`] to a [`DynamicIndex`].
+    ///
+    /// Returns [`None`] if the element was removed from its factory.
+    #[must_use]
+    pub fn upgrade(&self) -> Option<DynamicIndex> {
+        self.inner
+            .upgrade()
+            .map(|inner| DynamicIndex { inner })
+            .filter(|index| !index.is_removed())
+    }
+
+    /// Get the current index number.
+    ///
+    /// Returns [`None`] if the element was removed from its factory.
+    #[must_use]
+    pub fn current_index(&self) -> Option<usize> {
+        self.upgrade().map(|index| index.current_index())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DynamicIndex;
+
+    #[test]
+    fn weak_index() {
+        let index = DynamicIndex::new(3);
+        let weak = index.downgrade();
+        assert_eq!(weak.current_index(), Some(3));
+
+        index.increment();
+        assert_eq!(weak.current_index(), Some(4));
+
+        // Clones of removed elements might still be alive.
+        let clone = index.clone();
+        index.set_removed();
+        assert!(clone.is_removed());
+        assert!(weak.upgrade().is_none());
+
+        drop((index, clone));
+        assert!(weak.current_index().is_none());
+    }
+}
@@ -0,0 +1,69 @@
+//! Forwarding of factory outputs together with the index of the element that sent them.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Sender;
+use crate::channel::OutputTarget;
+
+type ElementOutput<Index, Output> = Box<dyn Fn(&Index) -> OutputTarget<Output>>;
+
+/// Creates the output target of each element that attaches the index
+/// of the element to all of its outputs.
+///
+/// Outputs are forwarded right away when they are sent,
+/// without a channel or task per element.
+/// Once the receiver is dropped, the next output is lost
+/// and all later outputs are returned to the element.
+pub(crate) struct IndexedOutput<Index, Output> {
+    element_output: ElementOutput<Index, Output>,
+}
+
+impl<Index, Output> fmt::Debug for IndexedOutput<Index, Output> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IndexedOutput").finish_non_exhaustive()
+    }
+}
+
+impl<Index, Output> IndexedOutput<Index, Output>
+where
+    Index: Clone + Send + Sync + 'static,
+    Output: Send + 'static,
+{
+    pub(crate) fn new<Msg, F>(sender: &Sender<Msg>, f: F) -> Self
+    where
+        F: Fn(Index, Output) -> Msg + Send + Sync + 'static,
+        Msg: Send + 'static,
+    {
+        let sender = sender.clone();
+        let closed = AtomicBool::new(false);
+        let forward = Arc::new(move |index, output| {
+            // Give the output back once nobody listens anymore, just like a closed channel would.
+            // The output that finds the channel closed is already mapped by `f`
+            // and is dropped, like an output that a forwarding task received last.
+            if closed.load(Ordering::Relaxed) {
+                return Err(output);
+            }
+            if sender.send(f(index, output)).is_err() {
+                closed.store(true, Ordering::Relaxed);
+            }
+            Ok(())
+        });
+
+        Self {
+            element_output: Box::new(move |index| {
+                let forward = Arc::clone(&forward);
+                let index = index.clone();
+                OutputTarget::forward(move |output| forward(index.clone(), output))
+            }),
+        }
+    }
+}
+
+impl<Index, Output> IndexedOutput<Index, Output> {
+    /// Creates the output target of a new element.
+    pub(crate) fn element_output(&self, index: &Index) -> OutputTarget<Output> {
+        (self.element_output)(index)
+    }
+}
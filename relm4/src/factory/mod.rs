@@ -1,6 +1,7 @@
 //! Defines traits and data types to generate widgets from collections efficiently.
 
 mod dynamic_index;
+mod tree_index;
 
 /// Traits and implementations used for factories to interact with widgets.
 pub mod widgets;
@@ -12,19 +13,44 @@ pub mod positions;
 /// Implementation of regular factories.
 mod sync;
 
+/// Implementation of factories that only create widgets for visible elements.
+mod virtualized;
+
 mod data_guard;
+mod diff;
 use data_guard::DataGuard;
 
+mod reorder;
+pub use reorder::FactoryMove;
+
+mod selection;
+
+mod transition;
+pub use transition::FactoryTransition;
+
+mod snapshot;
+pub use snapshot::FactorySnapshot;
+
+mod indexed_output;
+
 pub use r#async::{
-    AsyncFactoryComponent, AsyncFactoryVecDeque, AsyncFactoryVecDequeBuilder,
+    AsyncFactoryComponent, AsyncFactoryHashMap, AsyncFactoryHashMapBuilder,
+    AsyncFactoryHashMapConnector, AsyncFactoryVecDeque, AsyncFactoryVecDequeBuilder,
     AsyncFactoryVecDequeConnector, AsyncFactoryVecDequeGuard,
 };
 pub use sync::{
-    CloneableFactoryComponent, FactoryComponent, FactoryHashMap, FactoryHashMapBuilder,
-    FactoryHashMapConnector, FactoryVecDeque, FactoryVecDequeBuilder, FactoryVecDequeConnector,
-    FactoryVecDequeGuard,
+    CloneableFactoryComponent, FactoryBTreeMap, FactoryBTreeMapBuilder, FactoryBTreeMapConnector,
+    FactoryComponent, FactoryHashMap, FactoryHashMapBuilder, FactoryHashMapConnector,
+    FactoryIndexMap, FactoryIndexMapBuilder, FactoryIndexMapConnector, FactoryTree,
+    FactoryTreeBuilder, FactoryTreeConnector, FactoryVecDeque, FactoryVecDequeBuilder,
+    FactoryVecDequeConnector, FactoryVecDequeGuard,
+};
+pub use virtualized::{
+    VirtualFactory, VirtualFactoryBuilder, VirtualFactoryComponent, VirtualFactoryConnector,
+    VirtualFactoryElement, VirtualFactoryElementGuard, VirtualFactorySender, VirtualFactoryView,
 };
 
 pub use crate::channel::{AsyncFactorySender, FactorySender};
-pub use dynamic_index::DynamicIndex;
+pub use dynamic_index::{DynamicIndex, WeakDynamicIndex};
+pub use tree_index::TreeIndex;
 pub use widgets::traits::*;
@@ -22,3 +22,77 @@ pub struct FixedPosition {
     /// Position on the y-axis.
     pub y: f64,
 }
+
+/// Position used for [`gtk::Notebook`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotebookPosition {
+    /// The text of the tab label.
+    ///
+    /// If [`None`], GTK uses "page N" as label.
+    pub label: Option<String>,
+}
+
+/// Position used for [`gtk::CenterBox`].
+///
+/// Each slot can only hold one widget, so adding
+/// another widget to the same slot replaces the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CenterBoxPosition {
+    /// The start widget.
+    Start,
+    /// The center widget.
+    Center,
+    /// The end widget.
+    End,
+}
+
+/// Position used for containers that pack widgets
+/// at their start or end like [`gtk::HeaderBar`] and [`gtk::ActionBar`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackPosition {
+    /// Packs the widget at the start of the container.
+    Start,
+    /// Packs the widget at the end of the container.
+    End,
+}
+
+/// Position used for [`adw::ViewStack`].
+///
+/// The properties are applied to the [`adw::ViewStackPage`] of the element.
+#[cfg(feature = "libadwaita")]
+#[cfg_attr(docsrs, doc(cfg(feature = "libadwaita")))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewStackPosition {
+    /// The name of the page.
+    pub name: Option<String>,
+    /// The title of the page, shown by switchers.
+    pub title: Option<String>,
+    /// The icon name of the page, shown by switchers.
+    pub icon_name: Option<String>,
+}
+
+/// Position used for containers with a sidebar and a content pane
+/// like [`adw::Flap`] and `adw::OverlaySplitView`.
+///
+/// Each pane can only hold one widget, so adding
+/// another widget to the same pane replaces the previous one.
+#[cfg(feature = "libadwaita")]
+#[cfg_attr(docsrs, doc(cfg(feature = "libadwaita")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitPosition {
+    /// The sidebar, or the flap of an [`adw::Flap`].
+    Sidebar,
+    /// The content.
+    Content,
+}
+
+/// Position used for [`adw::ToolbarView`].
+#[cfg(all(feature = "libadwaita", feature = "gnome_45"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "libadwaita", feature = "gnome_45"))))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolbarPosition {
+    /// Adds the widget as top bar below the previous top bars.
+    Top,
+    /// Adds the widget as bottom bar below the previous bottom bars.
+    Bottom,
+}
@@ -0,0 +1,201 @@
+//! Reordering of factory elements with drag and drop.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+use gtk::prelude::{Cast, EventControllerExt, IsA, ObjectExt, StaticType, ToValue, WidgetExt};
+use gtk::{gdk, glib};
+
+use crate::Sender;
+use crate::factory::DynamicIndex;
+
+/// An element of a factory was dropped on another element.
+///
+/// This is only a request, the factory isn't changed automatically.
+/// Apply the move with [`FactoryVecDequeGuard::move_to()`](crate::factory::FactoryVecDequeGuard::move_to)
+/// or [`AsyncFactoryVecDequeGuard::move_to()`](crate::factory::AsyncFactoryVecDequeGuard::move_to).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FactoryMove {
+    /// The index of the dragged element.
+    pub from: usize,
+    /// The index of the element it was dropped on.
+    pub to: usize,
+}
+
+/// The key used to store the controllers on the root widget.
+const CONTROLLERS_KEY: &str = "relm4-factory-reorder";
+
+/// The CSS class of the element an element is currently dragged over.
+pub(crate) const DROP_TARGET_CLASS: &str = "relm4-reorder-drop-target";
+
+/// The default style of the drop indicator.
+/// Applications can override it with a higher priority.
+const DROP_TARGET_CSS: &str = ".relm4-reorder-drop-target {
+    outline: 2px dashed alpha(currentColor, 0.6);
+    outline-offset: -2px;
+}";
+
+thread_local! {
+    /// Set once the default style of the drop indicator was loaded.
+    static CSS_LOADED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Loads the default style of the drop indicator for the default display.
+fn load_css() {
+    if CSS_LOADED.get() {
+        return;
+    }
+
+    if let Some(display) = gdk::Display::default() {
+        let provider = gtk::CssProvider::new();
+        #[allow(deprecated)]
+        provider.load_from_data(DROP_TARGET_CSS);
+        #[allow(deprecated)]
+        gtk::StyleContext::add_provider_for_display(
+            &display,
+            &provider,
+            gtk::STYLE_PROVIDER_PRIORITY_FALLBACK,
+        );
+        CSS_LOADED.set(true);
+    }
+}
+
+type Attach<Root> = Box<dyn Fn(&Root, &DynamicIndex)>;
+
+/// Attaches drag and drop controllers to the root widgets of factory elements.
+pub(crate) struct Reorder<Root> {
+    attach: Attach<Root>,
+}
+
+impl<Root> fmt::Debug for Reorder<Root> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reorder").finish_non_exhaustive()
+    }
+}
+
+impl<Root> Reorder<Root> {
+    pub(crate) fn new<Msg, F>(sender: &Sender<Msg>, f: F) -> Self
+    where
+        Root: IsA<gtk::Widget>,
+        Msg: 'static,
+        F: Fn(FactoryMove) -> Msg + 'static,
+    {
+        load_css();
+
+        let sender = sender.clone();
+        let on_move: Rc<dyn Fn(FactoryMove)> = Rc::new(move |moved| sender.emit(f(moved)));
+
+        // The element that is currently dragged.
+        // Drags that don't originate from this factory are rejected.
+        let dragged: Rc<RefCell<Option<DynamicIndex>>> = Rc::default();
+
+        Self {
+            attach: Box::new(move |root, index| {
+                attach_controllers(root.upcast_ref(), index, &dragged, &on_move);
+            }),
+        }
+    }
+
+    /// Allow dragging the element and dropping other elements on it.
+    ///
+    /// Controllers attached by a previous [`Reorder`] are replaced.
+    pub(crate) fn attach(&self, root: &Root, index: &DynamicIndex) {
+        (self.attach)(root, index);
+    }
+}
+
+fn attach_controllers(
+    widget: &gtk::Widget,
+    index: &DynamicIndex,
+    dragged: &Rc<RefCell<Option<DynamicIndex>>>,
+    on_move: &Rc<dyn Fn(FactoryMove)>,
+) {
+    // Replace the controllers of a previous call to `enable_reorder_requests()`.
+    let previous =
+        unsafe { widget.steal_data::<(gtk::DragSource, gtk::DropTarget)>(CONTROLLERS_KEY) };
+    if let Some((drag_source, drop_target)) = previous {
+        widget.remove_controller(&drag_source);
+        widget.remove_controller(&drop_target);
+    }
+
+    let drag_source = gtk::DragSource::builder()
+        .actions(gdk::DragAction::MOVE)
+        .build();
+
+    drag_source.connect_prepare({
+        let index = index.clone();
+        let dragged = dragged.clone();
+        move |_, _, _| {
+            dragged.replace(Some(index.clone()));
+            let from = index.current_index() as u64;
+            Some(gdk::ContentProvider::for_value(&from.to_value()))
+        }
+    });
+
+    // Show the element itself while it's dragged.
+    drag_source.connect_drag_begin(|source, _| {
+        if let Some(widget) = source.widget() {
+            let paintable = gtk::WidgetPaintable::new(Some(&widget));
+            source.set_icon(Some(&paintable), 0, 0);
+        }
+    });
+
+    drag_source.connect_drag_end({
+        let dragged = dragged.clone();
+        move |_, _, _| {
+            dragged.replace(None);
+        }
+    });
+
+    widget.add_controller(drag_source.clone());
+
+    let drop_target = gtk::DropTarget::new(u64::static_type(), gdk::DragAction::MOVE);
+
+    // Mark the element under the pointer, so it shows the drop indicator.
+    drop_target.connect_enter({
+        let dragged = dragged.clone();
+        move |target, _, _| {
+            if dragged.borrow().is_some() {
+                if let Some(widget) = target.widget() {
+                    widget.add_css_class(DROP_TARGET_CLASS);
+                }
+                gdk::DragAction::MOVE
+            } else {
+                gdk::DragAction::empty()
+            }
+        }
+    });
+
+    drop_target.connect_leave(|target| {
+        if let Some(widget) = target.widget() {
+            widget.remove_css_class(DROP_TARGET_CLASS);
+        }
+    });
+
+    drop_target.connect_drop({
+        let index = index.clone();
+        let dragged = dragged.clone();
+        let on_move = on_move.clone();
+        move |target, _: &glib::Value, _, _| {
+            if let Some(widget) = target.widget() {
+                widget.remove_css_class(DROP_TARGET_CLASS);
+            }
+
+            let Some(from) = dragged.take() else {
+                return false;
+            };
+
+            let from = from.current_index();
+            let to = index.current_index();
+            if from != to {
+                on_move(FactoryMove { from, to });
+            }
+            true
+        }
+    });
+
+    widget.add_controller(drop_target.clone());
+
+    unsafe { widget.set_data(CONTROLLERS_KEY, (drag_source, drop_target)) };
+}
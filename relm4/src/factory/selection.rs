@@ -0,0 +1,99 @@
+//! Mapping the selection of a factory back to its elements.
+
+use std::fmt;
+
+use gtk::glib;
+use gtk::prelude::{Cast, IsA, ObjectExt};
+
+use crate::Sender;
+use crate::factory::{DynamicIndex, FactoryView, SelectableFactoryView};
+
+/// The key used to store the [`DynamicIndex`] on the returned widget.
+const INDEX_KEY: &str = "relm4-factory-selection-index";
+
+type Attach<W> = Box<dyn Fn(&W, &DynamicIndex)>;
+
+/// Sends a message every time the selection of a factory changed.
+///
+/// The signal handler is disconnected when this is dropped.
+pub(crate) struct SelectionChanged<W> {
+    attach: Attach<W>,
+    widget: gtk::Widget,
+    handler: Option<glib::SignalHandlerId>,
+}
+
+impl<W> fmt::Debug for SelectionChanged<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelectionChanged")
+            .field("widget", &self.widget)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W> SelectionChanged<W> {
+    pub(crate) fn new<V, Msg, F>(view: &V, sender: &Sender<Msg>, f: F) -> Self
+    where
+        V: SelectableFactoryView<ReturnedWidget = W>,
+        W: IsA<glib::Object>,
+        Msg: 'static,
+        F: Fn(Vec<usize>) -> Msg + 'static,
+    {
+        let sender = sender.clone();
+        let handler = view.factory_connect_selection_changed(move |view| {
+            sender.emit(f(selected_indices(view)));
+        });
+
+        Self {
+            attach: Box::new(|widget, index| {
+                unsafe { widget.set_data(INDEX_KEY, index.clone()) };
+            }),
+            widget: view.upcast_ref::<gtk::Widget>().clone(),
+            handler: Some(handler),
+        }
+    }
+
+    /// Allows mapping the returned widget of an element back to the element.
+    pub(crate) fn attach(&self, widget: &W, index: &DynamicIndex) {
+        (self.attach)(widget, index);
+    }
+}
+
+impl<W> Drop for SelectionChanged<W> {
+    fn drop(&mut self) {
+        if let Some(handler) = self.handler.take() {
+            self.widget.disconnect(handler);
+        }
+    }
+}
+
+/// Returns the sorted indices of the elements whose widgets are selected.
+///
+/// Widgets that don't belong to an element of the factory are skipped,
+/// as are widgets of removed elements that are still animated.
+fn selected_indices<V: SelectableFactoryView>(view: &V) -> Vec<usize> {
+    let mut indices: Vec<usize> = view
+        .factory_selected()
+        .iter()
+        .filter_map(|widget| {
+            let index = unsafe { widget.data::<DynamicIndex>(INDEX_KEY) }?;
+            let index = unsafe { index.as_ref() };
+            (!index.is_removed()).then(|| index.current_index())
+        })
+        .collect();
+    indices.sort_unstable();
+    indices
+}
+
+/// Returns the indices of the elements whose returned widgets are selected.
+pub(crate) fn selected_positions<'a, V, I>(view: &V, widgets: I) -> Vec<usize>
+where
+    V: SelectableFactoryView,
+    I: IntoIterator<Item = Option<&'a <V as FactoryView>::ReturnedWidget>>,
+{
+    widgets
+        .into_iter()
+        .enumerate()
+        .filter(|(_, widget)| widget.is_some_and(|widget| view.factory_is_selected(widget)))
+        .map(|(index, _)| index)
+        .collect()
+}
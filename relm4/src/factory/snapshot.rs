@@ -0,0 +1,104 @@
+use std::vec;
+
+/// A snapshot of the elements of a factory collection.
+///
+/// The snapshot stores the initialization data of each element,
+/// retrieved with [`CloneableFactoryComponent::get_init()`](super::CloneableFactoryComponent::get_init).
+/// With the `serde` feature, it's serialized as a plain sequence, so it can
+/// be stored to restore a session or passed to another window.
+///
+/// Create a snapshot with [`FactoryVecDeque::snapshot()`](super::FactoryVecDeque::snapshot)
+/// or [`FactoryHashMap::snapshot()`](super::FactoryHashMap::snapshot) and
+/// restore it with the `restore` methods of the collections.
+///
+/// # Example
+///
+/// ```ignore
+/// // Store the open documents
+/// let snapshot = self.documents.snapshot();
+/// let json = serde_json::to_string(&snapshot)?;
+///
+/// // Open the documents again
+/// let snapshot = serde_json::from_str(&json)?;
+/// self.documents.guard().restore(snapshot);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct FactorySnapshot<T> {
+    elements: Vec<T>,
+}
+
+impl<T> Default for FactorySnapshot<T> {
+    fn default() -> Self {
+        Self {
+            elements: Vec::new(),
+        }
+    }
+}
+
+impl<T> FactorySnapshot<T> {
+    /// Returns the number of elements in the snapshot.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns [`true`] if the snapshot contains no elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Returns an iterator over the elements of the snapshot.
+    #[must_use]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &T> {
+        self.elements.iter()
+    }
+}
+
+impl<T> From<Vec<T>> for FactorySnapshot<T> {
+    fn from(elements: Vec<T>) -> Self {
+        Self { elements }
+    }
+}
+
+impl<T> From<FactorySnapshot<T>> for Vec<T> {
+    fn from(snapshot: FactorySnapshot<T>) -> Self {
+        snapshot.elements
+    }
+}
+
+impl<T> FromIterator<T> for FactorySnapshot<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            elements: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> IntoIterator for FactorySnapshot<T> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.into_iter()
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use super::FactorySnapshot;
+
+    #[test]
+    fn serialize_as_sequence() {
+        let snapshot: FactorySnapshot<(u8, String)> =
+            vec![(1, "first".to_owned()), (2, "second".to_owned())].into();
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(json, r#"[[1,"first"],[2,"second"]]"#);
+
+        let restored: FactorySnapshot<(u8, String)> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, snapshot);
+    }
+}
@@ -1,8 +1,10 @@
 use super::{FactoryComponent, FactoryHandle};
 
+use crate::channel::OutputTarget;
+use crate::context::Context;
 use crate::factory::{DataGuard, FactorySender, FactoryView};
 use crate::shutdown::ShutdownSender;
-use crate::{GuardedReceiver, Receiver, Sender, shutdown};
+use crate::{GuardedReceiver, Receiver, shutdown};
 
 use std::any;
 
@@ -16,10 +18,15 @@ pub(super) struct FactoryBuilder<C: FactoryComponent> {
     pub(super) input_receiver: Receiver<C::Input>,
     pub(super) cmd_receiver: Receiver<C::CommandOutput>,
     pub(super) shutdown_notifier: ShutdownSender,
+    pub(super) context: Context,
 }
 
 impl<C: FactoryComponent> FactoryBuilder<C> {
-    pub(super) fn new(index: &C::Index, init: C::Init, output_sender: Sender<C::Output>) -> Self {
+    pub(super) fn new(
+        index: &C::Index,
+        init: C::Init,
+        output_sender: impl Into<OutputTarget<C::Output>>,
+    ) -> Self {
         // Used for all events to be processed by this component's internal service.
         let (input_sender, input_receiver) = crate::channel::<C::Input>();
 
@@ -33,7 +40,10 @@ impl<C: FactoryComponent> FactoryBuilder<C> {
         let component_sender =
             FactorySender::new(input_sender, output_sender, cmd_sender, shutdown_receiver);
 
-        let data = Box::new(C::init_model(init, index, component_sender.clone()));
+        // Elements inherit the context of the component that created them.
+        let context = Context::new_child();
+
+        let data = Box::new(context.enter(|| C::init_model(init, index, component_sender.clone())));
         let root_widget = data.init_root();
 
         Self {
@@ -43,6 +53,7 @@ impl<C: FactoryComponent> FactoryBuilder<C> {
             input_receiver,
             cmd_receiver,
             shutdown_notifier,
+            context,
         }
     }
 
@@ -59,20 +70,26 @@ impl<C: FactoryComponent> FactoryBuilder<C> {
             input_receiver,
             cmd_receiver,
             shutdown_notifier,
+            context,
         } = self;
 
         // Gets notifications when a component's model and view is updated externally.
         let (notifier, notifier_receiver) = crate::channel();
 
-        let widgets = Box::new(data.init_widgets(
-            index,
-            root_widget.clone(),
-            &returned_widget,
-            component_sender.clone(),
-        ));
+        let widgets = Box::new(context.enter(|| {
+            data.init_widgets(
+                index,
+                root_widget.clone(),
+                &returned_widget,
+                component_sender.clone(),
+            )
+        }));
 
         let input_sender = component_sender.input_sender().clone();
-        let output_sender = component_sender.output_sender().clone();
+        let output_sender = {
+            let component_sender = component_sender.clone();
+            move || component_sender.output_sender().clone()
+        };
 
         // Spawns the component's service. It will receive both `Self::Input` and
         // `Self::CommandOutput` messages. It will spawn commands as requested by
@@ -83,7 +100,7 @@ impl<C: FactoryComponent> FactoryBuilder<C> {
             shutdown_notifier,
             output_sender,
             |mut model, mut widgets| {
-                async move {
+                context.scope(async move {
                     let mut notifier = GuardedReceiver::new(notifier_receiver);
                     let mut cmd = GuardedReceiver::new(cmd_receiver);
                     let mut input = GuardedReceiver::new(input_receiver);
@@ -122,7 +139,7 @@ impl<C: FactoryComponent> FactoryBuilder<C> {
                             }
                         );
                     }
-                }
+                })
             },
             C::shutdown,
         );
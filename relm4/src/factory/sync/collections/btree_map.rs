@@ -0,0 +1,343 @@
+use crate::Sender;
+
+use crate::factory::sync::handle::FactoryHandle;
+use crate::factory::transition::{FactoryTransition, Transition};
+use crate::factory::{CloneableFactoryComponent, FactoryComponent, FactoryView};
+
+use super::hashmap::FactoryElementGuard;
+use super::map::{self, MapConnector, MapTransition};
+
+use std::collections::BTreeMap;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops;
+
+use gtk::prelude::IsA;
+
+#[derive(Debug)]
+/// A builder-pattern struct for building a [`FactoryBTreeMap`].
+pub struct FactoryBTreeMapBuilder<K, C: FactoryComponent> {
+    component: PhantomData<C>,
+    key: PhantomData<K>,
+}
+
+impl<K, C> Default for FactoryBTreeMapBuilder<K, C>
+where
+    C: FactoryComponent,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, C> FactoryBTreeMapBuilder<K, C>
+where
+    C: FactoryComponent,
+    C::ParentWidget: Default,
+{
+    #[must_use]
+    /// Launch the factory with a default parent widget.
+    pub fn launch_default(self) -> FactoryBTreeMapConnector<K, C> {
+        self.launch(Default::default())
+    }
+}
+
+impl<K, C> FactoryBTreeMapBuilder<K, C>
+where
+    C: FactoryComponent,
+{
+    /// Creates a new [`FactoryBTreeMapBuilder`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            component: PhantomData,
+            key: PhantomData,
+        }
+    }
+
+    /// Launch the factory.
+    /// This is similar to [`Connector::launch`](crate::component::ComponentBuilder::launch).
+    pub fn launch(self, widget: C::ParentWidget) -> FactoryBTreeMapConnector<K, C> {
+        FactoryBTreeMapConnector {
+            inner: MapConnector::new(widget),
+            _key: self.key,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Second stage of the builder-pattern for building a [`FactoryBTreeMap`].
+pub struct FactoryBTreeMapConnector<K, C>
+where
+    C: FactoryComponent,
+{
+    inner: MapConnector<C>,
+    _key: PhantomData<K>,
+}
+
+impl<K, C> FactoryBTreeMapConnector<K, C>
+where
+    C: FactoryComponent,
+{
+    /// Forwards output events to the designated sender.
+    pub fn forward<F, Msg>(self, sender_: &Sender<Msg>, f: F) -> FactoryBTreeMap<K, C>
+    where
+        F: Fn(C::Output) -> Msg + Send + 'static,
+        C::Output: Send,
+        Msg: Send + 'static,
+    {
+        let (widget, output_sender) = self.inner.forward(sender_, f);
+
+        FactoryBTreeMap {
+            widget,
+            output_sender,
+            inner: BTreeMap::new(),
+            transition: None,
+        }
+    }
+
+    /// Ignore outputs from the component and finish the builder.
+    pub fn detach(self) -> FactoryBTreeMap<K, C> {
+        let (widget, output_sender) = self.inner.detach();
+
+        FactoryBTreeMap {
+            widget,
+            output_sender,
+            inner: BTreeMap::new(),
+            transition: None,
+        }
+    }
+}
+
+/// A container similar to [`BTreeMap`] that can be used to store
+/// values of type [`FactoryComponent`].
+///
+/// The widgets of the elements are always sorted by their keys.
+#[derive(Debug)]
+pub struct FactoryBTreeMap<K, C: FactoryComponent> {
+    widget: C::ParentWidget,
+    output_sender: Sender<C::Output>,
+    inner: BTreeMap<K, FactoryHandle<C>>,
+    transition: Option<MapTransition<C>>,
+}
+
+impl<K, C> Drop for FactoryBTreeMap<K, C>
+where
+    C: FactoryComponent,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K, C> ops::Index<&K> for FactoryBTreeMap<K, C>
+where
+    C: FactoryComponent<Index = K>,
+    K: Ord,
+{
+    type Output = C;
+
+    fn index(&self, key: &K) -> &Self::Output {
+        self.get(key).expect("Called `get` on an invalid key")
+    }
+}
+
+impl<K, C> FactoryBTreeMap<K, C>
+where
+    C: FactoryComponent,
+{
+    /// Creates a new [`FactoryBTreeMap`].
+    #[must_use]
+    pub fn builder() -> FactoryBTreeMapBuilder<K, C> {
+        FactoryBTreeMapBuilder::new()
+    }
+
+    /// Returns the number of elements in the [`FactoryBTreeMap`].
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the [`FactoryBTreeMap`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Send clone of a message to all of the elements.
+    pub fn broadcast(&self, msg: C::Input)
+    where
+        C::Input: Clone,
+    {
+        self.inner.values().for_each(|c| c.input.emit(msg.clone()));
+    }
+
+    /// Returns the widget all components are attached to.
+    pub const fn widget(&self) -> &C::ParentWidget {
+        &self.widget
+    }
+
+    /// An iterator visiting all key-value pairs, sorted by key.
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (&K, &C)> + ExactSizeIterator + FusedIterator {
+        self.inner.iter().map(|(k, c)| (k, c.data.get()))
+    }
+
+    /// Returns an iterator over the factory components, sorted by key.
+    pub fn values(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &C> + ExactSizeIterator + FusedIterator {
+        self.inner.values().map(|c| c.data.get())
+    }
+
+    /// Returns an iterator over the sorted keys of the map.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator + FusedIterator {
+        self.inner.keys()
+    }
+
+    /// Clears the map, removing all factory components.
+    ///
+    /// The elements are removed without a [`FactoryTransition`].
+    pub fn clear(&mut self) {
+        map::clear(
+            &self.widget,
+            self.transition.as_ref(),
+            std::mem::take(&mut self.inner).into_values(),
+        );
+    }
+}
+
+impl<K, C> FactoryBTreeMap<K, C>
+where
+    C: FactoryComponent<Index = K>,
+    K: Ord,
+{
+    /// Creates a [`FactoryBTreeMap`] from a [`Vec`].
+    pub fn from_vec(component_vec: Vec<(K, C::Init)>, widget: C::ParentWidget) -> Self {
+        let mut output = Self::builder().launch(widget).detach();
+        for (key, init) in component_vec {
+            output.insert(key, init);
+        }
+        output
+    }
+
+    /// Returns the first key-value pair in the map.
+    pub fn first_key_value(&self) -> Option<(&K, &C)> {
+        self.inner.first_key_value().map(|(k, c)| (k, c.data.get()))
+    }
+
+    /// Returns the last key-value pair in the map.
+    pub fn last_key_value(&self) -> Option<(&K, &C)> {
+        self.inner.last_key_value().map(|(k, c)| (k, c.data.get()))
+    }
+
+    /// Send a message to one of the elements.
+    pub fn send(&self, key: &K, msg: C::Input) {
+        self.inner[key].input.emit(msg);
+    }
+
+    /// Tries to get an immutable reference to
+    /// the model of one element.
+    ///
+    /// Returns [`None`] if `key` is invalid.
+    pub fn get(&self, key: &K) -> Option<&C> {
+        self.inner.get(key).map(|c| c.data.get())
+    }
+
+    /// Tries to get a mutable reference to
+    /// the model of one element.
+    ///
+    /// Returns [`None`] if `key` is invalid.
+    pub fn get_mut(&mut self, key: &K) -> Option<FactoryElementGuard<'_, C>> {
+        self.inner
+            .get_mut(key)
+            .map(|c| FactoryElementGuard { inner: c })
+    }
+
+    /// Inserts a new factory component into the map.
+    /// Its widget is inserted after the widget of the previous key.
+    ///
+    /// If the map did not have this key present, None is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old value is returned.
+    pub fn insert(&mut self, key: K, init: C::Init) -> Option<C> {
+        let existing = self.remove(&key);
+
+        let previous = self.inner.range(..&key).next_back();
+        let component = map::launch(
+            &key,
+            init,
+            self.output_sender.clone(),
+            self.transition.as_ref(),
+            |root, position| match previous {
+                Some((_, previous)) => {
+                    self.widget
+                        .factory_insert_after(root, position, &previous.returned_widget)
+                }
+                None => self.widget.factory_prepend(root, position),
+            },
+        );
+
+        assert!(self.inner.insert(key, component).is_none());
+
+        existing
+    }
+
+    /// Removes a key from the map, returning the factory component at the key if the key was previously in the map.
+    pub fn remove(&mut self, key: &K) -> Option<C> {
+        self.inner
+            .remove(key)
+            .map(|handle| map::remove(&self.widget, self.transition.as_ref(), handle))
+    }
+}
+
+impl<K, C> FactoryBTreeMap<K, C>
+where
+    C: FactoryComponent,
+    C::Root: IsA<gtk::Widget>,
+{
+    /// Animates elements when they are inserted or removed.
+    ///
+    /// Removed elements are faded out before their widgets are removed from the parent widget.
+    /// Elements are only animated while the parent widget is shown.
+    /// The widgets of fading elements are removed right away if the parent widget is hidden,
+    /// the map is cleared or dropped, or the transition is replaced.
+    pub fn set_transition(&mut self, transition: Option<FactoryTransition>)
+    where
+        <C::ParentWidget as FactoryView>::ReturnedWidget: 'static,
+    {
+        self.transition = transition.map(|transition| Transition::new(&self.widget, transition));
+    }
+}
+
+/// Implements the Clone Trait for [`FactoryBTreeMap`] if the component implements [`CloneableFactoryComponent`].
+impl<K, C> Clone for FactoryBTreeMap<K, C>
+where
+    C: CloneableFactoryComponent,
+    K: Clone + Ord,
+    C: FactoryComponent<Index = K>,
+{
+    fn clone(&self) -> Self {
+        let mut clone = FactoryBTreeMap::builder()
+            .launch(self.widget.clone())
+            .detach();
+        for (k, item) in self.iter() {
+            let init = C::get_init(item);
+            clone.insert(k.clone(), init);
+        }
+        clone
+    }
+}
+
+impl<K, C> FactoryBTreeMap<K, C>
+where
+    C: FactoryComponent,
+    K: Ord,
+    C::ParentWidget: IsA<gtk::Stack>,
+    C::Root: IsA<gtk::Widget>,
+{
+    /// Makes the element at a given key visible in a [`gtk::Stack`].
+    /// Returns [`true`] on success, otherwise [`false`].
+    pub fn set_visible(&self, key: &K) -> bool {
+        map::set_visible(&self.widget, self.inner.get(key))
+    }
+}
@@ -1,8 +1,11 @@
-use crate::{Receiver, Sender};
+use crate::Sender;
 
-use crate::factory::sync::builder::FactoryBuilder;
+use crate::factory::indexed_output::IndexedOutput;
 use crate::factory::sync::handle::FactoryHandle;
-use crate::factory::{CloneableFactoryComponent, FactoryComponent, FactoryView};
+use crate::factory::transition::{FactoryTransition, Transition};
+use crate::factory::{CloneableFactoryComponent, FactoryComponent, FactorySnapshot, FactoryView};
+
+use super::map::{self, MapConnector, MapTransition};
 
 use std::collections::HashMap;
 use std::collections::hash_map::RandomState;
@@ -19,7 +22,7 @@ pub struct FactoryElementGuard<'a, C>
 where
     C: FactoryComponent,
 {
-    inner: &'a mut FactoryHandle<C>,
+    pub(super) inner: &'a mut FactoryHandle<C>,
 }
 
 impl<C> ops::Deref for FactoryElementGuard<'_, C>
@@ -110,12 +113,8 @@ where
     pub fn launch(self, widget: C::ParentWidget) -> FactoryHashMapConnector<K, C> {
         let Self { hasher, key, .. } = self;
 
-        let (output_sender, output_receiver) = crate::channel();
-
         FactoryHashMapConnector {
-            widget,
-            output_sender,
-            output_receiver,
+            inner: MapConnector::new(widget),
             hasher,
             _key: key,
         }
@@ -128,9 +127,7 @@ pub struct FactoryHashMapConnector<K, C, S = RandomState>
 where
     C: FactoryComponent,
 {
-    widget: C::ParentWidget,
-    output_sender: Sender<C::Output>,
-    output_receiver: Receiver<C::Output>,
+    inner: MapConnector<C>,
     hasher: S,
     _key: PhantomData<K>,
 }
@@ -146,55 +143,75 @@ where
         C::Output: Send,
         Msg: Send + 'static,
     {
-        let Self {
-            widget,
-            output_sender,
-            output_receiver,
-            hasher,
-            ..
-        } = self;
-
-        let sender_clone = sender_.clone();
-
-        crate::spawn(async move {
-            while let Some(msg) = output_receiver.recv().await {
-                if sender_clone.send(f(msg)).is_err() {
-                    break;
-                }
-            }
-        });
+        let Self { inner, hasher, .. } = self;
+        let (widget, output_sender) = inner.forward(sender_, f);
 
         FactoryHashMap {
             widget,
             output_sender,
             inner: HashMap::with_hasher(hasher),
+            next_order: 0,
+            transition: None,
+            indexed_output: None,
         }
     }
 
+    /// Forwards output events to the designated sender
+    /// together with the key of the element that sent them.
+    ///
+    /// This way, components don't need to include their key in their outputs.
+    /// Outputs sent with [`FactorySender::output()`](crate::FactorySender::output) are forwarded right away,
+    /// so they arrive in the order they were sent.
+    ///
+    /// Outputs can still arrive after the element was removed
+    /// or replaced by another element with the same key.
+    pub fn forward_with_key<F, Msg>(self, sender_: &Sender<Msg>, f: F) -> FactoryHashMap<K, C>
+    where
+        F: Fn(K, C::Output) -> Msg + Send + Sync + 'static,
+        K: Clone + Send + Sync + 'static,
+        C::Output: Send,
+        Msg: Send + 'static,
+    {
+        let mut factory = self.detach();
+        factory.indexed_output = Some(IndexedOutput::new(sender_, f));
+        factory
+    }
+
     /// Ignore outputs from the component and finish the builder.
     pub fn detach(self) -> FactoryHashMap<K, C> {
-        let Self {
-            widget,
-            output_sender,
-            hasher,
-            ..
-        } = self;
+        let Self { inner, hasher, .. } = self;
+        let (widget, output_sender) = inner.detach();
 
         FactoryHashMap {
             widget,
             output_sender,
             inner: HashMap::with_hasher(hasher),
+            next_order: 0,
+            transition: None,
+            indexed_output: None,
         }
     }
 }
 
+/// An element of a [`FactoryHashMap`].
+#[derive(Debug)]
+struct Element<C: FactoryComponent> {
+    handle: FactoryHandle<C>,
+    /// Elements are always appended, so this is the order of their widgets.
+    order: u64,
+}
+
 /// A container similar to [`HashMap`] that can be used to store
 /// values of type [`FactoryComponent`].
 #[derive(Debug)]
 pub struct FactoryHashMap<K, C: FactoryComponent, S = RandomState> {
     widget: C::ParentWidget,
     output_sender: Sender<C::Output>,
-    inner: HashMap<K, FactoryHandle<C>, S>,
+    inner: HashMap<K, Element<C>, S>,
+    /// The order of the next inserted element.
+    next_order: u64,
+    transition: Option<MapTransition<C>>,
+    indexed_output: Option<IndexedOutput<K, C::Output>>,
 }
 
 impl<K, C, S> Drop for FactoryHashMap<K, C, S>
@@ -249,7 +266,9 @@ where
     where
         C::Input: Clone,
     {
-        self.inner.values().for_each(|c| c.input.emit(msg.clone()));
+        self.inner
+            .values()
+            .for_each(|c| c.handle.input.emit(msg.clone()));
     }
 
     /// Returns the widget all components are attached to.
@@ -259,12 +278,12 @@ where
 
     /// An iterator visiting all key-value pairs in arbitrary order.
     pub fn iter(&self) -> impl ExactSizeIterator<Item = (&K, &C)> + FusedIterator {
-        self.inner.iter().map(|(k, c)| (k, c.data.get()))
+        self.inner.iter().map(|(k, c)| (k, c.handle.data.get()))
     }
 
     /// Returns an iterator over the factory components.
     pub fn values(&self) -> impl ExactSizeIterator<Item = &C> + FusedIterator {
-        self.inner.values().map(|c| c.data.get())
+        self.inner.values().map(|c| c.handle.data.get())
     }
 
     /// Returns an iterator over the keys of the hash map.
@@ -273,10 +292,14 @@ where
     }
 
     /// Clears the map, removing all factory components.
+    ///
+    /// The elements are removed without a [`FactoryTransition`].
     pub fn clear(&mut self) {
-        for (_, handle) in self.inner.drain() {
-            self.widget.factory_remove(&handle.returned_widget);
-        }
+        map::clear(
+            &self.widget,
+            self.transition.as_ref(),
+            self.inner.drain().map(|(_, element)| element.handle),
+        );
     }
 }
 
@@ -303,7 +326,7 @@ where
 {
     /// Send a mage to one of the elements.
     pub fn send(&self, key: &K, msg: C::Input) {
-        self.inner[key].input.emit(msg);
+        self.inner[key].handle.input.emit(msg);
     }
 
     /// Tries to get an immutable reference to
@@ -311,7 +334,7 @@ where
     ///
     /// Returns [`None`] if `key` is invalid.
     pub fn get(&self, key: &K) -> Option<&C> {
-        self.inner.get(key).map(|c| c.data.get())
+        self.inner.get(key).map(|c| c.handle.data.get())
     }
 
     /// Tries to get a mutable reference to
@@ -319,9 +342,9 @@ where
     ///
     /// Returns [`None`] if `key` is invalid.
     pub fn get_mut(&mut self, key: &K) -> Option<FactoryElementGuard<'_, C>> {
-        self.inner
-            .get_mut(key)
-            .map(|c| FactoryElementGuard { inner: c })
+        self.inner.get_mut(key).map(|c| FactoryElementGuard {
+            inner: &mut c.handle,
+        })
     }
 
     /// Inserts a new factory component into the map.
@@ -333,28 +356,52 @@ where
     pub fn insert(&mut self, key: K, init: C::Init) -> Option<C> {
         let existing = self.remove(&key);
 
-        let builder = FactoryBuilder::new(&key, init, self.output_sender.clone());
-
-        let position = C::position(&builder.data, &key);
-        let returned_widget = self
-            .widget
-            .factory_append(builder.root_widget.clone(), &position);
-
-        let component = builder.launch(&key, returned_widget);
+        let output_sender = match &self.indexed_output {
+            Some(indexed_output) => indexed_output.element_output(&key),
+            None => self.output_sender.clone().into(),
+        };
+        let component = map::launch(
+            &key,
+            init,
+            output_sender,
+            self.transition.as_ref(),
+            |root, position| self.widget.factory_append(root, position),
+        );
 
-        assert!(self.inner.insert(key, component).is_none());
+        let element = Element {
+            handle: component,
+            order: self.next_order,
+        };
+        self.next_order += 1;
+        assert!(self.inner.insert(key, element).is_none());
 
         existing
     }
 
     /// Removes a key from the map, returning the factory component at the key if the key was previously in the map.
     pub fn remove(&mut self, key: &K) -> Option<C> {
-        if let Some(handle) = self.inner.remove(key) {
-            self.widget.factory_remove(&handle.returned_widget);
-            Some(handle.data.into_inner())
-        } else {
-            None
-        }
+        self.inner
+            .remove(key)
+            .map(|element| map::remove(&self.widget, self.transition.as_ref(), element.handle))
+    }
+}
+
+impl<K, C, S> FactoryHashMap<K, C, S>
+where
+    C: FactoryComponent,
+    C::Root: IsA<gtk::Widget>,
+{
+    /// Animates elements when they are inserted or removed.
+    ///
+    /// Removed elements are faded out before their widgets are removed from the parent widget.
+    /// Elements are only animated while the parent widget is shown.
+    /// The widgets of fading elements are removed right away if the parent widget is hidden,
+    /// the map is cleared or dropped, or the transition is replaced.
+    pub fn set_transition(&mut self, transition: Option<FactoryTransition>)
+    where
+        <C::ParentWidget as FactoryView>::ReturnedWidget: 'static,
+    {
+        self.transition = transition.map(|transition| Transition::new(&self.widget, transition));
     }
 }
 
@@ -381,6 +428,47 @@ where
     }
 }
 
+impl<K, C, S> FactoryHashMap<K, C, S>
+where
+    C: CloneableFactoryComponent + FactoryComponent<Index = K>,
+    K: Clone + Hash + Eq,
+    S: BuildHasher,
+{
+    /// Creates a [`FactorySnapshot`] of all keys and elements
+    /// from [`CloneableFactoryComponent::get_init()`].
+    ///
+    /// The elements are stored in the order of their widgets, so
+    /// [`FactoryHashMap::restore()`] recreates the widgets in the same order.
+    #[must_use]
+    pub fn snapshot(&self) -> FactorySnapshot<(K, C::Init)> {
+        let mut elements: Vec<_> = self.inner.iter().collect();
+        elements.sort_unstable_by_key(|(_, element)| element.order);
+
+        elements
+            .into_iter()
+            .map(|(key, element)| (key.clone(), C::get_init(element.handle.data.get())))
+            .collect()
+    }
+}
+
+impl<K, C, S> FactoryHashMap<K, C, S>
+where
+    C: FactoryComponent<Index = K>,
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Replaces all elements with the elements of a [`FactorySnapshot`].
+    ///
+    /// If the snapshot contains a key more than once, the last element is kept.
+    pub fn restore(&mut self, snapshot: FactorySnapshot<(K, C::Init)>) {
+        self.clear();
+
+        for (key, init) in snapshot {
+            self.insert(key, init);
+        }
+    }
+}
+
 impl<K, C> FactoryHashMap<K, C, RandomState>
 where
     C: FactoryComponent,
@@ -391,13 +479,6 @@ where
     /// Makes the element at a given key visible in a [`gtk::Stack`].
     /// Returns [`true`] on success, otherwise [`false`].
     pub fn set_visible(&self, key: &K) -> bool {
-        if let Some(handle) = self.inner.get(key) {
-            self.widget
-                .as_ref()
-                .set_visible_child(handle.root_widget.as_ref());
-            true
-        } else {
-            false
-        }
+        map::set_visible(&self.widget, self.inner.get(key).map(|c| &c.handle))
     }
 }
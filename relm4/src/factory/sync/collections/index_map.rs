@@ -0,0 +1,405 @@
+use crate::Sender;
+
+use crate::factory::sync::handle::FactoryHandle;
+use crate::factory::transition::{FactoryTransition, Transition};
+use crate::factory::{CloneableFactoryComponent, FactoryComponent, FactoryView};
+
+use super::hashmap::FactoryElementGuard;
+use super::map::{self, MapConnector, MapTransition};
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops;
+
+use gtk::prelude::IsA;
+use indexmap::IndexMap;
+
+#[derive(Debug)]
+/// A builder-pattern struct for building a [`FactoryIndexMap`].
+pub struct FactoryIndexMapBuilder<K, C: FactoryComponent, S = RandomState> {
+    hasher: S,
+    component: PhantomData<C>,
+    key: PhantomData<K>,
+}
+
+impl<K, C> Default for FactoryIndexMapBuilder<K, C>
+where
+    C: FactoryComponent,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, C, S> FactoryIndexMapBuilder<K, C, S>
+where
+    C: FactoryComponent,
+    C::ParentWidget: Default,
+{
+    #[must_use]
+    /// Launch the factory with a default parent widget.
+    pub fn launch_default(self) -> FactoryIndexMapConnector<K, C, S> {
+        self.launch(Default::default())
+    }
+}
+
+impl<K, C> FactoryIndexMapBuilder<K, C>
+where
+    C: FactoryComponent,
+{
+    /// Creates a new [`FactoryIndexMapBuilder`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            hasher: RandomState::default(),
+            component: PhantomData,
+            key: PhantomData,
+        }
+    }
+}
+
+impl<K, C, S> FactoryIndexMapBuilder<K, C, S>
+where
+    C: FactoryComponent,
+{
+    /// Sets a different hasher.
+    pub fn hasher<H: BuildHasher>(self, hasher: H) -> FactoryIndexMapBuilder<K, C, H> {
+        let Self { component, key, .. } = self;
+
+        FactoryIndexMapBuilder {
+            hasher,
+            component,
+            key,
+        }
+    }
+
+    /// Launch the factory.
+    /// This is similar to [`Connector::launch`](crate::component::ComponentBuilder::launch).
+    pub fn launch(self, widget: C::ParentWidget) -> FactoryIndexMapConnector<K, C, S> {
+        let Self { hasher, key, .. } = self;
+
+        FactoryIndexMapConnector {
+            inner: MapConnector::new(widget),
+            hasher,
+            _key: key,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Second stage of the builder-pattern for building a [`FactoryIndexMap`].
+pub struct FactoryIndexMapConnector<K, C, S = RandomState>
+where
+    C: FactoryComponent,
+{
+    inner: MapConnector<C>,
+    hasher: S,
+    _key: PhantomData<K>,
+}
+
+impl<K, C, S> FactoryIndexMapConnector<K, C, S>
+where
+    C: FactoryComponent,
+{
+    /// Forwards output events to the designated sender.
+    pub fn forward<F, Msg>(self, sender_: &Sender<Msg>, f: F) -> FactoryIndexMap<K, C, S>
+    where
+        F: Fn(C::Output) -> Msg + Send + 'static,
+        C::Output: Send,
+        Msg: Send + 'static,
+    {
+        let Self { inner, hasher, .. } = self;
+        let (widget, output_sender) = inner.forward(sender_, f);
+
+        FactoryIndexMap {
+            widget,
+            output_sender,
+            inner: IndexMap::with_hasher(hasher),
+            transition: None,
+        }
+    }
+
+    /// Ignore outputs from the component and finish the builder.
+    pub fn detach(self) -> FactoryIndexMap<K, C, S> {
+        let Self { inner, hasher, .. } = self;
+        let (widget, output_sender) = inner.detach();
+
+        FactoryIndexMap {
+            widget,
+            output_sender,
+            inner: IndexMap::with_hasher(hasher),
+            transition: None,
+        }
+    }
+}
+
+/// A container similar to [`IndexMap`] that can be used to store
+/// values of type [`FactoryComponent`].
+///
+/// The widgets of the elements are kept in insertion order.
+#[derive(Debug)]
+pub struct FactoryIndexMap<K, C: FactoryComponent, S = RandomState> {
+    widget: C::ParentWidget,
+    output_sender: Sender<C::Output>,
+    inner: IndexMap<K, FactoryHandle<C>, S>,
+    transition: Option<MapTransition<C>>,
+}
+
+impl<K, C, S> Drop for FactoryIndexMap<K, C, S>
+where
+    C: FactoryComponent,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K, C, S> ops::Index<&K> for FactoryIndexMap<K, C, S>
+where
+    C: FactoryComponent<Index = K>,
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    type Output = C;
+
+    fn index(&self, key: &K) -> &Self::Output {
+        self.get(key).expect("Called `get` on an invalid key")
+    }
+}
+
+impl<K, C> FactoryIndexMap<K, C, RandomState>
+where
+    C: FactoryComponent,
+{
+    /// Creates a new [`FactoryIndexMap`].
+    #[must_use]
+    pub fn builder() -> FactoryIndexMapBuilder<K, C> {
+        FactoryIndexMapBuilder::new()
+    }
+}
+
+impl<K, C, S> FactoryIndexMap<K, C, S>
+where
+    C: FactoryComponent,
+{
+    /// Returns the number of elements in the [`FactoryIndexMap`].
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the [`FactoryIndexMap`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Send clone of a message to all of the elements.
+    pub fn broadcast(&self, msg: C::Input)
+    where
+        C::Input: Clone,
+    {
+        self.inner.values().for_each(|c| c.input.emit(msg.clone()));
+    }
+
+    /// Returns the widget all components are attached to.
+    pub const fn widget(&self) -> &C::ParentWidget {
+        &self.widget
+    }
+
+    /// An iterator visiting all key-value pairs in insertion order.
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (&K, &C)> + ExactSizeIterator + FusedIterator {
+        self.inner.iter().map(|(k, c)| (k, c.data.get()))
+    }
+
+    /// Returns an iterator over the factory components in insertion order.
+    pub fn values(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &C> + ExactSizeIterator + FusedIterator {
+        self.inner.values().map(|c| c.data.get())
+    }
+
+    /// Returns an iterator over the keys of the map in insertion order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator + FusedIterator {
+        self.inner.keys()
+    }
+
+    /// Returns the key-value pair at the given position.
+    ///
+    /// Returns [`None`] if `index` is out of bounds.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &C)> {
+        self.inner.get_index(index).map(|(k, c)| (k, c.data.get()))
+    }
+
+    /// Clears the map, removing all factory components.
+    ///
+    /// The elements are removed without a [`FactoryTransition`].
+    pub fn clear(&mut self) {
+        map::clear(
+            &self.widget,
+            self.transition.as_ref(),
+            self.inner.drain(..).map(|(_, handle)| handle),
+        );
+    }
+}
+
+impl<K, C> FactoryIndexMap<K, C, RandomState>
+where
+    C: FactoryComponent<Index = K>,
+    K: Hash + Eq,
+{
+    /// Creates a [`FactoryIndexMap`] from a [`Vec`].
+    pub fn from_vec(component_vec: Vec<(K, C::Init)>, widget: C::ParentWidget) -> Self {
+        let mut output = Self::builder().launch(widget).detach();
+        for (key, init) in component_vec {
+            output.insert(key, init);
+        }
+        output
+    }
+}
+
+impl<K, C, S> FactoryIndexMap<K, C, S>
+where
+    C: FactoryComponent<Index = K>,
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Send a message to one of the elements.
+    pub fn send(&self, key: &K, msg: C::Input) {
+        self.inner[key].input.emit(msg);
+    }
+
+    /// Tries to get an immutable reference to
+    /// the model of one element.
+    ///
+    /// Returns [`None`] if `key` is invalid.
+    pub fn get(&self, key: &K) -> Option<&C> {
+        self.inner.get(key).map(|c| c.data.get())
+    }
+
+    /// Tries to get a mutable reference to
+    /// the model of one element.
+    ///
+    /// Returns [`None`] if `key` is invalid.
+    pub fn get_mut(&mut self, key: &K) -> Option<FactoryElementGuard<'_, C>> {
+        self.inner
+            .get_mut(key)
+            .map(|c| FactoryElementGuard { inner: c })
+    }
+
+    /// Returns the position of a key in the map.
+    ///
+    /// Returns [`None`] if `key` is invalid.
+    pub fn get_index_of(&self, key: &K) -> Option<usize> {
+        self.inner.get_index_of(key)
+    }
+
+    /// Inserts a new factory component into the map.
+    ///
+    /// If the map did not have this key present, the element is appended and None is returned.
+    ///
+    /// If the map did have this key present, the value is updated at the same position
+    /// and the old value is returned.
+    pub fn insert(&mut self, key: K, init: C::Init) -> Option<C> {
+        let output_sender = self.output_sender.clone();
+
+        if let Some(index) = self.inner.get_index_of(&key) {
+            let previous = index.checked_sub(1).and_then(|i| self.inner.get_index(i));
+            let component = map::launch(
+                &key,
+                init,
+                output_sender,
+                self.transition.as_ref(),
+                |root, position| match previous {
+                    Some((_, previous)) => {
+                        self.widget
+                            .factory_insert_after(root, position, &previous.returned_widget)
+                    }
+                    None => self.widget.factory_prepend(root, position),
+                },
+            );
+            let existing = std::mem::replace(&mut self.inner[index], component);
+
+            Some(map::remove(
+                &self.widget,
+                self.transition.as_ref(),
+                existing,
+            ))
+        } else {
+            let component = map::launch(
+                &key,
+                init,
+                output_sender,
+                self.transition.as_ref(),
+                |root, position| self.widget.factory_append(root, position),
+            );
+            self.inner.insert(key, component);
+
+            None
+        }
+    }
+
+    /// Removes a key from the map, returning the factory component at the key if the key was previously in the map.
+    ///
+    /// The order of the remaining elements is preserved.
+    pub fn remove(&mut self, key: &K) -> Option<C> {
+        self.inner
+            .shift_remove(key)
+            .map(|handle| map::remove(&self.widget, self.transition.as_ref(), handle))
+    }
+}
+
+/// Implements the Clone Trait for [`FactoryIndexMap`] if the component implements [`CloneableFactoryComponent`].
+impl<K, C> Clone for FactoryIndexMap<K, C, RandomState>
+where
+    C: CloneableFactoryComponent,
+    K: Clone + Hash + Eq,
+    C: FactoryComponent<Index = K>,
+{
+    fn clone(&self) -> Self {
+        let mut clone = FactoryIndexMap::builder()
+            .launch(self.widget.clone())
+            .detach();
+        for (k, item) in self.iter() {
+            let init = C::get_init(item);
+            clone.insert(k.clone(), init);
+        }
+        clone
+    }
+}
+
+impl<K, C, S> FactoryIndexMap<K, C, S>
+where
+    C: FactoryComponent,
+    K: Hash + Eq,
+    S: BuildHasher,
+    C::ParentWidget: IsA<gtk::Stack>,
+    C::Root: IsA<gtk::Widget>,
+{
+    /// Makes the element at a given key visible in a [`gtk::Stack`].
+    /// Returns [`true`] on success, otherwise [`false`].
+    pub fn set_visible(&self, key: &K) -> bool {
+        map::set_visible(&self.widget, self.inner.get(key))
+    }
+}
+
+impl<K, C, S> FactoryIndexMap<K, C, S>
+where
+    C: FactoryComponent,
+    C::Root: IsA<gtk::Widget>,
+{
+    /// Animates elements when they are inserted or removed.
+    ///
+    /// Removed elements are faded out before their widgets are removed from the parent widget.
+    /// Elements are only animated while the parent widget is shown.
+    /// The widgets of fading elements are removed right away if the parent widget is hidden,
+    /// the map is cleared or dropped, or the transition is replaced.
+    pub fn set_transition(&mut self, transition: Option<FactoryTransition>)
+    where
+        <C::ParentWidget as FactoryView>::ReturnedWidget: 'static,
+    {
+        self.transition = transition.map(|transition| Transition::new(&self.widget, transition));
+    }
+}
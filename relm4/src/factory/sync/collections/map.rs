@@ -0,0 +1,165 @@
+//! Logic shared by the keyed factory collections.
+
+use crate::channel::OutputTarget;
+use crate::{Receiver, Sender};
+
+use crate::factory::sync::builder::FactoryBuilder;
+use crate::factory::sync::handle::FactoryHandle;
+use crate::factory::transition::Transition;
+use crate::factory::{FactoryComponent, FactoryView};
+
+use gtk::prelude::IsA;
+
+type Position<C> = <<C as FactoryComponent>::ParentWidget as FactoryView>::Position;
+type ReturnedWidget<C> = <<C as FactoryComponent>::ParentWidget as FactoryView>::ReturnedWidget;
+pub(super) type MapTransition<C> = Transition<<C as FactoryComponent>::Root, ReturnedWidget<C>>;
+
+/// Parent widget and output channel of a keyed collection
+/// whose outputs aren't connected yet.
+#[derive(Debug)]
+pub(super) struct MapConnector<C: FactoryComponent> {
+    widget: C::ParentWidget,
+    output_sender: Sender<C::Output>,
+    output_receiver: Receiver<C::Output>,
+}
+
+impl<C> MapConnector<C>
+where
+    C: FactoryComponent,
+{
+    pub(super) fn new(widget: C::ParentWidget) -> Self {
+        let (output_sender, output_receiver) = crate::channel();
+
+        Self {
+            widget,
+            output_sender,
+            output_receiver,
+        }
+    }
+
+    /// Forwards output events to the designated sender and
+    /// returns the parent widget and the output sender of the elements.
+    pub(super) fn forward<F, Msg>(
+        self,
+        sender_: &Sender<Msg>,
+        f: F,
+    ) -> (C::ParentWidget, Sender<C::Output>)
+    where
+        F: Fn(C::Output) -> Msg + Send + 'static,
+        C::Output: Send,
+        Msg: Send + 'static,
+    {
+        let Self {
+            widget,
+            output_sender,
+            output_receiver,
+        } = self;
+
+        let sender_clone = sender_.clone();
+
+        crate::spawn(async move {
+            while let Some(msg) = output_receiver.recv().await {
+                if sender_clone.send(f(msg)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        (widget, output_sender)
+    }
+
+    /// Ignores output events and returns the parent widget
+    /// and the output sender of the elements.
+    pub(super) fn detach(self) -> (C::ParentWidget, Sender<C::Output>) {
+        (self.widget, self.output_sender)
+    }
+}
+
+/// Launches the element of `key`.
+///
+/// `attach` adds the root widget to the parent widget at the right place.
+pub(super) fn launch<C, F>(
+    key: &C::Index,
+    init: C::Init,
+    output: impl Into<OutputTarget<C::Output>>,
+    transition: Option<&MapTransition<C>>,
+    attach: F,
+) -> FactoryHandle<C>
+where
+    C: FactoryComponent,
+    F: FnOnce(C::Root, &Position<C>) -> ReturnedWidget<C>,
+{
+    let builder = FactoryBuilder::new(key, init, output);
+
+    let position = C::position(&builder.data, key);
+    let returned_widget = attach(builder.root_widget.clone(), &position);
+
+    let handle = builder.launch(key, returned_widget);
+    if let Some(transition) = transition {
+        transition.enter(&handle.root_widget);
+    }
+    handle
+}
+
+/// Removes the widget of an element and returns its model.
+///
+/// With a transition, the widget is only removed after the animation is finished.
+pub(super) fn remove<C>(
+    widget: &C::ParentWidget,
+    transition: Option<&MapTransition<C>>,
+    handle: FactoryHandle<C>,
+) -> C
+where
+    C: FactoryComponent,
+{
+    let FactoryHandle {
+        data,
+        root_widget,
+        returned_widget,
+        ..
+    } = handle;
+    let (data, widgets) = data.into_parts();
+
+    if let Some(transition) = transition {
+        transition.exit(&root_widget, returned_widget, widgets);
+    } else {
+        widget.factory_remove(&returned_widget);
+    }
+    data
+}
+
+/// Removes the widgets of all elements right away and drops them,
+/// including the widgets of removed elements that are still animated.
+pub(super) fn clear<C, I>(
+    widget: &C::ParentWidget,
+    transition: Option<&MapTransition<C>>,
+    handles: I,
+) where
+    C: FactoryComponent,
+    I: IntoIterator<Item = FactoryHandle<C>>,
+{
+    if let Some(transition) = transition {
+        transition.finish();
+    }
+    for handle in handles {
+        widget.factory_remove(&handle.returned_widget);
+    }
+}
+
+/// Makes the widget of an element visible in a [`gtk::Stack`].
+/// Returns [`true`] on success, otherwise [`false`].
+pub(super) fn set_visible<C>(widget: &C::ParentWidget, handle: Option<&FactoryHandle<C>>) -> bool
+where
+    C: FactoryComponent,
+    C::ParentWidget: IsA<gtk::Stack>,
+    C::Root: IsA<gtk::Widget>,
+{
+    if let Some(handle) = handle {
+        widget
+            .as_ref()
+            .set_visible_child(handle.root_widget.as_ref());
+        true
+    } else {
+        false
+    }
+}
@@ -1,9 +1,18 @@
 //! Containers similar to [`std::collections`] that can be used to store factory data.
 
+mod btree_map;
 mod hashmap;
+mod index_map;
+mod map;
+#[cfg(test)]
+mod tests;
+mod tree;
 mod vec_deque;
 
+pub use btree_map::{FactoryBTreeMap, FactoryBTreeMapBuilder, FactoryBTreeMapConnector};
 pub use hashmap::{FactoryHashMap, FactoryHashMapBuilder, FactoryHashMapConnector};
+pub use index_map::{FactoryIndexMap, FactoryIndexMapBuilder, FactoryIndexMapConnector};
+pub use tree::{FactoryTree, FactoryTreeBuilder, FactoryTreeConnector};
 pub use vec_deque::{
     FactoryVecDeque, FactoryVecDequeBuilder, FactoryVecDequeConnector, FactoryVecDequeGuard,
 };
@@ -13,6 +22,8 @@ use crate::factory::DynamicIndex;
 #[derive(Debug)]
 struct RenderedState {
     uid: usize,
+    /// The position of the element when it was rendered.
+    position: usize,
     #[cfg(feature = "libadwaita")]
     widget_hash: u64,
 }
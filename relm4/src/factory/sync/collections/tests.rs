@@ -0,0 +1,836 @@
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk::prelude::{
+    Cast, GtkWindowExt, IsA, ListBoxRowExt, ListModelExtManual, ObjectExt, ToValue, WidgetExt,
+};
+use gtk::{gdk, glib};
+
+use crate::RelmIterChildrenExt;
+use crate::factory::reorder::DROP_TARGET_CLASS;
+use crate::factory::{
+    CloneableFactoryComponent, DynamicIndex, FactoryComponent, FactoryMove, FactorySender,
+    FactoryTransition, FactoryView, TreeIndex,
+};
+
+use super::{FactoryBTreeMap, FactoryHashMap, FactoryIndexMap, FactoryTree, FactoryVecDeque};
+
+/// Returns all children of `container` in widget order.
+fn children(container: &gtk::Box) -> Vec<gtk::Widget> {
+    container.iter_children().collect()
+}
+
+/// Returns the labels of all children of `container` in widget order.
+fn labels(container: &gtk::Box) -> Vec<String> {
+    container
+        .iter_children()
+        .map(|child| child.downcast::<gtk::Label>().unwrap().label().into())
+        .collect()
+}
+
+/// Returns the visibility of all children of `container` in widget order.
+fn visible(container: &gtk::Box) -> Vec<bool> {
+    container
+        .iter_children()
+        .map(|child| child.is_visible())
+        .collect()
+}
+
+/// Shows the key of map elements in front of the value of the element.
+trait Label {
+    fn label(&self, value: u8) -> String {
+        value.to_string()
+    }
+}
+
+impl Label for DynamicIndex {}
+
+impl Label for TreeIndex {}
+
+impl Label for char {
+    fn label(&self, value: u8) -> String {
+        format!("{self}{value}")
+    }
+}
+
+/// The element of all collections in these tests.
+///
+/// It keeps its sender so tests can send outputs on its behalf
+/// and sets the `dropped` flag once its widgets are dropped.
+#[derive(Debug)]
+struct Element<P, I>
+where
+    P: FactoryView<Children = gtk::Widget, Position = ()> + 'static,
+    I: Label + Clone + fmt::Debug + 'static,
+{
+    value: u8,
+    index: I,
+    marked: bool,
+    dropped: Rc<Cell<bool>>,
+    sender: FactorySender<Self>,
+}
+
+impl<P, I> FactoryComponent for Element<P, I>
+where
+    P: FactoryView<Children = gtk::Widget, Position = ()> + 'static,
+    I: Label + Clone + fmt::Debug + 'static,
+{
+    type ParentWidget = P;
+    type CommandOutput = ();
+    type Input = ();
+    type Output = u8;
+    type Init = u8;
+    type Root = gtk::Label;
+    type Widgets = DropFlag;
+    type Index = I;
+
+    fn init_model(value: Self::Init, index: &I, sender: FactorySender<Self>) -> Self {
+        Self {
+            value,
+            index: index.clone(),
+            marked: false,
+            dropped: Rc::default(),
+            sender,
+        }
+    }
+
+    fn init_root(&self) -> Self::Root {
+        gtk::Label::new(Some(&self.index.label(self.value)))
+    }
+
+    fn init_widgets(
+        &mut self,
+        _: &I,
+        _: Self::Root,
+        _: &<Self::ParentWidget as FactoryView>::ReturnedWidget,
+        _: FactorySender<Self>,
+    ) -> Self::Widgets {
+        DropFlag(self.dropped.clone())
+    }
+}
+
+impl<P, I> CloneableFactoryComponent for Element<P, I>
+where
+    P: FactoryView<Children = gtk::Widget, Position = ()> + 'static,
+    I: Label + Clone + fmt::Debug + 'static,
+{
+    fn get_init(&self) -> Self::Init {
+        self.value
+    }
+}
+
+/// Sets the flag once the widgets of an element are dropped.
+#[derive(Debug)]
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+/// The element of the vector tests that only need a [`gtk::Box`].
+type Item = Element<gtk::Box, DynamicIndex>;
+
+/// Asserts that the values and the indices of the models and
+/// the order of the widgets are the same.
+fn assert_values(factory: &FactoryVecDeque<Item>, values: &[u8]) {
+    let models: Vec<u8> = factory.iter().map(|item| item.value).collect();
+    assert_eq!(models, values);
+
+    let labels: Vec<String> = values.iter().map(ToString::to_string).collect();
+    assert_eq!(self::labels(factory.widget()), labels);
+
+    for (position, item) in factory.iter().enumerate() {
+        assert_eq!(item.index.current_index(), position);
+    }
+}
+
+fn vec_deque() -> FactoryVecDeque<Item> {
+    FactoryVecDeque::builder().launch_default().detach()
+}
+
+#[gtk::test]
+fn vec_deque_reconcile() {
+    let mut factory = vec_deque();
+
+    factory
+        .guard()
+        .reconcile([1, 2, 3], |value| *value, |_, _| None);
+    assert_values(&factory, &[1, 2, 3]);
+    let widgets = children(factory.widget());
+    let removed_index = factory[1].index.clone();
+
+    // Reuse, reorder, remove and add elements.
+    let mut updated = Vec::new();
+    factory.guard().reconcile(
+        [3, 4, 1],
+        |value| *value,
+        |model, init| {
+            updated.push((model.value, init));
+            None
+        },
+    );
+    assert_values(&factory, &[3, 4, 1]);
+    assert_eq!(updated, [(3, 3), (1, 1)]);
+
+    let reconciled = children(factory.widget());
+    assert_eq!(reconciled[0], widgets[2]);
+    assert_eq!(reconciled[2], widgets[0]);
+    assert!(!widgets.contains(&reconciled[1]));
+    assert!(widgets[1].parent().is_none());
+    assert!(removed_index.is_removed());
+
+    // Removing all items removes all elements.
+    factory.guard().reconcile([], |value| *value, |_, _| None);
+    assert_values(&factory, &[]);
+    assert!(reconciled.iter().all(|widget| widget.parent().is_none()));
+}
+
+#[gtk::test]
+fn vec_deque_reconcile_duplicate_keys() {
+    let mut factory = vec_deque();
+
+    factory.guard().reconcile([5], |value| *value, |_, _| None);
+    let widgets = children(factory.widget());
+
+    // Only the first item with a key reuses the element.
+    factory
+        .guard()
+        .reconcile([5, 5], |value| *value, |_, _| None);
+    assert_values(&factory, &[5, 5]);
+    let duplicated = children(factory.widget());
+    assert_eq!(duplicated[0], widgets[0]);
+    assert_ne!(duplicated[1], widgets[0]);
+
+    // Both elements have a key, but only the first one is reused.
+    factory.guard().reconcile([5], |value| *value, |_, _| None);
+    assert_values(&factory, &[5]);
+    assert_eq!(children(factory.widget()), [duplicated[0].clone()]);
+    assert!(duplicated[1].parent().is_none());
+}
+
+#[gtk::test]
+fn vec_deque_reconcile_without_keys() {
+    let mut factory = vec_deque();
+
+    factory
+        .guard()
+        .reconcile([1, 2], |value| *value, |_, _| None);
+    factory.guard().push_back(3);
+    let widgets = children(factory.widget());
+
+    // Elements added without a key are always recreated.
+    factory
+        .guard()
+        .reconcile([1, 2, 3], |value| *value, |_, _| None);
+    assert_values(&factory, &[1, 2, 3]);
+    let reconciled = children(factory.widget());
+    assert_eq!(reconciled[..2], widgets[..2]);
+    assert_ne!(reconciled[2], widgets[2]);
+
+    // Keys of another type never match.
+    factory
+        .guard()
+        .reconcile([1, 2, 3], |value| u32::from(*value), |_, _| None);
+    assert_values(&factory, &[1, 2, 3]);
+    let retyped = children(factory.widget());
+    assert!(retyped.iter().all(|widget| !reconciled.contains(widget)));
+
+    // Cleared factories don't match old keys.
+    factory.guard().clear();
+    factory.guard().push_back(1);
+    factory
+        .guard()
+        .reconcile([1], |value| u32::from(*value), |_, _| None);
+    assert_values(&factory, &[1]);
+}
+
+#[gtk::test]
+fn vec_deque_sort() {
+    let mut factory = vec_deque();
+    factory.extend([3, 1, 2, 1]);
+    let indices: Vec<DynamicIndex> = factory.iter().map(|item| item.index.clone()).collect();
+    let widgets = children(factory.widget());
+
+    // The sort is stable.
+    factory.guard().sort_by_key(|item| item.value);
+    assert_values(&factory, &[1, 1, 2, 3]);
+    let positions: Vec<usize> = indices.iter().map(DynamicIndex::current_index).collect();
+    assert_eq!(positions, [3, 0, 2, 1]);
+    assert_eq!(
+        children(factory.widget()),
+        [1, 3, 2, 0].map(|position| widgets[position].clone())
+    );
+
+    factory
+        .guard()
+        .sort_by(|first, second| second.value.cmp(&first.value));
+    assert_values(&factory, &[3, 2, 1, 1]);
+    let positions: Vec<usize> = indices.iter().map(DynamicIndex::current_index).collect();
+    assert_eq!(positions, [0, 2, 1, 3]);
+    assert_eq!(
+        children(factory.widget()),
+        [0, 2, 1, 3].map(|position| widgets[position].clone())
+    );
+}
+
+#[gtk::test]
+fn vec_deque_bulk_removal() {
+    let mut factory = vec_deque();
+    factory.extend(0..8);
+    let indices: Vec<DynamicIndex> = factory.iter().map(|item| item.index.clone()).collect();
+
+    factory.guard().retain(|item| item.value % 2 == 0);
+    assert_values(&factory, &[0, 2, 4, 6]);
+    assert!(indices[1].is_removed());
+    assert_eq!(indices[6].current_index(), 3);
+
+    let drained: Vec<u8> = factory.guard().drain(1..3).map(|item| item.value).collect();
+    assert_eq!(drained, [2, 4]);
+    assert_values(&factory, &[0, 6]);
+    assert!(indices[4].is_removed());
+
+    // Draining is eager, so dropping the iterator doesn't keep the elements.
+    drop(factory.guard().drain(..1));
+    assert_values(&factory, &[6]);
+    assert!(indices[0].is_removed());
+
+    factory.extend([6, 7, 7, 6]);
+    factory.guard().dedup_by_key(|item| item.value);
+    assert_values(&factory, &[6, 7, 6]);
+
+    factory.guard().truncate(5);
+    assert_values(&factory, &[6, 7, 6]);
+    factory.guard().truncate(1);
+    assert_values(&factory, &[6]);
+    assert_eq!(indices[6].current_index(), 0);
+}
+
+#[gtk::test]
+fn vec_deque_append() {
+    let mut factory = vec_deque();
+    let mut other = vec_deque();
+    factory.extend([1, 2]);
+    other.extend([3]);
+
+    let moved_index = other[0].index.clone();
+    other.guard().get_mut(0).unwrap().marked = true;
+
+    // Appended elements are recreated from their init value.
+    factory.guard().append(&mut other);
+    assert_values(&factory, &[1, 2, 3]);
+    assert!(!factory[2].marked);
+    assert!(moved_index.is_removed());
+    assert!(other.is_empty());
+    assert!(labels(other.widget()).is_empty());
+}
+
+/// Returns all controllers of type `T` of `widget`.
+fn controllers<T: IsA<glib::Object>>(widget: &gtk::Widget) -> Vec<T> {
+    widget
+        .observe_controllers()
+        .iter::<glib::Object>()
+        .filter_map(|controller| controller.ok()?.downcast().ok())
+        .collect()
+}
+
+/// Simulates dragging the child at `from` onto the child at `to`.
+fn drag(container: &gtk::Box, from: usize, to: usize) {
+    let widgets = children(container);
+    let [drag_source] = &controllers::<gtk::DragSource>(&widgets[from])[..] else {
+        panic!("Expected exactly one drag source");
+    };
+    let [drop_target] = &controllers::<gtk::DropTarget>(&widgets[to])[..] else {
+        panic!("Expected exactly one drop target");
+    };
+
+    drag_source.emit_by_name::<Option<gdk::ContentProvider>>("prepare", &[&0.0, &0.0]);
+
+    // The element under the pointer shows the drop indicator until the drop.
+    drop_target.emit_by_name::<gdk::DragAction>("enter", &[&0.0, &0.0]);
+    assert!(widgets[to].has_css_class(DROP_TARGET_CLASS));
+    drop_target.emit_by_name::<bool>("drop", &[&0_u64.to_value(), &0.0, &0.0]);
+    assert!(!widgets[to].has_css_class(DROP_TARGET_CLASS));
+}
+
+#[gtk::test]
+fn vec_deque_reorder() {
+    let (sender, receiver) = crate::channel();
+    let mut factory = vec_deque();
+    factory.extend([0, 1, 2]);
+
+    // Enabling reordering again replaces the previous controllers.
+    factory.enable_reorder_requests(&sender, |_| FactoryMove { from: 0, to: 0 });
+    factory.enable_reorder_requests(&sender, |moved| moved);
+    factory.guard().push_back(3);
+
+    drag(factory.widget(), 0, 2);
+    let moved = receiver.recv_sync().unwrap();
+    assert_eq!(moved, FactoryMove { from: 0, to: 2 });
+
+    factory.guard().move_to(moved.from, moved.to);
+    assert_values(&factory, &[1, 2, 0, 3]);
+
+    // The indices of the controllers follow the elements.
+    drag(factory.widget(), 3, 2);
+    assert_eq!(receiver.recv_sync(), Some(FactoryMove { from: 3, to: 2 }));
+
+    // Dropping an element on itself doesn't send a message.
+    drag(factory.widget(), 1, 1);
+    drag(factory.widget(), 2, 0);
+    assert_eq!(receiver.recv_sync(), Some(FactoryMove { from: 2, to: 0 }));
+}
+
+#[gtk::test]
+fn vec_deque_selection() {
+    let mut factory = FactoryVecDeque::<Element<gtk::ListBox, DynamicIndex>>::builder()
+        .launch_default()
+        .detach();
+    factory.extend([0, 1, 2, 3]);
+
+    // Reverse the rows and add a row that doesn't belong to the factory,
+    // so the positions of the rows don't match the indices of the elements.
+    let list_box = factory.widget().clone();
+    list_box.set_sort_func(|first, second| {
+        let label = |row: &gtk::ListBoxRow| {
+            row.child()
+                .and_then(|child| child.downcast::<gtk::Label>().ok())
+                .map(|label| label.label())
+        };
+        label(second).cmp(&label(first)).into()
+    });
+    let foreign_row = gtk::ListBoxRow::new();
+    list_box.append(&foreign_row);
+    factory.set_selection_mode(gtk::SelectionMode::Multiple);
+
+    let (first_sender, first_receiver) = crate::channel();
+    let (sender, receiver) = crate::channel();
+    factory.connect_selection_changed(&first_sender, |selected| selected);
+    factory.connect_selection_changed(&sender, |selected| selected);
+
+    factory.select(1);
+    assert_eq!(receiver.recv_sync(), Some(vec![1]));
+    list_box.select_row(Some(&foreign_row));
+    assert_eq!(receiver.recv_sync(), Some(vec![1]));
+    factory.select(3);
+    assert_eq!(receiver.recv_sync(), Some(vec![1, 3]));
+
+    assert!(foreign_row.is_selected());
+    assert_eq!(factory.selected(), [1, 3]);
+    let selected: Vec<usize> = factory
+        .selected_dynamic_indices()
+        .iter()
+        .map(DynamicIndex::current_index)
+        .collect();
+    assert_eq!(selected, [1, 3]);
+
+    // Removing an element updates the indices of the selection.
+    factory.guard().remove(0);
+    assert_eq!(factory.selected(), [0, 2]);
+    factory.guard().remove(2);
+    assert_eq!(receiver.recv_sync(), Some(vec![0]));
+    assert_eq!(factory.selected(), [0]);
+
+    // The previous handler was replaced.
+    assert!(first_receiver.0.try_recv().is_err());
+
+    // The handler is disconnected when the factory is dropped.
+    drop(factory);
+    list_box.unselect_all();
+    assert!(receiver.0.try_recv().is_err());
+}
+
+#[gtk::test]
+fn hashmap_snapshot_restore() {
+    let mut map = FactoryHashMap::<char, Element<gtk::Box, char>>::builder()
+        .launch_default()
+        .detach();
+    for (key, value) in [('d', 0), ('a', 1), ('c', 2), ('b', 3)] {
+        map.insert(key, value);
+    }
+
+    // Replaced elements are appended, so the widgets aren't sorted by the keys.
+    map.insert('a', 4);
+    assert!(map.remove(&'c').is_some());
+    assert_eq!(labels(map.widget()), ["d0", "b3", "a4"]);
+
+    // The snapshot is in widget order.
+    let snapshot = map.snapshot();
+    assert!(snapshot.iter().copied().eq([('d', 0), ('b', 3), ('a', 4)]));
+
+    // Snapshots survive serialization, e.g. to restore a session.
+    #[cfg(feature = "serde")]
+    let snapshot: crate::factory::FactorySnapshot<(char, u8)> =
+        serde_json::from_str(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+
+    let mut restored = FactoryHashMap::<char, Element<gtk::Box, char>>::builder()
+        .launch_default()
+        .detach();
+    restored.insert('x', 9);
+    restored.restore(snapshot.clone());
+    assert_eq!(labels(restored.widget()), ["d0", "b3", "a4"]);
+    assert_eq!(restored.snapshot(), snapshot);
+}
+
+#[gtk::test]
+fn btree_map_key_order() {
+    let mut map = FactoryBTreeMap::<char, Element<gtk::Box, char>>::builder()
+        .launch_default()
+        .detach();
+
+    assert!(map.insert('c', 0).is_none());
+    assert!(map.insert('a', 0).is_none());
+    assert!(map.insert('d', 0).is_none());
+    assert!(map.insert('b', 0).is_none());
+    assert_eq!(labels(map.widget()), ["a0", "b0", "c0", "d0"]);
+
+    // Replacing an element keeps it at the position of its key.
+    assert_eq!(map.insert('c', 1).unwrap().value, 0);
+    assert_eq!(map.insert('a', 1).unwrap().value, 0);
+    assert_eq!(labels(map.widget()), ["a1", "b0", "c1", "d0"]);
+
+    assert_eq!(map.remove(&'b').unwrap().value, 0);
+    assert!(map.remove(&'b').is_none());
+    assert_eq!(labels(map.widget()), ["a1", "c1", "d0"]);
+    assert_eq!(map.first_key_value().unwrap().0, &'a');
+    assert_eq!(map.last_key_value().unwrap().0, &'d');
+
+    map.clear();
+    assert!(map.is_empty());
+    assert!(labels(map.widget()).is_empty());
+}
+
+#[gtk::test]
+fn index_map_insertion_order() {
+    let mut map = FactoryIndexMap::<char, Element<gtk::Box, char>>::builder()
+        .launch_default()
+        .detach();
+
+    assert!(map.insert('c', 0).is_none());
+    assert!(map.insert('a', 0).is_none());
+    assert!(map.insert('d', 0).is_none());
+    assert!(map.insert('b', 0).is_none());
+    assert_eq!(labels(map.widget()), ["c0", "a0", "d0", "b0"]);
+
+    // Replacing an element keeps its position.
+    assert_eq!(map.insert('c', 1).unwrap().value, 0);
+    assert_eq!(map.insert('d', 1).unwrap().value, 0);
+    assert_eq!(labels(map.widget()), ["c1", "a0", "d1", "b0"]);
+    assert_eq!(map.get_index_of(&'d'), Some(2));
+
+    // Removing an element preserves the order of the others.
+    assert_eq!(map.remove(&'a').unwrap().value, 0);
+    assert_eq!(labels(map.widget()), ["c1", "d1", "b0"]);
+    assert_eq!(map.get_index(1).unwrap().0, &'d');
+    assert!(map.keys().copied().eq(['c', 'd', 'b']));
+
+    map.clear();
+    assert!(map.is_empty());
+    assert!(labels(map.widget()).is_empty());
+}
+
+#[gtk::test]
+fn tree_insert_remove_move() {
+    let mut tree = FactoryTree::<Element<gtk::Box, TreeIndex>>::builder()
+        .autoexpand(true)
+        .launch_default()
+        .detach();
+
+    let index0 = tree.append(&[], 0);
+    let index1 = tree.append(&[], 1);
+    let index10 = tree.append(&[0], 10);
+    let index11 = tree.append(&[0], 11);
+    let index20 = tree.append(&[1], 20);
+    let index9 = tree.insert(&[0, 0], 9);
+
+    assert_eq!(tree.len(), 6);
+    assert_eq!(tree.children_len(&[0]), Some(3));
+    assert_eq!(labels(tree.widget()), ["0", "9", "10", "11", "1", "20"]);
+    assert_eq!(index9.current_path(), [0, 0]);
+    assert_eq!(index10.current_path(), [0, 1]);
+    assert_eq!(index11.current_path(), [0, 2]);
+
+    // Move a node to another parent.
+    tree.move_to(&[0, 2], &[1, 0]);
+    assert_eq!(labels(tree.widget()), ["0", "9", "10", "1", "11", "20"]);
+    assert_eq!(index11.current_path(), [1, 0]);
+    assert_eq!(index11.parent().unwrap().current_path(), [1]);
+    assert_eq!(index20.current_path(), [1, 1]);
+    assert_eq!(tree.get(&[1, 0]).unwrap().value, 11);
+
+    // Move a subtree behind its former sibling.
+    tree.move_to(&[0], &[1]);
+    assert_eq!(labels(tree.widget()), ["1", "11", "20", "0", "9", "10"]);
+    assert_eq!(index0.current_path(), [1]);
+    assert_eq!(index1.current_path(), [0]);
+    assert_eq!(index9.current_path(), [1, 0]);
+    assert_eq!(index10.current_path(), [1, 1]);
+    assert_eq!(index9.depth(), 1);
+
+    // Move a node to the top level.
+    tree.move_to(&[1, 1], &[0]);
+    assert_eq!(labels(tree.widget()), ["10", "1", "11", "20", "0", "9"]);
+    assert_eq!(index10.current_path(), [0]);
+    assert_eq!(index10.parent(), None);
+    assert_eq!(index1.current_path(), [1]);
+    assert_eq!(index0.current_path(), [2]);
+
+    // Removing a node removes its descendants.
+    assert_eq!(tree.remove(&[1]).unwrap().value, 1);
+    assert_eq!(labels(tree.widget()), ["10", "0", "9"]);
+    assert_eq!(index0.current_path(), [1]);
+    assert_eq!(index9.current_path(), [1, 0]);
+    assert_eq!(tree.len(), 3);
+    assert!(index1.is_removed());
+    assert!(index11.is_removed());
+    assert!(index20.is_removed());
+    assert!(!index0.is_removed());
+
+    // Stale indices don't equal the node that is now at their path.
+    assert_eq!(index1.current_path(), index0.current_path());
+    assert_ne!(index1, index0);
+    assert_eq!(index0, index0.clone());
+
+    assert!(tree.remove(&[5]).is_none());
+    assert!(tree.remove(&[1, 1]).is_none());
+    assert!(tree.remove(&[]).is_none());
+
+    tree.clear();
+    assert!(tree.is_empty());
+    assert!(labels(tree.widget()).is_empty());
+    assert!(index10.is_removed());
+    assert!(index9.is_removed());
+}
+
+#[gtk::test]
+fn tree_collapsed_subtrees() {
+    let mut tree = FactoryTree::<Element<gtk::Box, TreeIndex>>::builder()
+        .launch_default()
+        .detach();
+
+    tree.append(&[], 0);
+    tree.append(&[0], 10);
+    tree.append(&[0, 0], 100);
+    tree.append(&[], 1);
+    assert_eq!(visible(tree.widget()), [true, false, false, true]);
+
+    assert!(tree.set_expanded(&[0], true));
+    assert_eq!(visible(tree.widget()), [true, true, false, true]);
+
+    assert!(tree.set_expanded(&[0, 0], true));
+    assert_eq!(visible(tree.widget()), [true, true, true, true]);
+
+    // Collapsing a node hides all descendants, but keeps their state.
+    assert!(tree.set_expanded(&[0], false));
+    assert_eq!(visible(tree.widget()), [true, false, false, true]);
+    assert!(tree.is_expanded(&[0, 0]));
+
+    // New nodes and moved subtrees inherit the visibility of their parent.
+    tree.append(&[0, 0], 101);
+    assert_eq!(visible(tree.widget()), [true, false, false, false, true]);
+
+    tree.move_to(&[0, 0], &[1, 0]);
+    assert_eq!(labels(tree.widget()), ["0", "1", "10", "100", "101"]);
+    assert_eq!(visible(tree.widget()), [true, true, false, false, false]);
+
+    assert!(tree.set_expanded(&[1], true));
+    assert_eq!(visible(tree.widget()), [true, true, true, true, true]);
+
+    // Invalid paths are rejected without panicking.
+    assert!(!tree.set_expanded(&[], true));
+    assert!(!tree.set_expanded(&[5], true));
+    assert!(!tree.set_expanded(&[5, 0], true));
+    assert!(!tree.set_expanded(&[1, 0, 7], true));
+    assert!(!tree.is_expanded(&[5, 0]));
+}
+
+/// Shows `widget` in a window and waits until it is mapped,
+/// so the transitions of factories are played.
+fn show(window: &gtk::Window, widget: &impl IsA<gtk::Widget>) {
+    window.set_child(Some(widget));
+    window.present();
+    while !widget.is_mapped() {
+        glib::MainContext::default().iteration(true);
+    }
+}
+
+/// A transition that takes much longer than the tests.
+const TRANSITION: FactoryTransition = FactoryTransition::Fade(Duration::from_secs(60));
+
+#[gtk::test]
+fn vec_deque_transition() {
+    let mut factory = FactoryVecDeque::<Element<gtk::ListBox, DynamicIndex>>::builder()
+        .launch_default()
+        .detach();
+    factory.set_transition(Some(TRANSITION));
+    let list_box = factory.widget().clone();
+
+    // Elements of hidden parent widgets are removed right away.
+    factory.extend([0, 1, 2]);
+    factory.guard().remove(0);
+    assert_eq!(list_box.iter_children().count(), 2);
+
+    let window = gtk::Window::new();
+    show(&window, &list_box);
+    factory.set_selection_mode(gtk::SelectionMode::Multiple);
+    let (sender, receiver) = crate::channel();
+    factory.connect_selection_changed(&sender, |selected| selected);
+
+    // Removed elements fade out, but the factory ignores them.
+    let ghost: gtk::ListBoxRow = list_box.iter_children().next().unwrap().downcast().unwrap();
+    assert_eq!(factory.guard().remove(0).unwrap().value, 1);
+    assert_eq!(list_box.iter_children().count(), 2);
+    assert_eq!(ghost.parent().as_ref(), Some(list_box.upcast_ref()));
+    assert!(!ghost.child().unwrap().can_target());
+
+    list_box.select_all();
+    assert!(ghost.is_selected());
+    assert_eq!(receiver.recv_sync(), Some(vec![0]));
+    assert_eq!(factory.selected(), [0]);
+
+    // Hiding the parent widget finishes the transitions.
+    window.set_visible(false);
+    assert_eq!(list_box.iter_children().count(), 1);
+    assert!(ghost.parent().is_none());
+
+    // Clearing and dropping the factory removes fading elements right away.
+    show(&window, &list_box);
+    factory.guard().push_back(3);
+    factory.guard().remove(0);
+    assert_eq!(list_box.iter_children().count(), 2);
+    factory.guard().clear();
+    assert_eq!(list_box.iter_children().count(), 0);
+
+    factory.extend([4, 5]);
+    factory.guard().remove(0);
+    assert_eq!(list_box.iter_children().count(), 2);
+    drop(factory);
+    assert_eq!(list_box.iter_children().count(), 0);
+
+    window.destroy();
+}
+
+#[gtk::test]
+fn hashmap_transition() {
+    let mut map = FactoryHashMap::<char, Element<gtk::Box, char>>::builder()
+        .launch_default()
+        .detach();
+    map.set_transition(Some(TRANSITION));
+    let container = map.widget().clone();
+    let window = gtk::Window::new();
+    show(&window, &container);
+
+    map.insert('a', 0);
+    map.insert('b', 1);
+    let dropped = map.get(&'a').unwrap().dropped.clone();
+
+    // The model is returned right away, but the widgets are kept until the element faded out.
+    assert_eq!(map.remove(&'a').unwrap().value, 0);
+    assert_eq!(labels(&container), ["a0", "b1"]);
+    assert!(!dropped.get());
+
+    // Hiding the parent widget finishes the transitions.
+    window.set_visible(false);
+    assert_eq!(labels(&container), ["b1"]);
+    assert!(dropped.get());
+
+    // Clearing the map removes fading elements right away.
+    show(&window, &container);
+    map.insert('c', 2);
+    assert!(map.remove(&'b').is_some());
+    assert_eq!(labels(&container), ["b1", "c2"]);
+    map.clear();
+    assert!(labels(&container).is_empty());
+
+    window.destroy();
+}
+
+#[gtk::test]
+fn btree_map_transition() {
+    let mut map = FactoryBTreeMap::<char, Element<gtk::Box, char>>::builder()
+        .launch_default()
+        .detach();
+    map.set_transition(Some(TRANSITION));
+    let container = map.widget().clone();
+    let window = gtk::Window::new();
+    show(&window, &container);
+
+    map.insert('b', 0);
+    map.insert('c', 0);
+    let dropped = map.get(&'b').unwrap().dropped.clone();
+
+    // New elements are inserted next to the fading ones.
+    assert!(map.remove(&'b').is_some());
+    map.insert('a', 0);
+    assert_eq!(labels(&container), ["a0", "b0", "c0"]);
+    assert!(!dropped.get());
+
+    // Dropping the map removes fading elements right away.
+    drop(map);
+    assert!(labels(&container).is_empty());
+    assert!(dropped.get());
+
+    window.destroy();
+}
+
+#[gtk::test]
+fn vec_deque_forward_with_index() {
+    let (sender, receiver) = crate::channel();
+    let mut factory = FactoryVecDeque::<Item>::builder()
+        .launch_default()
+        .forward_with_index(&sender, |index, output| (index, output));
+    factory.extend([0, 0, 0]);
+
+    let received = || {
+        let (index, output) = receiver.recv_sync().unwrap();
+        (index.current_index(), output)
+    };
+
+    // Outputs arrive in the order they were sent.
+    factory[2].sender.output(0).unwrap();
+    factory[0].sender.output(1).unwrap();
+    factory[2].sender.output(2).unwrap();
+    assert_eq!(received(), (2, 0));
+    assert_eq!(received(), (0, 1));
+    assert_eq!(received(), (2, 2));
+
+    // The output sender forwards the outputs, too.
+    factory[1].sender.output_sender().emit(3);
+    assert_eq!(received(), (1, 3));
+
+    // The index follows the element.
+    factory.guard().move_front(2);
+    factory[0].sender.output(4).unwrap();
+    assert_eq!(received(), (0, 4));
+
+    // Outputs of removed elements can still arrive.
+    let removed = factory.guard().remove(1).unwrap();
+    removed.sender.output(5).unwrap();
+    let (index, output) = receiver.recv_sync().unwrap();
+    assert!(index.is_removed());
+    assert_eq!(output, 5);
+
+    // Outputs are returned once the closed channel was noticed.
+    drop(receiver);
+    assert_eq!(factory[0].sender.output(6), Ok(()));
+    assert_eq!(factory[0].sender.output(7), Err(7));
+    assert_eq!(factory[1].sender.output(8), Err(8));
+}
+
+#[gtk::test]
+fn hashmap_forward_with_key() {
+    let (sender, receiver) = crate::channel();
+    let mut map = FactoryHashMap::<char, Element<gtk::Box, char>>::builder()
+        .launch_default()
+        .forward_with_key(&sender, |key, output| (key, output));
+    map.insert('a', 0);
+    map.insert('b', 0);
+
+    map.get(&'b').unwrap().sender.output(0).unwrap();
+    map.get(&'a').unwrap().sender.output(1).unwrap();
+    assert_eq!(receiver.recv_sync(), Some(('b', 0)));
+    assert_eq!(receiver.recv_sync(), Some(('a', 1)));
+}
@@ -0,0 +1,614 @@
+use crate::{Receiver, Sender};
+
+use crate::factory::sync::builder::FactoryBuilder;
+use crate::factory::sync::handle::FactoryHandle;
+use crate::factory::{FactoryComponent, FactoryView, TreeIndex};
+
+use super::hashmap::FactoryElementGuard;
+
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+
+use gtk::prelude::{IsA, WidgetExt};
+
+/// The default indentation of one level in pixels.
+const DEFAULT_INDENT: i32 = 16;
+
+type ReturnedWidget<C> = <<C as FactoryComponent>::ParentWidget as FactoryView>::ReturnedWidget;
+
+#[derive(Debug)]
+struct TreeNode<C: FactoryComponent> {
+    index: TreeIndex,
+    handle: FactoryHandle<C>,
+    expanded: bool,
+    children: Vec<TreeNode<C>>,
+}
+
+impl<C: FactoryComponent> TreeNode<C> {
+    /// Returns the last node of this subtree in pre-order.
+    fn last_descendant(&self) -> &Self {
+        let mut node = self;
+        while let Some(last) = node.children.last() {
+            node = last;
+        }
+        node
+    }
+
+    /// Removes the widgets of this subtree and marks its indices as removed.
+    fn remove_widgets(&self, widget: &C::ParentWidget) {
+        widget.factory_remove(&self.handle.returned_widget);
+        self.index.set_removed();
+        for child in &self.children {
+            child.remove_widgets(widget);
+        }
+    }
+}
+
+impl<C> TreeNode<C>
+where
+    C: FactoryComponent,
+    ReturnedWidget<C>: IsA<gtk::Widget>,
+{
+    /// Shows or hides the widgets of this subtree.
+    fn set_shown(&self, shown: bool) {
+        self.handle.returned_widget.set_visible(shown);
+        for child in &self.children {
+            child.set_shown(shown && self.expanded);
+        }
+    }
+}
+
+fn find_children<'a, C: FactoryComponent>(
+    roots: &'a [TreeNode<C>],
+    parent: &[usize],
+) -> Option<&'a [TreeNode<C>]> {
+    let mut siblings = roots;
+    for index in parent {
+        siblings = &siblings.get(*index)?.children;
+    }
+    Some(siblings)
+}
+
+fn find_node<'a, C: FactoryComponent>(
+    roots: &'a [TreeNode<C>],
+    path: &[usize],
+) -> Option<&'a TreeNode<C>> {
+    let (index, parent) = path.split_last()?;
+    find_children(roots, parent)?.get(*index)
+}
+
+fn find_children_mut<'a, C: FactoryComponent>(
+    roots: &'a mut Vec<TreeNode<C>>,
+    parent: &[usize],
+) -> Option<&'a mut Vec<TreeNode<C>>> {
+    let mut siblings = roots;
+    for index in parent {
+        siblings = &mut siblings.get_mut(*index)?.children;
+    }
+    Some(siblings)
+}
+
+fn find_node_mut<'a, C: FactoryComponent>(
+    roots: &'a mut Vec<TreeNode<C>>,
+    path: &[usize],
+) -> Option<&'a mut TreeNode<C>> {
+    let (index, parent) = path.split_last()?;
+    find_children_mut(roots, parent)?.get_mut(*index)
+}
+
+#[derive(Debug)]
+/// A builder-pattern struct for building a [`FactoryTree`].
+pub struct FactoryTreeBuilder<C: FactoryComponent> {
+    indent: i32,
+    autoexpand: bool,
+    component: PhantomData<C>,
+}
+
+impl<C> Default for FactoryTreeBuilder<C>
+where
+    C: FactoryComponent,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> FactoryTreeBuilder<C>
+where
+    C: FactoryComponent,
+    C::ParentWidget: Default,
+{
+    #[must_use]
+    /// Launch the factory with a default parent widget.
+    pub fn launch_default(self) -> FactoryTreeConnector<C> {
+        self.launch(Default::default())
+    }
+}
+
+impl<C> FactoryTreeBuilder<C>
+where
+    C: FactoryComponent,
+{
+    /// Creates a new [`FactoryTreeBuilder`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            indent: DEFAULT_INDENT,
+            autoexpand: false,
+            component: PhantomData,
+        }
+    }
+
+    /// Sets the indentation of each level of the tree in pixels.
+    ///
+    /// The default is 16 pixels.
+    #[must_use]
+    pub fn indent(mut self, indent: i32) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Sets whether new nodes are expanded.
+    ///
+    /// By default, new nodes are collapsed.
+    #[must_use]
+    pub fn autoexpand(mut self, autoexpand: bool) -> Self {
+        self.autoexpand = autoexpand;
+        self
+    }
+
+    /// Launch the factory.
+    /// This is similar to [`Connector::launch`](crate::component::ComponentBuilder::launch).
+    pub fn launch(self, widget: C::ParentWidget) -> FactoryTreeConnector<C> {
+        let Self {
+            indent, autoexpand, ..
+        } = self;
+
+        let (output_sender, output_receiver) = crate::channel();
+
+        FactoryTreeConnector {
+            widget,
+            output_sender,
+            output_receiver,
+            indent,
+            autoexpand,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Second stage of the builder-pattern for building a [`FactoryTree`].
+pub struct FactoryTreeConnector<C>
+where
+    C: FactoryComponent,
+{
+    widget: C::ParentWidget,
+    output_sender: Sender<C::Output>,
+    output_receiver: Receiver<C::Output>,
+    indent: i32,
+    autoexpand: bool,
+}
+
+impl<C> FactoryTreeConnector<C>
+where
+    C: FactoryComponent,
+{
+    /// Forwards output events to the designated sender.
+    pub fn forward<F, Msg>(self, sender_: &Sender<Msg>, f: F) -> FactoryTree<C>
+    where
+        F: Fn(C::Output) -> Msg + Send + 'static,
+        C::Output: Send,
+        Msg: Send + 'static,
+    {
+        let Self {
+            widget,
+            output_sender,
+            output_receiver,
+            indent,
+            autoexpand,
+        } = self;
+
+        let sender_clone = sender_.clone();
+
+        crate::spawn(async move {
+            while let Some(msg) = output_receiver.recv().await {
+                if sender_clone.send(f(msg)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        FactoryTree {
+            widget,
+            output_sender,
+            roots: Vec::new(),
+            indent,
+            autoexpand,
+        }
+    }
+
+    /// Ignore outputs from the component and finish the builder.
+    pub fn detach(self) -> FactoryTree<C> {
+        let Self {
+            widget,
+            output_sender,
+            indent,
+            autoexpand,
+            ..
+        } = self;
+
+        FactoryTree {
+            widget,
+            output_sender,
+            roots: Vec::new(),
+            indent,
+            autoexpand,
+        }
+    }
+}
+
+/// A tree of values of type [`FactoryComponent`].
+///
+/// The nodes are rendered in pre-order into a list-like container,
+/// usually a [`gtk::ListBox`], and are indented by their depth.
+/// The descendants of collapsed nodes are hidden.
+///
+/// Nodes are addressed by their path, which contains the position
+/// of the node and all of its ancestors among their siblings,
+/// starting with the top-level ancestor.
+/// Each node receives a [`TreeIndex`] that always refers to its current path.
+#[derive(Debug)]
+pub struct FactoryTree<C: FactoryComponent> {
+    widget: C::ParentWidget,
+    output_sender: Sender<C::Output>,
+    roots: Vec<TreeNode<C>>,
+    indent: i32,
+    autoexpand: bool,
+}
+
+impl<C> Drop for FactoryTree<C>
+where
+    C: FactoryComponent,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<C> FactoryTree<C>
+where
+    C: FactoryComponent,
+{
+    /// Creates a new [`FactoryTree`].
+    #[must_use]
+    pub fn builder() -> FactoryTreeBuilder<C> {
+        FactoryTreeBuilder::new()
+    }
+
+    /// Returns the number of nodes in the [`FactoryTree`].
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns true if the [`FactoryTree`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Returns the number of children of the node at `path`.
+    ///
+    /// An empty path returns the number of top-level nodes.
+    /// Returns [`None`] if `path` is invalid.
+    pub fn children_len(&self, path: &[usize]) -> Option<usize> {
+        find_children(&self.roots, path).map(<[_]>::len)
+    }
+
+    /// Returns true if the node at `path` is expanded.
+    ///
+    /// Returns [`false`] if `path` is invalid.
+    pub fn is_expanded(&self, path: &[usize]) -> bool {
+        find_node(&self.roots, path).is_some_and(|node| node.expanded)
+    }
+
+    /// Send clone of a message to all of the nodes.
+    pub fn broadcast(&self, msg: C::Input)
+    where
+        C::Input: Clone,
+    {
+        self.iter_nodes()
+            .for_each(|node| node.handle.input.emit(msg.clone()));
+    }
+
+    /// Returns the widget all components are attached to.
+    pub const fn widget(&self) -> &C::ParentWidget {
+        &self.widget
+    }
+
+    /// Returns an iterator over the indices and components of all nodes in pre-order.
+    pub fn iter(&self) -> impl FusedIterator<Item = (&TreeIndex, &C)> {
+        self.iter_nodes()
+            .map(|node| (&node.index, node.handle.data.get()))
+    }
+
+    /// Removes all nodes.
+    pub fn clear(&mut self) {
+        for node in self.roots.drain(..) {
+            node.remove_widgets(&self.widget);
+        }
+    }
+
+    fn iter_nodes(&self) -> impl FusedIterator<Item = &TreeNode<C>> {
+        let mut stack = vec![self.roots.iter()];
+        std::iter::from_fn(move || {
+            while let Some(siblings) = stack.last_mut() {
+                if let Some(node) = siblings.next() {
+                    stack.push(node.children.iter());
+                    return Some(node);
+                }
+                stack.pop();
+            }
+            None
+        })
+        .fuse()
+    }
+}
+
+impl<C> FactoryTree<C>
+where
+    C: FactoryComponent<Index = TreeIndex>,
+    ReturnedWidget<C>: IsA<gtk::Widget>,
+{
+    /// Send a message to one of the nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is invalid.
+    pub fn send(&self, path: &[usize], msg: C::Input) {
+        find_node(&self.roots, path)
+            .expect("Called `send` with an invalid path")
+            .handle
+            .input
+            .emit(msg);
+    }
+
+    /// Tries to get an immutable reference to
+    /// the model of one node.
+    ///
+    /// Returns [`None`] if `path` is invalid.
+    pub fn get(&self, path: &[usize]) -> Option<&C> {
+        find_node(&self.roots, path).map(|node| node.handle.data.get())
+    }
+
+    /// Tries to get a mutable reference to
+    /// the model of one node.
+    ///
+    /// Returns [`None`] if `path` is invalid.
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<FactoryElementGuard<'_, C>> {
+        find_node_mut(&mut self.roots, path).map(|node| FactoryElementGuard {
+            inner: &mut node.handle,
+        })
+    }
+
+    /// Appends a new node to the children of the node at `parent`.
+    ///
+    /// An empty `parent` path appends a top-level node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parent` is invalid.
+    pub fn append(&mut self, parent: &[usize], init: C::Init) -> TreeIndex {
+        let len = self
+            .children_len(parent)
+            .expect("Called `append` with an invalid parent path");
+
+        let mut path = parent.to_vec();
+        path.push(len);
+        self.insert(&path, init)
+    }
+
+    /// Inserts a new node so that it's located at `path`.
+    ///
+    /// All following siblings are shifted by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is empty, its parent is invalid or
+    /// the position is greater than the number of siblings.
+    pub fn insert(&mut self, path: &[usize], init: C::Init) -> TreeIndex {
+        let (&position, parent) = path
+            .split_last()
+            .expect("Called `insert` with an empty path");
+        let parent_index = self.parent_index(parent);
+        assert!(
+            position <= self.children_len(parent).unwrap(),
+            "Called `insert` with an out of bounds path"
+        );
+
+        let index = TreeIndex::new(parent_index, position);
+        let builder = FactoryBuilder::new(&index, init, self.output_sender.clone());
+
+        let widget_position = C::position(&builder.data, &index);
+        let returned_widget = match self.previous_widget(parent, position) {
+            Some(previous) => self.widget.factory_insert_after(
+                builder.root_widget.clone(),
+                &widget_position,
+                previous,
+            ),
+            None => self
+                .widget
+                .factory_prepend(builder.root_widget.clone(), &widget_position),
+        };
+        self.style(&returned_widget, parent.len(), self.is_shown(parent));
+
+        let handle = builder.launch(&index, returned_widget);
+
+        let siblings = find_children_mut(&mut self.roots, parent).unwrap();
+        for sibling in &siblings[position..] {
+            sibling.index.increment();
+        }
+        siblings.insert(
+            position,
+            TreeNode {
+                index: index.clone(),
+                handle,
+                expanded: self.autoexpand,
+                children: Vec::new(),
+            },
+        );
+
+        index
+    }
+
+    /// Removes the node at `path` with all of its descendants
+    /// and returns the model of the node.
+    ///
+    /// Returns [`None`] if `path` is invalid.
+    pub fn remove(&mut self, path: &[usize]) -> Option<C> {
+        let (&position, parent) = path.split_last()?;
+        let siblings = find_children_mut(&mut self.roots, parent)?;
+        if position >= siblings.len() {
+            return None;
+        }
+
+        let node = siblings.remove(position);
+        for sibling in &siblings[position..] {
+            sibling.index.decrement();
+        }
+
+        node.remove_widgets(&self.widget);
+        Some(node.handle.data.into_inner())
+    }
+
+    /// Moves the node at `from` with all of its descendants
+    /// so that it's located at `to` afterwards.
+    ///
+    /// Like [`Vec::remove`] followed by [`Vec::insert`], `to` refers
+    /// to the tree after the node was removed from its old position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` is invalid or `to` isn't a valid path
+    /// for [`insert()`](Self::insert) after removing the node.
+    pub fn move_to(&mut self, from: &[usize], to: &[usize]) {
+        let (&from_position, from_parent) = from
+            .split_last()
+            .expect("Called `move_to` with an empty path");
+        let siblings = find_children_mut(&mut self.roots, from_parent)
+            .filter(|siblings| from_position < siblings.len())
+            .expect("Called `move_to` with an invalid path");
+
+        let node = siblings.remove(from_position);
+        for sibling in &siblings[from_position..] {
+            sibling.index.decrement();
+        }
+
+        let (&to_position, to_parent) = to
+            .split_last()
+            .expect("Called `move_to` with an empty path");
+        let parent_index = self.parent_index(to_parent);
+        assert!(
+            to_position <= self.children_len(to_parent).unwrap(),
+            "Called `move_to` with an out of bounds path"
+        );
+
+        node.index.set_position(parent_index, to_position);
+        let previous = self.previous_widget(to_parent, to_position).cloned();
+        self.place(&node, previous, to_parent.len(), self.is_shown(to_parent));
+
+        let siblings = find_children_mut(&mut self.roots, to_parent).unwrap();
+        for sibling in &siblings[to_position..] {
+            sibling.index.increment();
+        }
+        siblings.insert(to_position, node);
+    }
+
+    /// Expands or collapses the node at `path`.
+    ///
+    /// The descendants of collapsed nodes are hidden.
+    /// Returns [`true`] on success, otherwise [`false`].
+    pub fn set_expanded(&mut self, path: &[usize], expanded: bool) -> bool {
+        if find_node(&self.roots, path).is_none() {
+            return false;
+        }
+        let shown = self.is_shown(&path[..path.len() - 1]);
+
+        let node = find_node_mut(&mut self.roots, path).unwrap();
+        node.expanded = expanded;
+        for child in &node.children {
+            child.set_shown(shown && expanded);
+        }
+        true
+    }
+
+    /// Returns the index of the node at `parent` or [`None`] for the top level.
+    fn parent_index(&self, parent: &[usize]) -> Option<TreeIndex> {
+        if parent.is_empty() {
+            None
+        } else {
+            let node = find_node(&self.roots, parent).expect("Invalid parent path");
+            Some(node.index.clone())
+        }
+    }
+
+    /// Returns true if the children of the node at `parent` are visible.
+    ///
+    /// Returns [`false`] if `parent` is invalid.
+    fn is_shown(&self, parent: &[usize]) -> bool {
+        let mut siblings = &self.roots;
+        for index in parent {
+            let Some(node) = siblings.get(*index) else {
+                return false;
+            };
+            if !node.expanded {
+                return false;
+            }
+            siblings = &node.children;
+        }
+        true
+    }
+
+    /// Returns the widget that precedes a node at `position` among the children of `parent`.
+    fn previous_widget(&self, parent: &[usize], position: usize) -> Option<&ReturnedWidget<C>> {
+        if position > 0 {
+            let siblings = find_children(&self.roots, parent)?;
+            Some(
+                &siblings[position - 1]
+                    .last_descendant()
+                    .handle
+                    .returned_widget,
+            )
+        } else {
+            find_node(&self.roots, parent).map(|node| &node.handle.returned_widget)
+        }
+    }
+
+    /// Moves the widgets of a subtree after `previous` and updates their indentation
+    /// and visibility.
+    ///
+    /// Returns the last widget of the subtree.
+    fn place(
+        &self,
+        node: &TreeNode<C>,
+        previous: Option<ReturnedWidget<C>>,
+        depth: usize,
+        shown: bool,
+    ) -> ReturnedWidget<C> {
+        let widget = &node.handle.returned_widget;
+        match previous {
+            Some(previous) => self.widget.factory_move_after(widget, &previous),
+            None => self.widget.factory_move_start(widget),
+        }
+        self.style(widget, depth, shown);
+
+        let mut previous = widget.clone();
+        for child in &node.children {
+            previous = self.place(child, Some(previous), depth + 1, shown && node.expanded);
+        }
+        previous
+    }
+
+    fn style(&self, widget: &ReturnedWidget<C>, depth: usize, shown: bool) {
+        widget.set_margin_start(self.indent * depth as i32);
+        widget.set_visible(shown);
+    }
+}
@@ -1,18 +1,28 @@
+use crate::channel::OutputTarget;
 use crate::{Receiver, Sender};
 
+use crate::factory::indexed_output::IndexedOutput;
+use crate::factory::reorder::{FactoryMove, Reorder};
+use crate::factory::selection::{self, SelectionChanged};
 use crate::factory::sync::builder::FactoryBuilder;
 use crate::factory::sync::component_storage::ComponentStorage;
 use crate::factory::sync::traits::CloneableFactoryComponent;
-use crate::factory::{DynamicIndex, FactoryComponent, FactoryView};
+use crate::factory::transition::{FactoryTransition, Transition};
+use crate::factory::{
+    DynamicIndex, FactoryComponent, FactorySnapshot, FactoryView, SelectableFactoryView,
+    WeakDynamicIndex, diff,
+};
 
 use super::{ModelStateValue, RenderedState};
 
-use std::collections::VecDeque;
+use std::any::Any;
+use std::cmp::Ordering;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
 use std::iter::FusedIterator;
 use std::marker::PhantomData;
-use std::ops::{Deref, Index, IndexMut};
+use std::ops::{Bound, Deref, Index, IndexMut, RangeBounds};
 
 #[cfg(feature = "libadwaita")]
 use gtk::prelude::Cast;
@@ -135,6 +145,15 @@ where
             .map(ComponentStorage::get_mut)
     }
 
+    /// Tries to get a mutable reference to the model
+    /// of the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`None`] if the element was removed.
+    pub fn get_mut_weak(&mut self, index: &WeakDynamicIndex) -> Option<&mut C> {
+        let position = self.resolve(index)?;
+        self.get_mut(position)
+    }
+
     /// Provides a mutable reference to the model of the back element.
     ///
     ///  Returns [`None`] if the deque is empty.
@@ -170,21 +189,31 @@ where
     ///
     /// Element at index 0 is the front of the queue.
     pub fn remove(&mut self, index: usize) -> Option<C> {
-        self.inner.model_state.remove(index);
+        let state = self.inner.model_state.remove(index);
         let component = self.inner.components.remove(index);
 
+        // The widget is removed right away, so it doesn't need to be rendered.
+        if let Some(state) = state {
+            state.index.set_removed();
+            self.inner
+                .rendered_state
+                .retain(|rendered| rendered.uid != state.uid);
+        }
+
         // Decrement the indexes of the following elements.
         for states in self.inner.model_state.iter_mut().skip(index) {
             states.index.decrement();
         }
 
-        if let Some(comp) = &component
-            && let Some(widget) = &comp.returned_widget()
-        {
-            self.widget.factory_remove(widget);
-        }
+        component.map(|component| self.inner.remove_component(component))
+    }
 
-        component.map(ComponentStorage::extract)
+    /// Removes and returns the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`None`] if the element was removed already.
+    pub fn remove_weak(&mut self, index: &WeakDynamicIndex) -> Option<C> {
+        let position = self.resolve(index)?;
+        self.remove(position)
     }
 
     /// Appends an element at the end of the [`FactoryVecDeque`].
@@ -215,7 +244,7 @@ where
             states.index.increment();
         }
 
-        let builder = FactoryBuilder::new(&dyn_index, init, self.output_sender.clone());
+        let builder = FactoryBuilder::new(&dyn_index, init, self.element_output(&dyn_index));
 
         self.inner
             .components
@@ -322,21 +351,305 @@ where
         self.move_to(current_position, self.len() - 1);
     }
 
-    /// Remove all components from the [`FactoryVecDeque`].
-    pub fn clear(&mut self) {
-        self.inner.model_state.clear();
+    /// Synchronizes the [`FactoryVecDeque`] with a list of items,
+    /// for example data received from a backend.
+    ///
+    /// Elements and items are matched by the key returned by `key_fn`.
+    /// Matching elements are kept and only moved to their new position,
+    /// so their widgets and their state, such as focus or scroll position, are preserved.
+    /// For each kept element, `update_fn` is called with its model and the new item
+    /// and can return a message that is sent to the element.
+    /// Elements without a matching item are removed and new elements
+    /// are created for the remaining items.
+    ///
+    /// Keys are only known for elements created or kept by the last call of this method.
+    /// Elements added in other ways, for example with [`Self::push_back()`] or
+    /// [`Self::insert()`], never match a key and are always removed and recreated.
+    /// Calling this method with another key type also recreates all elements.
+    /// If several items have the same key, only the first one can reuse an existing element.
+    pub fn reconcile<K, F, U>(
+        &mut self,
+        new_items: impl IntoIterator<Item = C::Init>,
+        key_fn: F,
+        mut update_fn: U,
+    ) where
+        K: Eq + Hash + 'static,
+        F: Fn(&C::Init) -> K,
+        U: FnMut(&C, C::Init) -> Option<C::Input>,
+    {
+        let inner = &mut *self.inner;
+
+        // Map the keys of the current elements to their positions.
+        let mut old_keys = inner
+            .reconcile_keys
+            .take()
+            .and_then(|keys| keys.downcast::<HashMap<usize, K>>().ok())
+            .map(|keys| *keys)
+            .unwrap_or_default();
+        let mut old_positions: HashMap<K, usize> = HashMap::new();
+        for (position, state) in inner.model_state.iter().enumerate() {
+            if let Some(key) = old_keys.remove(&state.uid) {
+                old_positions.entry(key).or_insert(position);
+            }
+        }
+        let mut keys: HashMap<usize, K> = HashMap::new();
+
+        let mut old_elements: Vec<_> = inner
+            .components
+            .drain(..)
+            .zip(inner.model_state.drain(..))
+            .map(Some)
+            .collect();
+
+        for init in new_items {
+            let key = key_fn(&init);
+            let position = inner.components.len();
+
+            let old_element = old_positions
+                .remove(&key)
+                .and_then(|old_position| old_elements[old_position].take());
+
+            if let Some((component, state)) = old_element {
+                state.index.set_value(position);
+                keys.insert(state.uid, key);
+
+                if let Some(msg) = update_fn(component.get(), init) {
+                    component.send(msg);
+                }
+
+                inner.components.push_back(component);
+                inner.model_state.push_back(state);
+            } else {
+                let dyn_index = DynamicIndex::new(position);
+                let builder =
+                    FactoryBuilder::new(&dyn_index, init, inner.element_output(&dyn_index));
+
+                inner
+                    .components
+                    .push_back(ComponentStorage::Builder(builder));
+                inner.model_state.push_back(ModelStateValue {
+                    index: dyn_index,
+                    uid: inner.uid_counter,
+                    changed: false,
+                });
+                keys.insert(inner.uid_counter, key);
+                inner.uid_counter += 1;
+            }
+        }
+
+        // Remove all elements that weren't reused.
+        let mut removed_uids = HashSet::new();
+        for (component, state) in old_elements.into_iter().flatten() {
+            state.index.set_removed();
+            removed_uids.insert(state.uid);
+            inner.remove_component(component);
+        }
+
+        // The widgets are removed already, so they don't need to be rendered.
+        inner
+            .rendered_state
+            .retain(|state| !removed_uids.contains(&state.uid));
+        inner.reconcile_keys = Some(Box::new(keys));
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` for which `f(&e)` returns false.
+    /// This method operates in place, visiting each element exactly once in the
+    /// original order, and preserves the order of the retained elements.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&C) -> bool,
+    {
+        self.remove_where(|_, model| !f(model));
+    }
+
+    /// Removes the specified range from the [`FactoryVecDeque`]
+    /// and returns the removed elements.
+    ///
+    /// Unlike [`VecDeque::drain()`], the elements are removed eagerly:
+    /// they are gone even if the returned iterator is dropped without being consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the [`FactoryVecDeque`].
+    pub fn drain<R>(&mut self, range: R) -> impl DoubleEndedIterator<Item = C> + ExactSizeIterator
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(start <= end, "drain lower bound was too large");
+        assert!(end <= self.len(), "drain upper bound was too large");
+
+        self.remove_where(|index, _| (start..end).contains(&index))
+            .into_iter()
+    }
+
+    /// Shortens the [`FactoryVecDeque`], keeping the first `len` elements and dropping the rest.
+    ///
+    /// If `len` is greater than the current length, this has no effect.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.remove_where(|index, _| index >= len);
+        }
+    }
+
+    /// Moves all elements of `other` to the back of this [`FactoryVecDeque`],
+    /// leaving `other` empty.
+    ///
+    /// Widgets can't be moved between factories, so the elements are
+    /// recreated from [`CloneableFactoryComponent::get_init()`].
+    /// Any state of the elements that isn't part of their init value is lost
+    /// and their old [`DynamicIndex`] values are marked as removed.
+    pub fn append(&mut self, other: &mut FactoryVecDeque<C>)
+    where
+        C: CloneableFactoryComponent,
+    {
+        let inits: Vec<C::Init> = other.iter().map(C::get_init).collect();
+        other.guard().clear();
+
+        for init in inits {
+            self.push_back(init);
+        }
+    }
+
+    /// Replaces all elements with the elements of a [`FactorySnapshot`].
+    ///
+    /// Use [`FactoryVecDeque::extend()`] to add the elements
+    /// of a snapshot without removing the existing ones.
+    pub fn restore(&mut self, snapshot: FactorySnapshot<C::Init>) {
+        self.clear();
+
+        for init in snapshot {
+            self.push_back(init);
+        }
+    }
+
+    /// Sorts the [`FactoryVecDeque`] with a comparison function.
+    ///
+    /// The sort is stable. The widgets are moved once all changes are rendered,
+    /// and widgets that keep their relative order aren't moved.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&C, &C) -> Ordering,
+    {
+        let inner = &mut *self.inner;
+
+        let mut elements: Vec<_> = inner
+            .components
+            .drain(..)
+            .zip(inner.model_state.drain(..))
+            .collect();
+        elements.sort_by(|(first, _), (second, _)| compare(first.get(), second.get()));
+
+        for (position, (component, state)) in elements.into_iter().enumerate() {
+            state.index.set_value(position);
+            inner.components.push_back(component);
+            inner.model_state.push_back(state);
+        }
+    }
+
+    /// Sorts the [`FactoryVecDeque`] with a key extraction function.
+    ///
+    /// The sort is stable. The widgets are moved once all changes are rendered,
+    /// and widgets that keep their relative order aren't moved.
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&C) -> K,
+        K: Ord,
+    {
+        self.sort_by(|first, second| f(first).cmp(&f(second)));
+    }
 
-        for component in self.inner.components.drain(..) {
-            // Remove all widgets
-            if let Some(widget) = component.returned_widget() {
-                self.inner.widget.factory_remove(widget);
+    /// Removes all but the first of consecutive elements
+    /// that resolve to the same key.
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&C) -> K,
+        K: PartialEq,
+    {
+        let mut previous: Option<K> = None;
+        self.remove_where(|_, model| {
+            let current = key(model);
+            if previous.as_ref() == Some(&current) {
+                true
+            } else {
+                previous = Some(current);
+                false
             }
+        });
+    }
+
+    /// Removes all elements for which `remove` returns true
+    /// and returns their models.
+    fn remove_where<F>(&mut self, mut remove: F) -> Vec<C>
+    where
+        F: FnMut(usize, &C) -> bool,
+    {
+        let inner = &mut *self.inner;
+
+        let elements: Vec<_> = inner
+            .components
+            .drain(..)
+            .zip(inner.model_state.drain(..))
+            .collect();
+
+        let mut removed = Vec::new();
+        let mut removed_uids = HashSet::new();
+        for (index, (component, state)) in elements.into_iter().enumerate() {
+            if remove(index, component.get()) {
+                state.index.set_removed();
+                removed_uids.insert(state.uid);
+                removed.push(inner.remove_component(component));
+            } else {
+                state.index.set_value(inner.components.len());
+                inner.components.push_back(component);
+                inner.model_state.push_back(state);
+            }
+        }
 
-            // Make sure the component is shutdown properly
-            component.extract();
+        // The widgets are removed already, so they don't need to be rendered.
+        if !removed_uids.is_empty() {
+            inner
+                .rendered_state
+                .retain(|state| !removed_uids.contains(&state.uid));
         }
 
+        removed
+    }
+
+    /// Remove all components from the [`FactoryVecDeque`].
+    ///
+    /// The elements are removed without a [`FactoryTransition`].
+    pub fn clear(&mut self) {
+        for state in self.inner.model_state.drain(..) {
+            state.index.set_removed();
+        }
+
+        // Remove all widgets right away, including those of elements that are still animated.
+        let transition = self.inner.transition.take();
+        if let Some(transition) = &transition {
+            transition.finish();
+        }
+        let components = std::mem::take(&mut self.inner.components);
+        for component in components {
+            self.inner.remove_component(component);
+        }
+        self.inner.transition = transition;
+
         self.inner.rendered_state.clear();
+        // The UIDs are reused, so the keys must not be matched anymore.
+        self.inner.reconcile_keys = None;
         self.inner.uid_counter = 1;
     }
 
@@ -487,9 +800,52 @@ where
             rendered_state: VecDeque::new(),
             // 0 is always an invalid uid
             uid_counter: 1,
+            reconcile_keys: None,
+            reorder: None,
+            selection_changed: None,
+            transition: None,
+            indexed_output: None,
         }
     }
 
+    /// Forwards output events from child components to the designated sender
+    /// together with the [`DynamicIndex`] of the element that sent them.
+    ///
+    /// This way, components don't need to include their index in their outputs.
+    /// Outputs sent with [`FactorySender::output()`](crate::FactorySender::output) are forwarded right away,
+    /// so they arrive in the order they were sent.
+    ///
+    /// Outputs can still arrive after the element was removed, for example
+    /// if a button was clicked twice. Check [`DynamicIndex::is_removed()`]
+    /// before using the index to access the factory.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let counters = FactoryVecDeque::builder()
+    ///     .launch(gtk::Box::default())
+    ///     .forward_with_index(sender.input_sender(), |index, output| match output {
+    ///         CounterOutput::Remove => AppMsg::Remove(index),
+    ///     });
+    ///
+    /// // In `update()`:
+    /// AppMsg::Remove(index) => {
+    ///     if !index.is_removed() {
+    ///         self.counters.guard().remove(index.current_index());
+    ///     }
+    /// }
+    /// ```
+    pub fn forward_with_index<F, Msg>(self, sender_: &Sender<Msg>, f: F) -> FactoryVecDeque<C>
+    where
+        F: Fn(DynamicIndex, C::Output) -> Msg + Send + Sync + 'static,
+        C::Output: Send,
+        Msg: Send + 'static,
+    {
+        let mut factory = self.detach();
+        factory.indexed_output = Some(IndexedOutput::new(sender_, f));
+        factory
+    }
+
     /// Ignore output events from child components and just create the [`FactoryVecDeque`].
     pub fn detach(self) -> FactoryVecDeque<C> {
         let Self {
@@ -505,6 +861,11 @@ where
             rendered_state: VecDeque::new(),
             // 0 is always an invalid uid
             uid_counter: 1,
+            reconcile_keys: None,
+            reorder: None,
+            selection_changed: None,
+            transition: None,
+            indexed_output: None,
         }
     }
 }
@@ -524,6 +885,13 @@ where
     model_state: VecDeque<ModelStateValue>,
     rendered_state: VecDeque<RenderedState>,
     uid_counter: usize,
+    /// The keys of the elements by UID, stored as `HashMap<usize, K>`
+    /// by [`FactoryVecDequeGuard::reconcile()`].
+    reconcile_keys: Option<Box<dyn Any>>,
+    reorder: Option<Reorder<C::Root>>,
+    selection_changed: Option<SelectionChanged<<C::ParentWidget as FactoryView>::ReturnedWidget>>,
+    transition: Option<Transition<C::Root, <C::ParentWidget as FactoryView>::ReturnedWidget>>,
+    indexed_output: Option<IndexedOutput<DynamicIndex, C::Output>>,
 }
 
 impl<C> Drop for FactoryVecDeque<C>
@@ -531,6 +899,9 @@ where
     C: FactoryComponent<Index = DynamicIndex>,
 {
     fn drop(&mut self) {
+        // The parent widget is likely destroyed as well, so don't animate the removal.
+        self.transition = None;
+        self.selection_changed = None;
         self.guard().clear();
     }
 }
@@ -574,34 +945,43 @@ where
     fn render_changes(&mut self) {
         let mut first_position_change_idx = None;
 
+        // The index of each element in the previously rendered order.
+        let rendered_orders: HashMap<usize, usize> = self
+            .rendered_state
+            .iter()
+            .enumerate()
+            .map(|(order, rendered)| (rendered.uid, order))
+            .collect();
+        let rendered_orders: Vec<Option<usize>> = self
+            .model_state
+            .iter()
+            .map(|state| rendered_orders.get(&state.uid).copied())
+            .collect();
+        let unmoved = diff::unmoved_elements(&rendered_orders);
+
         let components = &mut self.components;
-        let rendered_state = &mut self.rendered_state;
         for (index, state) in self.model_state.iter().enumerate() {
-            if state.uid == rendered_state.front().map(|r| r.uid).unwrap_or_default() {
-                // Remove item from previously rendered list
-                rendered_state.pop_front();
-
-                if state.changed {
-                    // Update component
-                    components[index].state_change_notify();
-                }
-            } else if let Some(rendered_index) =
-                rendered_state.iter().position(|r| r.uid == state.uid)
-            {
-                if first_position_change_idx.is_none() {
-                    first_position_change_idx = Some(index);
-                }
-
-                // Remove item from previously rendered list
-                rendered_state.remove(rendered_index);
-
-                // Detach and re-attach item
-                let widget = components[index].returned_widget().unwrap();
-                if index == 0 {
-                    self.widget.factory_move_start(widget);
+            if let Some(order) = rendered_orders[index] {
+                if unmoved[index] {
+                    // Elements in front of this one were removed.
+                    if self.rendered_state[order].position != index
+                        && first_position_change_idx.is_none()
+                    {
+                        first_position_change_idx = Some(index);
+                    }
                 } else {
-                    let previous_widget = components[index - 1].returned_widget().unwrap();
-                    self.widget.factory_move_after(widget, previous_widget);
+                    if first_position_change_idx.is_none() {
+                        first_position_change_idx = Some(index);
+                    }
+
+                    // Detach and re-attach item
+                    let widget = components[index].returned_widget().unwrap();
+                    if index == 0 {
+                        self.widget.factory_move_start(widget);
+                    } else {
+                        let previous_widget = components[index - 1].returned_widget().unwrap();
+                        self.widget.factory_move_after(widget, previous_widget);
+                    }
                 }
 
                 if state.changed {
@@ -624,10 +1004,25 @@ where
                     self.widget
                         .factory_insert_after(insert_widget, &position, previous_widget)
                 };
-                let component = components.remove(index).unwrap();
-                let dyn_index = &self.model_state[index].index;
-                let component = component.launch(dyn_index, returned_widget).unwrap();
-                components.insert(index, component);
+
+                // Swap the component with the last one instead of shifting
+                // all following components when launching it.
+                let component = components.swap_remove_back(index).unwrap();
+                let component = component.launch(&state.index, returned_widget).unwrap();
+                if let Some(reorder) = &self.reorder {
+                    reorder.attach(component.widget(), &state.index);
+                }
+                if let (Some(selection_changed), Some(returned_widget)) =
+                    (&self.selection_changed, component.returned_widget())
+                {
+                    selection_changed.attach(returned_widget, &state.index);
+                }
+                if let Some(transition) = &self.transition {
+                    transition.enter(component.widget());
+                }
+                components.push_back(component);
+                let last = components.len() - 1;
+                components.swap(index, last);
             }
         }
 
@@ -640,12 +1035,14 @@ where
             .model_state
             .iter()
             .zip(components.iter())
-            .map(|(s, c)| {
+            .enumerate()
+            .map(|(position, (s, c))| {
                 let mut hasher = DefaultHasher::default();
                 c.returned_widget().unwrap().hash(&mut hasher);
 
                 RenderedState {
                     uid: s.uid,
+                    position,
                     #[cfg(feature = "libadwaita")]
                     widget_hash: hasher.finish(),
                 }
@@ -661,6 +1058,30 @@ where
         }
     }
 
+    /// Returns the output target of a new element.
+    fn element_output(&self, index: &DynamicIndex) -> OutputTarget<C::Output> {
+        match &self.indexed_output {
+            Some(indexed_output) => indexed_output.element_output(index),
+            None => self.output_sender.clone().into(),
+        }
+    }
+
+    /// Removes the widget of a component and shuts the component down.
+    ///
+    /// With a [`FactoryTransition`], the widget is only removed
+    /// after the animation is finished.
+    fn remove_component(&self, component: ComponentStorage<C>) -> C {
+        let (data, widgets) = component.extract();
+        if let Some((root_widget, returned_widget, widgets)) = widgets {
+            if let Some(transition) = &self.transition {
+                transition.exit(&root_widget, returned_widget, widgets);
+            } else {
+                self.widget.factory_remove(&returned_widget);
+            }
+        }
+        data
+    }
+
     /// Returns the number of elements in the [`FactoryVecDeque`].
     pub fn len(&self) -> usize {
         self.components.len()
@@ -692,6 +1113,39 @@ where
         self.components.get(index).map(ComponentStorage::get)
     }
 
+    /// Returns the current position of the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`None`] if the element was removed or doesn't belong to this factory.
+    #[must_use]
+    pub fn resolve(&self, index: &WeakDynamicIndex) -> Option<usize> {
+        let index = index.upgrade()?;
+        let position = index.current_index();
+        self.model_state
+            .get(position)
+            .filter(|state| state.index.ptr_eq(&index))
+            .map(|_| position)
+    }
+
+    /// Tries to get an immutable reference to the model
+    /// of the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`None`] if the element was removed.
+    pub fn get_weak(&self, index: &WeakDynamicIndex) -> Option<&C> {
+        self.resolve(index).and_then(|position| self.get(position))
+    }
+
+    /// Sends a message to the element of a [`WeakDynamicIndex`].
+    ///
+    /// Returns [`false`] if the element was removed.
+    pub fn send_weak(&self, index: &WeakDynamicIndex, msg: C::Input) -> bool {
+        if let Some(position) = self.resolve(index) {
+            self.send(position, msg);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Provides a reference to the model of the back element.
     ///
     ///  Returns [`None`] if the deque is empty.
@@ -767,6 +1221,20 @@ where
     }
 }
 
+impl<C> FactoryVecDeque<C>
+where
+    C: CloneableFactoryComponent + FactoryComponent<Index = DynamicIndex>,
+{
+    /// Creates a [`FactorySnapshot`] of all elements
+    /// from [`CloneableFactoryComponent::get_init()`].
+    ///
+    /// The snapshot can be restored with [`FactoryVecDequeGuard::restore()`].
+    #[must_use]
+    pub fn snapshot(&self) -> FactorySnapshot<C::Init> {
+        self.iter().map(C::get_init).collect()
+    }
+}
+
 impl<C> FactoryVecDeque<C>
 where
     C: FactoryComponent<Index = DynamicIndex>,
@@ -786,3 +1254,187 @@ where
         }
     }
 }
+
+impl<C> FactoryVecDeque<C>
+where
+    C: FactoryComponent<Index = DynamicIndex>,
+    C::Root: IsA<gtk::Widget>,
+{
+    /// Allows dragging elements onto other elements to request moving them.
+    ///
+    /// If an element is dropped on another element, the message returned by `f`
+    /// is sent to `sender`. **The factory isn't changed automatically**,
+    /// because it's owned by your component. The move needs to be applied with [`FactoryVecDequeGuard::move_to()`].
+    ///
+    /// While an element is dragged over another element, the other element has
+    /// the CSS class `relm4-reorder-drop-target`, which shows a dashed outline by default.
+    ///
+    /// Calling this method again replaces the previous `sender` and `f`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // In `init`
+    /// counters.enable_reorder_requests(sender.input_sender(), AppMsg::Moved);
+    ///
+    /// // In `update`
+    /// AppMsg::Moved(FactoryMove { from, to }) => {
+    ///     self.counters.guard().move_to(from, to);
+    /// }
+    /// ```
+    pub fn enable_reorder_requests<Msg, F>(&mut self, sender: &Sender<Msg>, f: F)
+    where
+        Msg: 'static,
+        F: Fn(FactoryMove) -> Msg + 'static,
+    {
+        let reorder = Reorder::new(sender, f);
+
+        // Elements that are already rendered.
+        for (component, state) in self.components.iter().zip(&self.model_state) {
+            if component.returned_widget().is_some() {
+                reorder.attach(component.widget(), &state.index);
+            }
+        }
+
+        self.reorder = Some(reorder);
+    }
+
+    /// Animates elements when they are added or removed.
+    ///
+    /// Removed elements are faded out before their widgets are removed from the parent widget.
+    /// Elements are only animated while the parent widget is shown.
+    /// The widgets of fading elements are removed right away if the parent widget is hidden,
+    /// the factory is cleared or dropped, or the transition is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// counters.set_transition(Some(FactoryTransition::Fade(Duration::from_millis(200))));
+    /// ```
+    pub fn set_transition(&mut self, transition: Option<FactoryTransition>)
+    where
+        <C::ParentWidget as FactoryView>::ReturnedWidget: 'static,
+    {
+        self.transition = transition.map(|transition| Transition::new(&self.widget, transition));
+    }
+}
+
+impl<C> FactoryVecDeque<C>
+where
+    C: FactoryComponent<Index = DynamicIndex>,
+    C::ParentWidget: SelectableFactoryView,
+{
+    /// Sets how the elements can be selected.
+    ///
+    /// With [`gtk::SelectionMode::Multiple`], clicking an element
+    /// while holding Ctrl toggles its selection and holding Shift
+    /// selects a range of elements.
+    pub fn set_selection_mode(&self, mode: gtk::SelectionMode) {
+        self.widget.factory_set_selection_mode(mode);
+    }
+
+    /// Returns the sorted indices of the selected elements.
+    ///
+    /// Elements that aren't rendered yet are never selected.
+    #[must_use]
+    pub fn selected(&self) -> Vec<usize> {
+        selection::selected_positions(
+            &self.widget,
+            self.components
+                .iter()
+                .map(|component| component.returned_widget()),
+        )
+    }
+
+    /// Returns the [`DynamicIndex`]es of the selected elements.
+    ///
+    /// Unlike the indices returned by [`selected()`](Self::selected),
+    /// they stay valid if the elements are moved.
+    #[must_use]
+    pub fn selected_dynamic_indices(&self) -> Vec<DynamicIndex> {
+        self.selected()
+            .into_iter()
+            .filter_map(|index| self.model_state.get(index))
+            .map(|state| state.index.clone())
+            .collect()
+    }
+
+    /// Selects the element at `index`.
+    ///
+    /// In [`gtk::SelectionMode::Single`] and [`gtk::SelectionMode::Browse`],
+    /// the previously selected element is unselected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn select(&self, index: usize) {
+        if let Some(widget) = self.components[index].returned_widget() {
+            self.widget.factory_select(widget);
+        }
+    }
+
+    /// Unselects the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn unselect(&self, index: usize) {
+        if let Some(widget) = self.components[index].returned_widget() {
+            self.widget.factory_unselect(widget);
+        }
+    }
+
+    /// Selects all elements in `range`.
+    ///
+    /// This only makes sense in [`gtk::SelectionMode::Multiple`].
+    pub fn select_range(&self, range: impl RangeBounds<usize>) {
+        for (index, component) in self.components.iter().enumerate() {
+            if range.contains(&index)
+                && let Some(widget) = component.returned_widget()
+            {
+                self.widget.factory_select(widget);
+            }
+        }
+    }
+
+    /// Selects all elements if the selection mode allows it.
+    pub fn select_all(&self) {
+        self.widget.factory_select_all();
+    }
+
+    /// Unselects all elements.
+    pub fn unselect_all(&self) {
+        self.widget.factory_unselect_all();
+    }
+
+    /// Sends the message returned by `f` to `sender` every time the selection changed.
+    ///
+    /// `f` receives the sorted indices of the selected elements.
+    /// Selected widgets that weren't added by this factory are ignored.
+    /// The selection also changes if selected elements are removed
+    /// from the factory.
+    ///
+    /// While an element is dragged over another element, the other element has
+    /// the CSS class `relm4-reorder-drop-target`, which shows a dashed outline by default.
+    ///
+    /// Calling this method again replaces the previous `sender` and `f`.
+    /// The signal handler is disconnected when the factory is dropped.
+    pub fn connect_selection_changed<Msg, F>(&mut self, sender: &Sender<Msg>, f: F)
+    where
+        Msg: 'static,
+        F: Fn(Vec<usize>) -> Msg + 'static,
+    {
+        // Disconnect the previous handler first.
+        self.selection_changed = None;
+        let selection_changed = SelectionChanged::new(&self.widget, sender, f);
+
+        // Elements that are already rendered.
+        for (component, state) in self.components.iter().zip(&self.model_state) {
+            if let Some(returned_widget) = component.returned_widget() {
+                selection_changed.attach(returned_widget, &state.index);
+            }
+        }
+
+        self.selection_changed = Some(selection_changed);
+    }
+}
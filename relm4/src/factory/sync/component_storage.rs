@@ -2,6 +2,13 @@ use crate::factory::{FactoryComponent, FactoryView};
 
 use super::{FactoryBuilder, FactoryHandle};
 
+/// The root widget, the returned widget and the widgets of a launched component.
+type LaunchedWidgets<C> = (
+    <C as FactoryComponent>::Root,
+    <<C as FactoryComponent>::ParentWidget as FactoryView>::ReturnedWidget,
+    Box<<C as FactoryComponent>::Widgets>,
+);
+
 #[derive(Debug)]
 pub(super) enum ComponentStorage<C: FactoryComponent> {
     Builder(FactoryBuilder<C>),
@@ -43,10 +50,19 @@ impl<C: FactoryComponent> ComponentStorage<C> {
         }
     }
 
-    pub(super) fn extract(self) -> C {
+    /// Returns the model and the widgets if the component was already launched.
+    pub(super) fn extract(self) -> (C, Option<LaunchedWidgets<C>>) {
         match self {
-            Self::Builder(builder) => *builder.data,
-            Self::Final(handle) => handle.data.into_inner(),
+            Self::Builder(builder) => (*builder.data, None),
+            Self::Final(FactoryHandle {
+                data,
+                root_widget,
+                returned_widget,
+                ..
+            }) => {
+                let (data, widgets) = data.into_parts();
+                (data, Some((root_widget, returned_widget, widgets)))
+            }
         }
     }
 
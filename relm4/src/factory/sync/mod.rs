@@ -8,7 +8,9 @@ use builder::FactoryBuilder;
 use handle::FactoryHandle;
 
 pub use collections::{
-    FactoryHashMap, FactoryHashMapBuilder, FactoryHashMapConnector, FactoryVecDeque,
-    FactoryVecDequeBuilder, FactoryVecDequeConnector, FactoryVecDequeGuard,
+    FactoryBTreeMap, FactoryBTreeMapBuilder, FactoryBTreeMapConnector, FactoryHashMap,
+    FactoryHashMapBuilder, FactoryHashMapConnector, FactoryIndexMap, FactoryIndexMapBuilder,
+    FactoryIndexMapConnector, FactoryTree, FactoryTreeBuilder, FactoryTreeConnector,
+    FactoryVecDeque, FactoryVecDequeBuilder, FactoryVecDequeConnector, FactoryVecDequeGuard,
 };
 pub use traits::{CloneableFactoryComponent, FactoryComponent};
@@ -0,0 +1,241 @@
+//! Animations for elements that are added to or removed from a factory.
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk::glib;
+use gtk::prelude::{Cast, IsA, ObjectExt, WidgetExt, WidgetExtManual};
+
+use crate::factory::FactoryView;
+
+/// The key used to store the running animation on a widget.
+const ANIMATION_KEY: &str = "relm4-factory-transition";
+
+/// An animation that is played when elements are added to or removed from a factory.
+///
+/// Removed elements stay inside the parent widget until the animation is finished.
+/// Their models are returned and shut down right away, but their widgets are only
+/// dropped once they are removed from the parent widget.
+///
+/// While they fade out, removed elements are ignored by the factory,
+/// but they are still counted by position based APIs of the parent widget
+/// such as [`gtk::ListBoxRow::index()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FactoryTransition {
+    /// Fade new elements in and removed elements out.
+    Fade(Duration),
+}
+
+impl FactoryTransition {
+    const fn duration(self) -> Duration {
+        match self {
+            Self::Fade(duration) => duration,
+        }
+    }
+}
+
+/// A removed element whose widget is still animated.
+struct PendingExit<ReturnedWidget> {
+    id: usize,
+    root: gtk::Widget,
+    returned_widget: ReturnedWidget,
+    /// The widgets of the component are kept until the root widget is removed.
+    _widgets: Box<dyn Any>,
+}
+
+/// The removed elements that are still part of the parent widget.
+struct PendingExits<ReturnedWidget> {
+    remove: Box<dyn Fn(&ReturnedWidget)>,
+    exits: RefCell<Vec<PendingExit<ReturnedWidget>>>,
+    next_id: Cell<usize>,
+}
+
+impl<ReturnedWidget> PendingExits<ReturnedWidget> {
+    /// Removes the widget of an element once its animation is finished.
+    fn finish(&self, id: usize) {
+        let exit = {
+            let mut exits = self.exits.borrow_mut();
+            let position = exits.iter().position(|exit| exit.id == id);
+            position.map(|position| exits.remove(position))
+        };
+
+        if let Some(exit) = exit {
+            (self.remove)(&exit.returned_widget);
+        }
+    }
+
+    /// Stops all animations and removes the widgets right away.
+    fn finish_all(&self) {
+        let exits = std::mem::take(&mut *self.exits.borrow_mut());
+        for exit in exits {
+            stop_animation(&exit.root);
+            (self.remove)(&exit.returned_widget);
+        }
+    }
+}
+
+/// Plays the [`FactoryTransition`] of a factory and removes
+/// the widgets of removed elements once it is finished.
+///
+/// The animations of removed elements are finished right away
+/// when the parent widget is unmapped or this is dropped.
+pub(crate) struct Transition<Root, ReturnedWidget> {
+    duration: Duration,
+    view: gtk::Widget,
+    upcast: fn(&Root) -> gtk::Widget,
+    pending: Rc<PendingExits<ReturnedWidget>>,
+    unmap_handler: Option<glib::SignalHandlerId>,
+}
+
+impl<Root, ReturnedWidget> fmt::Debug for Transition<Root, ReturnedWidget> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transition")
+            .field("duration", &self.duration)
+            .field("pending", &self.pending.exits.borrow().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Root, ReturnedWidget> Transition<Root, ReturnedWidget> {
+    pub(crate) fn new<View>(view: &View, transition: FactoryTransition) -> Self
+    where
+        View: FactoryView<ReturnedWidget = ReturnedWidget> + Clone + 'static,
+        Root: IsA<gtk::Widget>,
+        ReturnedWidget: 'static,
+    {
+        let pending = Rc::new(PendingExits {
+            remove: Box::new({
+                let view = view.clone();
+                move |returned_widget| view.factory_remove(returned_widget)
+            }),
+            exits: RefCell::default(),
+            next_id: Cell::new(0),
+        });
+
+        // Unmapped widgets don't receive frame clock ticks, so the animations would never finish.
+        let unmap_handler = view.connect_unmap({
+            let pending = Rc::clone(&pending);
+            move |_| pending.finish_all()
+        });
+
+        Self {
+            duration: transition.duration(),
+            view: view.upcast_ref::<gtk::Widget>().clone(),
+            upcast: |root| root.upcast_ref::<gtk::Widget>().clone(),
+            pending,
+            unmap_handler: Some(unmap_handler),
+        }
+    }
+
+    /// Play the animation of a newly added element.
+    pub(crate) fn enter(&self, root: &Root) {
+        // Only animate elements that will actually be shown.
+        if self.view.is_mapped() {
+            animate_opacity(&(self.upcast)(root), self.duration, 0.0, 1.0, || ());
+        }
+    }
+
+    /// Play the animation of a removed element and remove its widget afterwards.
+    ///
+    /// `widgets` is dropped once the widget is removed.
+    pub(crate) fn exit<W: 'static>(&self, root: &Root, returned_widget: ReturnedWidget, widgets: W)
+    where
+        ReturnedWidget: 'static,
+    {
+        let root = (self.upcast)(root);
+        if !root.is_mapped() {
+            stop_animation(&root);
+            (self.pending.remove)(&returned_widget);
+            return;
+        }
+
+        // The element is gone already, so it shouldn't react to input anymore.
+        root.set_can_target(false);
+        root.set_can_focus(false);
+
+        let id = self.pending.next_id.get();
+        self.pending.next_id.set(id + 1);
+
+        let pending = Rc::downgrade(&self.pending);
+        animate_opacity(&root, self.duration, root.opacity(), 0.0, move || {
+            if let Some(pending) = pending.upgrade() {
+                pending.finish(id);
+            }
+        });
+
+        self.pending.exits.borrow_mut().push(PendingExit {
+            id,
+            root,
+            returned_widget,
+            _widgets: Box::new(widgets),
+        });
+    }
+
+    /// Removes the widgets of all removed elements right away.
+    pub(crate) fn finish(&self) {
+        self.pending.finish_all();
+    }
+}
+
+impl<Root, ReturnedWidget> Drop for Transition<Root, ReturnedWidget> {
+    fn drop(&mut self) {
+        if let Some(handler) = self.unmap_handler.take() {
+            self.view.disconnect(handler);
+        }
+        self.pending.finish_all();
+    }
+}
+
+/// Stops the animation that is currently played on `widget`.
+fn stop_animation(widget: &gtk::Widget) {
+    if let Some(tick) = unsafe { widget.steal_data::<gtk::TickCallbackId>(ANIMATION_KEY) } {
+        tick.remove();
+    }
+}
+
+/// Animate the opacity of `widget` and call `done` once the animation is finished.
+///
+/// A previous animation of `widget` is stopped.
+/// The opacity is only changed on frame clock ticks, so widgets that are never
+/// drawn keep their current opacity.
+fn animate_opacity<F>(widget: &gtk::Widget, duration: Duration, from: f64, to: f64, done: F)
+where
+    F: FnOnce() + 'static,
+{
+    stop_animation(widget);
+
+    let duration = duration.as_micros().max(1) as f64;
+    let start = Cell::new(None);
+    let done = Cell::new(Some(done));
+
+    let tick = widget.add_tick_callback(move |widget, clock| {
+        let now = clock.frame_time();
+        let start = start.get().unwrap_or_else(|| {
+            start.set(Some(now));
+            now
+        });
+
+        let progress = ((now - start) as f64 / duration).clamp(0.0, 1.0);
+        widget.set_opacity(from + (to - from) * ease_out_cubic(progress));
+
+        if progress < 1.0 {
+            glib::ControlFlow::Continue
+        } else {
+            // The callback is removed by returning `Break`.
+            drop(unsafe { widget.steal_data::<gtk::TickCallbackId>(ANIMATION_KEY) });
+            if let Some(done) = done.take() {
+                done();
+            }
+            glib::ControlFlow::Break
+        }
+    });
+    unsafe { widget.set_data(ANIMATION_KEY, tick) };
+}
+
+fn ease_out_cubic(progress: f64) -> f64 {
+    1.0 - (1.0 - progress).powi(3)
+}
@@ -0,0 +1,113 @@
+use std::sync::{Arc, RwLock};
+
+use super::DynamicIndex;
+
+/// A dynamic index of a node inside a [`FactoryTree`](super::FactoryTree).
+///
+/// Similar to [`DynamicIndex`], this index updates automatically when
+/// nodes are inserted, removed or moved, so it can be sent in messages
+/// to identify a node.
+///
+/// [`TreeIndex`] is a smart pointer so cloning will work similar to [`std::rc::Rc`] and will create
+/// a pointer to the same data.
+/// Two indices are only equal if they belong to the same node.
+#[derive(Debug, Clone)]
+pub struct TreeIndex {
+    inner: Arc<TreeIndexInner>,
+}
+
+#[derive(Debug)]
+struct TreeIndexInner {
+    /// The position among the siblings.
+    index: DynamicIndex,
+    parent: RwLock<Option<TreeIndex>>,
+}
+
+impl PartialEq for TreeIndex {
+    fn eq(&self, other: &Self) -> bool {
+        // The path of a removed node might refer to another node later.
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for TreeIndex {}
+
+impl TreeIndex {
+    /// Get the current position of the node among its siblings.
+    ///
+    /// This value is updated by the factory container and might change after each update function.
+    #[must_use]
+    pub fn current_index(&self) -> usize {
+        self.inner.index.current_index()
+    }
+
+    /// Get the current path of the node, starting with the
+    /// position of its top-level ancestor.
+    ///
+    /// This value is updated by the factory container and might change after each update function.
+    #[must_use]
+    pub fn current_path(&self) -> Vec<usize> {
+        let mut path = vec![self.current_index()];
+        let mut parent = self.parent();
+        while let Some(index) = parent {
+            path.push(index.current_index());
+            parent = index.parent();
+        }
+        path.reverse();
+        path
+    }
+
+    /// Returns [`true`] if the node or one of its ancestors was removed from the tree.
+    ///
+    /// The path of a removed node isn't updated anymore.
+    #[must_use]
+    pub fn is_removed(&self) -> bool {
+        self.inner.index.is_removed()
+    }
+
+    /// Returns the number of ancestors of the node.
+    ///
+    /// Top-level nodes have a depth of zero.
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut parent = self.parent();
+        while let Some(index) = parent {
+            depth += 1;
+            parent = index.parent();
+        }
+        depth
+    }
+
+    /// Returns the index of the parent node or [`None`] for top-level nodes.
+    #[must_use]
+    pub fn parent(&self) -> Option<Self> {
+        self.inner.parent.read().unwrap().clone()
+    }
+
+    pub(super) fn increment(&self) {
+        self.inner.index.increment();
+    }
+
+    pub(super) fn decrement(&self) {
+        self.inner.index.decrement();
+    }
+
+    pub(super) fn set_removed(&self) {
+        self.inner.index.set_removed();
+    }
+
+    pub(super) fn set_position(&self, parent: Option<Self>, index: usize) {
+        self.inner.index.set_value(index);
+        *self.inner.parent.write().unwrap() = parent;
+    }
+
+    pub(super) fn new(parent: Option<Self>, index: usize) -> Self {
+        Self {
+            inner: Arc::new(TreeIndexInner {
+                index: DynamicIndex::new(index),
+                parent: RwLock::new(parent),
+            }),
+        }
+    }
+}
@@ -0,0 +1,725 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops;
+use std::rc::Rc;
+
+use futures::future::AbortHandle;
+use gtk::prelude::{Cast, CastNone, ListItemExt, ListModelExt, ObjectExt};
+use gtk::{gio, glib};
+
+use crate::typed_view::RelmSelectionExt;
+use crate::{Receiver, Sender};
+
+use super::sender::{UNBOUND, VirtualFactorySender};
+use super::{VirtualFactoryComponent, VirtualFactoryView};
+
+/// The key used to store the [`Row`] on the root widget while it's unbound.
+const ROW_KEY: &str = "relm4-virtual-factory-row";
+
+/// Elements by uid, used to deliver input messages.
+type Elements = Rc<RefCell<HashMap<usize, glib::WeakRef<glib::BoxedAnyObject>>>>;
+
+/// The widgets of a row.
+struct Row<C: VirtualFactoryComponent> {
+    widgets: C::Widgets,
+    sender: VirtualFactorySender<C>,
+}
+
+/// The data of an element stored in the [`gio::ListStore`].
+struct Element<C: VirtualFactoryComponent> {
+    uid: usize,
+    model: C,
+    /// The row the element is currently bound to.
+    view: Option<(C::Root, Row<C>)>,
+}
+
+impl<C: VirtualFactoryComponent> Element<C> {
+    fn update(&mut self, message: C::Input, sender: VirtualFactorySender<C>) {
+        self.model.update(message, sender);
+        self.update_view();
+    }
+
+    fn update_view(&mut self) {
+        if let Some((_, row)) = &mut self.view {
+            self.model.update_view(&mut row.widgets, row.sender.clone());
+        }
+    }
+}
+
+fn bound_item<C: VirtualFactoryComponent>(
+    list_item: &gtk::ListItem,
+) -> (C::Root, glib::BoxedAnyObject) {
+    let root = list_item.child().and_downcast::<C::Root>().unwrap();
+    let item = list_item
+        .item()
+        .and_downcast::<glib::BoxedAnyObject>()
+        .unwrap();
+    (root, item)
+}
+
+#[derive(Debug)]
+/// A builder-pattern struct for building a [`VirtualFactory`].
+pub struct VirtualFactoryBuilder<C, S = gtk::NoSelection> {
+    _component: PhantomData<C>,
+    _selection: PhantomData<S>,
+}
+
+impl<C> Default for VirtualFactoryBuilder<C>
+where
+    C: VirtualFactoryComponent,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> VirtualFactoryBuilder<C>
+where
+    C: VirtualFactoryComponent,
+{
+    /// Creates a new [`VirtualFactoryBuilder`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            _component: PhantomData,
+            _selection: PhantomData,
+        }
+    }
+}
+
+impl<C, S> VirtualFactoryBuilder<C, S>
+where
+    C: VirtualFactoryComponent,
+    S: RelmSelectionExt,
+{
+    /// Sets a different selection model.
+    ///
+    /// By default, [`gtk::NoSelection`] is used.
+    #[must_use]
+    pub fn selection_model<S2: RelmSelectionExt>(self) -> VirtualFactoryBuilder<C, S2> {
+        VirtualFactoryBuilder {
+            _component: PhantomData,
+            _selection: PhantomData,
+        }
+    }
+
+    /// Launch the factory with a default parent widget.
+    #[must_use]
+    pub fn launch_default(self) -> VirtualFactoryConnector<C, S>
+    where
+        C::ParentWidget: Default,
+    {
+        self.launch(Default::default())
+    }
+
+    /// Launch the factory.
+    /// This is similar to [`Connector::launch`](crate::component::ComponentBuilder::launch).
+    pub fn launch(self, widget: C::ParentWidget) -> VirtualFactoryConnector<C, S> {
+        let (input_sender, input_receiver) = crate::channel::<(usize, C::Input)>();
+        let (output_sender, output_receiver) = crate::channel();
+
+        let store = gio::ListStore::new::<glib::BoxedAnyObject>();
+        let elements = Elements::default();
+
+        let factory = gtk::SignalListItemFactory::new();
+        {
+            let input_sender = input_sender.clone();
+            let output_sender = output_sender.clone();
+            factory.connect_setup(move |_, list_item| {
+                let list_item = list_item
+                    .downcast_ref::<gtk::ListItem>()
+                    .expect("Needs to be ListItem");
+
+                let root = C::init_root();
+                let sender =
+                    VirtualFactorySender::new(input_sender.clone(), output_sender.clone(), UNBOUND);
+                let widgets = C::init_widgets(&root, sender.clone());
+                unsafe { root.set_data(ROW_KEY, Row { widgets, sender }) };
+                list_item.set_child(Some(&root));
+            });
+        }
+
+        factory.connect_bind(move |_, list_item| {
+            let (root, item) = bound_item::<C>(list_item);
+            let mut element = item.borrow_mut::<Element<C>>();
+            let element = &mut *element;
+
+            let mut row: Row<C> = unsafe { root.steal_data(ROW_KEY) }.unwrap();
+            row.sender.set_uid(element.uid);
+            element
+                .model
+                .bind(&mut row.widgets, &root, row.sender.clone());
+            element.view = Some((root, row));
+        });
+
+        factory.connect_unbind(move |_, list_item| {
+            let (_, item) = bound_item::<C>(list_item);
+            let mut element = item.borrow_mut::<Element<C>>();
+            let element = &mut *element;
+
+            if let Some((root, mut row)) = element.view.take() {
+                element.model.unbind(&mut row.widgets, &root);
+                row.sender.set_uid(UNBOUND);
+                unsafe { root.set_data(ROW_KEY, row) };
+            }
+        });
+
+        let selection_model = S::new_model(store.clone().upcast());
+        widget.set_factory_model(&selection_model, &factory);
+
+        // Delivers input messages to the elements, regardless of whether they are visible.
+        let (runtime, abort_handle) = {
+            let elements = elements.clone();
+            let input_sender = input_sender.clone();
+            let output_sender = output_sender.clone();
+            futures::future::abortable(async move {
+                while let Some((uid, message)) = input_receiver.recv().await {
+                    let item = elements.borrow().get(&uid).and_then(|e| e.upgrade());
+                    if let Some(item) = item {
+                        let sender = VirtualFactorySender::new(
+                            input_sender.clone(),
+                            output_sender.clone(),
+                            uid,
+                        );
+                        item.borrow_mut::<Element<C>>().update(message, sender);
+                    }
+                }
+            })
+        };
+        crate::spawn_local(async move {
+            let _ = runtime.await;
+        });
+
+        VirtualFactoryConnector {
+            factory: VirtualFactory {
+                widget,
+                selection_model,
+                store,
+                elements,
+                input_sender,
+                output_sender,
+                abort_handle,
+                // 0 is always an invalid uid
+                uid_counter: 1,
+                _component: PhantomData,
+            },
+            output_receiver,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Second stage of the builder-pattern for building a [`VirtualFactory`].
+pub struct VirtualFactoryConnector<C, S>
+where
+    C: VirtualFactoryComponent,
+{
+    factory: VirtualFactory<C, S>,
+    output_receiver: Receiver<C::Output>,
+}
+
+impl<C, S> VirtualFactoryConnector<C, S>
+where
+    C: VirtualFactoryComponent,
+{
+    /// Forwards output events from the elements to the designated sender.
+    pub fn forward<F, Msg>(self, sender_: &Sender<Msg>, f: F) -> VirtualFactory<C, S>
+    where
+        F: Fn(C::Output) -> Msg + Send + 'static,
+        C::Output: Send,
+        Msg: Send + 'static,
+    {
+        let Self {
+            factory,
+            output_receiver,
+        } = self;
+
+        let sender_clone = sender_.clone();
+        crate::spawn(async move {
+            while let Some(msg) = output_receiver.recv().await {
+                if sender_clone.send(f(msg)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        factory
+    }
+
+    /// Ignore output events from the elements and just create the [`VirtualFactory`].
+    pub fn detach(self) -> VirtualFactory<C, S> {
+        self.factory
+    }
+}
+
+/// A factory that only creates widgets for the visible elements.
+///
+/// The elements are stored in a [`gio::ListStore`] and displayed by a
+/// [`gtk::ListView`] or [`gtk::GridView`].
+/// Widgets are only created for the visible rows and reused while scrolling,
+/// so this factory can handle hundreds of thousands of elements.
+/// Each element still receives and processes its own messages,
+/// even if it isn't visible.
+///
+/// Unlike [`FactoryVecDeque`](crate::factory::FactoryVecDeque), all changes are
+/// applied immediately.
+pub struct VirtualFactory<C: VirtualFactoryComponent, S = gtk::NoSelection> {
+    widget: C::ParentWidget,
+    selection_model: S,
+    store: gio::ListStore,
+    elements: Elements,
+    input_sender: Sender<(usize, C::Input)>,
+    output_sender: Sender<C::Output>,
+    abort_handle: AbortHandle,
+    uid_counter: usize,
+    _component: PhantomData<C>,
+}
+
+impl<C: VirtualFactoryComponent, S: fmt::Debug> fmt::Debug for VirtualFactory<C, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VirtualFactory")
+            .field("widget", &self.widget)
+            .field("selection_model", &self.selection_model)
+            .field("store", &self.store)
+            .field("uid_counter", &self.uid_counter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: VirtualFactoryComponent, S> Drop for VirtualFactory<C, S> {
+    fn drop(&mut self) {
+        self.store.remove_all();
+        self.abort_handle.abort();
+    }
+}
+
+impl<C> VirtualFactory<C>
+where
+    C: VirtualFactoryComponent,
+{
+    /// Creates a new [`VirtualFactoryBuilder`].
+    #[must_use]
+    pub fn builder() -> VirtualFactoryBuilder<C> {
+        VirtualFactoryBuilder::new()
+    }
+}
+
+impl<C, S> VirtualFactory<C, S>
+where
+    C: VirtualFactoryComponent,
+{
+    /// Returns the number of elements in the [`VirtualFactory`].
+    pub fn len(&self) -> u32 {
+        self.store.n_items()
+    }
+
+    /// Returns true if the [`VirtualFactory`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the widget that displays the elements.
+    pub const fn widget(&self) -> &C::ParentWidget {
+        &self.widget
+    }
+
+    /// Returns the selection model of the widget.
+    pub const fn selection_model(&self) -> &S {
+        &self.selection_model
+    }
+
+    fn new_element(&mut self, init: C::Init) -> glib::BoxedAnyObject {
+        let uid = self.uid_counter;
+        self.uid_counter += 1;
+
+        let sender =
+            VirtualFactorySender::new(self.input_sender.clone(), self.output_sender.clone(), uid);
+        let model = C::init_model(init, sender);
+
+        let item = glib::BoxedAnyObject::new(Element {
+            uid,
+            model,
+            view: None,
+        });
+        self.elements.borrow_mut().insert(uid, item.downgrade());
+        item
+    }
+
+    fn uid(item: &glib::Object) -> usize {
+        let item = item.downcast_ref::<glib::BoxedAnyObject>().unwrap();
+        item.borrow::<Element<C>>().uid
+    }
+
+    /// Appends an element.
+    pub fn push_back(&mut self, init: C::Init) {
+        let item = self.new_element(init);
+        self.store.append(&item);
+    }
+
+    /// Appends all elements of an iterator.
+    ///
+    /// This is much faster than calling [`Self::push_back`] for each element.
+    pub fn extend<I: IntoIterator<Item = C::Init>>(&mut self, iter: I) {
+        let items: Vec<glib::BoxedAnyObject> = iter
+            .into_iter()
+            .map(|init| self.new_element(init))
+            .collect();
+        self.store.extend_from_slice(&items);
+    }
+
+    /// Inserts an element at `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is greater than the length of the factory.
+    pub fn insert(&mut self, position: u32, init: C::Init) {
+        let item = self.new_element(init);
+        self.store.insert(position, &item);
+    }
+
+    /// Removes the element at `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is out of bounds.
+    pub fn remove(&mut self, position: u32) {
+        let item = self
+            .store
+            .item(position)
+            .expect("Called `remove` on an invalid position");
+        self.elements.borrow_mut().remove(&Self::uid(&item));
+        self.store.remove(position);
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.elements.borrow_mut().clear();
+        self.store.remove_all();
+    }
+
+    /// Returns the element at `position`.
+    ///
+    /// Returns [`None`] if `position` is invalid.
+    pub fn get(&self, position: u32) -> Option<VirtualFactoryElement<C>> {
+        self.store.item(position).map(|item| VirtualFactoryElement {
+            inner: item.downcast().unwrap(),
+            _component: PhantomData,
+        })
+    }
+
+    /// Send a message to the element at `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is out of bounds.
+    pub fn send(&self, position: u32, msg: C::Input) {
+        let item = self
+            .store
+            .item(position)
+            .expect("Called `send` on an invalid position");
+        self.input_sender.send((Self::uid(&item), msg)).unwrap();
+    }
+
+    /// Send clone of a message to all of the elements in position order.
+    pub fn broadcast(&self, msg: C::Input)
+    where
+        C::Input: Clone,
+    {
+        for position in 0..self.store.n_items() {
+            let item = self.store.item(position).unwrap();
+            self.input_sender
+                .send((Self::uid(&item), msg.clone()))
+                .unwrap();
+        }
+    }
+}
+
+/// An element of a [`VirtualFactory`].
+///
+/// The interface is similar to [`std::cell::RefCell`].
+pub struct VirtualFactoryElement<C> {
+    inner: glib::BoxedAnyObject,
+    _component: PhantomData<*const C>,
+}
+
+impl<C> fmt::Debug for VirtualFactoryElement<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VirtualFactoryElement")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<C: VirtualFactoryComponent> VirtualFactoryElement<C> {
+    /// Immutably borrows the model of the element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is currently mutably borrowed.
+    #[must_use]
+    pub fn borrow(&self) -> Ref<'_, C> {
+        Ref::map(self.inner.borrow::<Element<C>>(), |element| &element.model)
+    }
+
+    /// Mutably borrows the model of the element.
+    ///
+    /// If the element is visible, its view is updated after the guard goes out of scope.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is currently borrowed.
+    pub fn borrow_mut(&self) -> VirtualFactoryElementGuard<'_, C> {
+        VirtualFactoryElementGuard {
+            inner: self.inner.borrow_mut(),
+        }
+    }
+}
+
+/// Provides mutable access to the model of a [`VirtualFactoryElement`].
+///
+/// If the element is visible, its view is updated after the guard goes out of scope.
+#[must_use]
+pub struct VirtualFactoryElementGuard<'a, C: VirtualFactoryComponent> {
+    inner: RefMut<'a, Element<C>>,
+}
+
+impl<C: VirtualFactoryComponent + fmt::Debug> fmt::Debug for VirtualFactoryElementGuard<'_, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VirtualFactoryElementGuard")
+            .field("model", &self.inner.model)
+            .finish()
+    }
+}
+
+impl<C: VirtualFactoryComponent> ops::Deref for VirtualFactoryElementGuard<'_, C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner.model
+    }
+}
+
+impl<C: VirtualFactoryComponent> ops::DerefMut for VirtualFactoryElementGuard<'_, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner.model
+    }
+}
+
+impl<C: VirtualFactoryComponent> Drop for VirtualFactoryElementGuard<'_, C> {
+    fn drop(&mut self) {
+        self.inner.update_view();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use gtk::glib::MainContext;
+    use gtk::prelude::{GtkWindowExt, ListModelExt, ObjectExt};
+
+    use super::{
+        Element, ROW_KEY, Row, VirtualFactory, VirtualFactoryComponent, VirtualFactorySender,
+    };
+
+    #[derive(Debug)]
+    struct Counter {
+        value: u8,
+    }
+
+    impl VirtualFactoryComponent for Counter {
+        type ParentWidget = gtk::ListView;
+        type Input = u8;
+        type Output = u8;
+        type Init = u8;
+        type Root = gtk::Label;
+        /// The label and the sender of the row.
+        type Widgets = (gtk::Label, VirtualFactorySender<Self>);
+
+        fn init_model(value: Self::Init, _: VirtualFactorySender<Self>) -> Self {
+            Self { value }
+        }
+
+        fn init_root() -> Self::Root {
+            gtk::Label::default()
+        }
+
+        fn init_widgets(root: &Self::Root, sender: VirtualFactorySender<Self>) -> Self::Widgets {
+            (root.clone(), sender)
+        }
+
+        fn update(&mut self, add: Self::Input, sender: VirtualFactorySender<Self>) {
+            self.value += add;
+            sender.output(self.value).unwrap();
+        }
+
+        fn update_view(&self, (label, _): &mut Self::Widgets, _: VirtualFactorySender<Self>) {
+            label.set_label(&self.value.to_string());
+        }
+    }
+
+    fn values(factory: &VirtualFactory<Counter>) -> Vec<u8> {
+        (0..factory.len())
+            .map(|position| factory.get(position).unwrap().borrow().value)
+            .collect()
+    }
+
+    fn uids(factory: &VirtualFactory<Counter>) -> Vec<usize> {
+        (0..factory.len())
+            .map(|position| VirtualFactory::<Counter>::uid(&factory.store.item(position).unwrap()))
+            .collect()
+    }
+
+    fn run_main_loop() {
+        let main_ctx = MainContext::default();
+        while main_ctx.iteration(false) {}
+    }
+
+    /// Runs the main loop until `condition` is true, for example
+    /// until the view allocated its rows.
+    fn run_main_loop_until(condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "Timed out waiting for the view");
+            run_main_loop();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Returns the root and the row sender of the element at `position` if it's bound.
+    fn bound_row(
+        factory: &VirtualFactory<Counter>,
+        position: u32,
+    ) -> Option<(gtk::Label, VirtualFactorySender<Counter>)> {
+        let element = factory.get(position)?;
+        let element = element.inner.borrow::<Element<Counter>>();
+        let (root, row) = element.view.as_ref()?;
+        Some((root.clone(), row.widgets.1.clone()))
+    }
+
+    /// Returns the position of the element that is bound to `root`.
+    fn bound_position(factory: &VirtualFactory<Counter>, root: &gtk::Label) -> Option<u32> {
+        (0..factory.len()).find(|position| {
+            bound_row(factory, *position).is_some_and(|(bound_root, _)| &bound_root == root)
+        })
+    }
+
+    #[gtk::test]
+    fn uid_bookkeeping() {
+        let mut factory = VirtualFactory::<Counter>::builder()
+            .launch_default()
+            .detach();
+
+        factory.push_back(0);
+        factory.extend([1, 2]);
+        factory.insert(1, 3);
+        assert_eq!(values(&factory), [0, 3, 1, 2]);
+        assert_eq!(uids(&factory), [1, 4, 2, 3]);
+        assert_eq!(factory.elements.borrow().len(), 4);
+
+        // Removed elements are dropped and their uids are never reused.
+        let removed = factory.get(1).unwrap().inner.downgrade();
+        factory.remove(1);
+        assert!(removed.upgrade().is_none());
+        assert!(!factory.elements.borrow().contains_key(&4));
+        factory.push_back(5);
+        assert_eq!(values(&factory), [0, 1, 2, 5]);
+        assert_eq!(uids(&factory), [1, 2, 3, 5]);
+
+        factory.clear();
+        assert!(factory.is_empty());
+        assert!(factory.elements.borrow().is_empty());
+        factory.push_back(6);
+        assert_eq!(uids(&factory), [6]);
+    }
+
+    #[gtk::test]
+    fn send_and_broadcast() {
+        let (sender, receiver) = crate::channel();
+        let mut factory = VirtualFactory::<Counter>::builder()
+            .launch_default()
+            .forward(&sender, |value| value);
+
+        factory.extend([0, 10, 20]);
+
+        factory.send(1, 5);
+        run_main_loop();
+        assert_eq!(values(&factory), [0, 15, 20]);
+        assert_eq!(receiver.recv_sync(), Some(15));
+
+        // Messages for removed elements are dropped.
+        factory.send(0, 1);
+        factory.remove(0);
+        factory.broadcast(2);
+        run_main_loop();
+        assert_eq!(values(&factory), [17, 22]);
+
+        // Elements receive broadcasts in position order.
+        assert_eq!(receiver.recv_sync(), Some(17));
+        assert_eq!(receiver.recv_sync(), Some(22));
+    }
+
+    #[gtk::test]
+    fn bound_rows() {
+        let (sender, receiver) = crate::channel();
+        let mut factory = VirtualFactory::<Counter>::builder()
+            .launch_default()
+            .forward(&sender, |value| value);
+        factory.extend(0..200);
+
+        let scrolled_window = gtk::ScrolledWindow::builder()
+            .child(factory.widget())
+            .build();
+        let window = gtk::Window::builder()
+            .default_width(100)
+            .default_height(100)
+            .child(&scrolled_window)
+            .build();
+        window.present();
+        run_main_loop_until(|| bound_row(&factory, 0).is_some());
+
+        // Only the visible elements are bound to a row.
+        assert!(bound_row(&factory, 199).is_none());
+        let (root, row_sender) = bound_row(&factory, 0).unwrap();
+        assert_eq!(root.label(), "0");
+
+        // The row sender reaches the bound element and its view is updated.
+        row_sender.input(5);
+        run_main_loop();
+        assert_eq!(factory.get(0).unwrap().borrow().value, 5);
+        assert_eq!(receiver.recv_sync(), Some(5));
+        assert_eq!(root.label(), "5");
+
+        // Elements that aren't bound are still updated.
+        factory.send(199, 1);
+        run_main_loop();
+        assert_eq!(factory.get(199).unwrap().borrow().value, 200);
+        assert_eq!(receiver.recv_sync(), Some(200));
+
+        // The row of the removed element is recycled for another element
+        // and its sender follows the newly bound element.
+        factory.remove(0);
+        run_main_loop_until(|| bound_position(&factory, &root).is_some());
+        let position = bound_position(&factory, &root).unwrap();
+        let value = factory.get(position).unwrap().borrow().value;
+        assert_eq!(root.label(), value.to_string());
+
+        row_sender.input(1);
+        run_main_loop();
+        assert_eq!(factory.get(position).unwrap().borrow().value, value + 1);
+        assert_eq!(receiver.recv_sync(), Some(value + 1));
+        assert_eq!(root.label(), (value + 1).to_string());
+
+        // Unbinding returns the row to the root and disconnects its sender.
+        factory.clear();
+        run_main_loop();
+        assert!(unsafe { root.data::<Row<Counter>>(ROW_KEY) }.is_some());
+        row_sender.input(1);
+        run_main_loop();
+        assert!(receiver.0.try_recv().is_err());
+
+        window.destroy();
+    }
+}
@@ -0,0 +1,10 @@
+mod collection;
+mod sender;
+mod traits;
+
+pub use collection::{
+    VirtualFactory, VirtualFactoryBuilder, VirtualFactoryConnector, VirtualFactoryElement,
+    VirtualFactoryElementGuard,
+};
+pub use sender::VirtualFactorySender;
+pub use traits::{VirtualFactoryComponent, VirtualFactoryView};
@@ -0,0 +1,89 @@
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::Sender;
+
+use super::VirtualFactoryComponent;
+
+/// The uid of rows that aren't bound to an element.
+pub(super) const UNBOUND: usize = 0;
+
+/// Contain senders used by the elements of a [`VirtualFactory`](super::VirtualFactory).
+///
+/// The senders passed to [`init_widgets()`](VirtualFactoryComponent::init_widgets)
+/// belong to a row and always send input messages to the element that is
+/// currently bound to this row.
+/// Messages sent from rows that aren't bound are ignored.
+pub struct VirtualFactorySender<C: VirtualFactoryComponent> {
+    input: Sender<(usize, C::Input)>,
+    output: Sender<C::Output>,
+    uid: Arc<AtomicUsize>,
+}
+
+impl<C: VirtualFactoryComponent> fmt::Debug for VirtualFactorySender<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VirtualFactorySender")
+            .field("input", &self.input)
+            .field("output", &self.output)
+            .field("uid", &self.uid)
+            .finish()
+    }
+}
+
+impl<C: VirtualFactoryComponent> Clone for VirtualFactorySender<C> {
+    fn clone(&self) -> Self {
+        Self {
+            input: self.input.clone(),
+            output: self.output.clone(),
+            uid: self.uid.clone(),
+        }
+    }
+}
+
+impl<C: VirtualFactoryComponent> VirtualFactorySender<C> {
+    pub(super) fn new(
+        input: Sender<(usize, C::Input)>,
+        output: Sender<C::Output>,
+        uid: usize,
+    ) -> Self {
+        Self {
+            input,
+            output,
+            uid: Arc::new(AtomicUsize::new(uid)),
+        }
+    }
+
+    /// Changes the element that receives the input messages.
+    pub(super) fn set_uid(&self, uid: usize) {
+        self.uid.store(uid, Ordering::Release);
+    }
+
+    /// Emit an input to the element.
+    pub fn input(&self, message: C::Input) {
+        let uid = self.uid.load(Ordering::Acquire);
+        if uid != UNBOUND {
+            // The message is dropped if the factory was already dropped.
+            self.input.send((uid, message)).ok();
+        }
+    }
+
+    /// Emit an output to the component the factory belongs to.
+    ///
+    /// Returns [`Err`] if all receivers were dropped,
+    /// for example by [`detach`].
+    ///
+    /// [`detach`]: super::VirtualFactoryConnector::detach
+    pub fn output(&self, message: C::Output) -> Result<(), C::Output> {
+        self.output.send(message)
+    }
+
+    /// Retrieve the sender for output messages.
+    ///
+    /// Useful to forward outputs from another component. If you just need to send output messages,
+    /// [`output()`][Self::output] is more concise.
+    #[must_use]
+    pub fn output_sender(&self) -> &Sender<C::Output> {
+        &self.output
+    }
+}
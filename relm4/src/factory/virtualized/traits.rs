@@ -0,0 +1,110 @@
+//! Traits for virtualized factories.
+
+use std::fmt::Debug;
+
+use gtk::prelude::IsA;
+
+use super::VirtualFactorySender;
+
+/// A component that's stored inside a [`VirtualFactory`](super::VirtualFactory).
+///
+/// Similar to [`FactoryComponent`](crate::factory::FactoryComponent), but
+/// widgets are only created for the rows that are currently visible
+/// and are reused for different elements while scrolling.
+/// Therefore, the widgets are created independently of the model in
+/// [`init_widgets()`](Self::init_widgets) and are connected to a model in
+/// [`bind()`](Self::bind).
+///
+/// Messages sent by the widgets are always delivered to the element that
+/// is currently bound to the row.
+pub trait VirtualFactoryComponent: Sized + 'static {
+    /// Container widget that displays the rows of the factory.
+    type ParentWidget: VirtualFactoryView;
+
+    /// The message type that the factory component accepts as inputs.
+    type Input: Debug + 'static;
+
+    /// The message type that the factory component provides as outputs.
+    type Output: Debug + 'static;
+
+    /// The parameter used to initialize the factory component.
+    type Init;
+
+    /// The top-level widget of a row.
+    type Root: IsA<gtk::Widget> + Debug + Clone;
+
+    /// The type that's used for storing widgets created for a row.
+    type Widgets: 'static;
+
+    /// Initializes the model.
+    fn init_model(init: Self::Init, sender: VirtualFactorySender<Self>) -> Self;
+
+    /// Initializes the root widget of a new row.
+    fn init_root() -> Self::Root;
+
+    /// Initializes the widgets of a new row.
+    ///
+    /// The widgets are reused for several elements, so they
+    /// shouldn't contain any data of a specific element.
+    fn init_widgets(root: &Self::Root, sender: VirtualFactorySender<Self>) -> Self::Widgets;
+
+    /// Binds the widgets of a row to this element.
+    ///
+    /// The default implementation calls [`update_view()`](Self::update_view).
+    fn bind(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        _root: &Self::Root,
+        sender: VirtualFactorySender<Self>,
+    ) {
+        self.update_view(widgets, sender);
+    }
+
+    /// Undo the steps of [`bind()`](Self::bind) if necessary.
+    #[allow(unused)]
+    fn unbind(&mut self, widgets: &mut Self::Widgets, root: &Self::Root) {}
+
+    /// Processes inputs received by the component.
+    ///
+    /// This is called for all elements, regardless of whether they are visible.
+    #[allow(unused)]
+    fn update(&mut self, message: Self::Input, sender: VirtualFactorySender<Self>) {}
+
+    /// Updates the view after the model has been updated.
+    ///
+    /// This is only called if the element is bound to a row.
+    #[allow(unused)]
+    fn update_view(&self, widgets: &mut Self::Widgets, sender: VirtualFactorySender<Self>) {}
+}
+
+/// A widget that can display the rows of a [`VirtualFactory`](super::VirtualFactory).
+pub trait VirtualFactoryView: IsA<gtk::Widget> {
+    /// Sets the model and the factory of the view.
+    fn set_factory_model(
+        &self,
+        model: &impl IsA<gtk::SelectionModel>,
+        factory: &gtk::SignalListItemFactory,
+    );
+}
+
+impl VirtualFactoryView for gtk::ListView {
+    fn set_factory_model(
+        &self,
+        model: &impl IsA<gtk::SelectionModel>,
+        factory: &gtk::SignalListItemFactory,
+    ) {
+        self.set_model(Some(model));
+        self.set_factory(Some(factory));
+    }
+}
+
+impl VirtualFactoryView for gtk::GridView {
+    fn set_factory_model(
+        &self,
+        model: &impl IsA<gtk::SelectionModel>,
+        factory: &gtk::SignalListItemFactory,
+    ) {
+        self.set_model(Some(model));
+        self.set_factory(Some(factory));
+    }
+}
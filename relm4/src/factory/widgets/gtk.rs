@@ -1,6 +1,9 @@
-use gtk::prelude::{BoxExt, Cast, FlowBoxChildExt, GridExt, ListBoxRowExt, WidgetExt};
+use gtk::glib;
+use gtk::prelude::{
+    BoxExt, Cast, FlowBoxChildExt, GridExt, ListBoxRowExt, OrientableExt, WidgetExt,
+};
 
-use crate::factory::{FactoryView, positions};
+use crate::factory::{FactoryView, SelectableFactoryView, positions};
 
 impl FactoryView for gtk::Box {
     type Children = gtk::Widget;
@@ -275,6 +278,43 @@ impl FactoryView for gtk::ListBox {
     }
 }
 
+impl SelectableFactoryView for gtk::ListBox {
+    fn factory_set_selection_mode(&self, mode: gtk::SelectionMode) {
+        self.set_selection_mode(mode);
+    }
+
+    fn factory_selected(&self) -> Vec<Self::ReturnedWidget> {
+        self.selected_rows()
+    }
+
+    fn factory_is_selected(&self, widget: &Self::ReturnedWidget) -> bool {
+        widget.is_selected()
+    }
+
+    fn factory_select(&self, widget: &Self::ReturnedWidget) {
+        self.select_row(Some(widget));
+    }
+
+    fn factory_unselect(&self, widget: &Self::ReturnedWidget) {
+        self.unselect_row(widget);
+    }
+
+    fn factory_select_all(&self) {
+        self.select_all();
+    }
+
+    fn factory_unselect_all(&self) {
+        self.unselect_all();
+    }
+
+    fn factory_connect_selection_changed<F: Fn(&Self) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_selected_rows_changed(f)
+    }
+}
+
 impl FactoryView for gtk::FlowBox {
     type Children = gtk::Widget;
     type ReturnedWidget = gtk::FlowBoxChild;
@@ -348,6 +388,612 @@ impl FactoryView for gtk::FlowBox {
     }
 }
 
+impl SelectableFactoryView for gtk::FlowBox {
+    fn factory_set_selection_mode(&self, mode: gtk::SelectionMode) {
+        self.set_selection_mode(mode);
+    }
+
+    fn factory_selected(&self) -> Vec<Self::ReturnedWidget> {
+        self.selected_children()
+    }
+
+    fn factory_is_selected(&self, widget: &Self::ReturnedWidget) -> bool {
+        widget.is_selected()
+    }
+
+    fn factory_select(&self, widget: &Self::ReturnedWidget) {
+        self.select_child(widget);
+    }
+
+    fn factory_unselect(&self, widget: &Self::ReturnedWidget) {
+        self.unselect_child(widget);
+    }
+
+    fn factory_select_all(&self) {
+        self.select_all();
+    }
+
+    fn factory_unselect_all(&self) {
+        self.unselect_all();
+    }
+
+    fn factory_connect_selection_changed<F: Fn(&Self) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_selected_children_changed(f)
+    }
+}
+
+impl FactoryView for gtk::Notebook {
+    type Children = gtk::Widget;
+    type ReturnedWidget = gtk::Widget;
+    type Position = positions::NotebookPosition;
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        self.detach_tab(widget);
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        self.append_page(widget, None::<&gtk::Widget>);
+        self.factory_update_position(widget, position);
+        widget.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        self.prepend_page(widget, None::<&gtk::Widget>);
+        self.factory_update_position(widget, position);
+        widget.clone()
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+        other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        let page_num = self.page_num(other).map(|num| num + 1);
+        self.insert_page(widget, None::<&gtk::Widget>, page_num);
+        self.factory_update_position(widget, position);
+        widget.clone()
+    }
+
+    fn factory_move_after(&self, widget: &Self::ReturnedWidget, other: &Self::ReturnedWidget) {
+        if let (Some(current), Some(other)) = (self.page_num(widget), self.page_num(other)) {
+            // The other pages are shifted when the page is moved to the back.
+            let target = if current < other { other } else { other + 1 };
+            self.reorder_child(widget, Some(target));
+        }
+    }
+
+    fn factory_move_start(&self, widget: &Self::ReturnedWidget) {
+        self.reorder_child(widget, Some(0));
+    }
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+
+    fn factory_update_position(&self, widget: &Self::ReturnedWidget, position: &Self::Position) {
+        if let Some(label) = &position.label {
+            self.set_tab_label_text(widget, label);
+        } else {
+            // Let GTK create the default label again.
+            self.set_tab_label(widget, None::<&gtk::Widget>);
+        }
+    }
+}
+
+/// The name of the [`gtk::Paned`]s that are nested to display more than two elements.
+const NESTED_PANED_NAME: &str = "relm4-nested-paned";
+
+/// Returns the chain of nested [`gtk::Paned`]s and the elements displayed by them.
+fn paned_chain(paned: &gtk::Paned) -> (Vec<gtk::Paned>, Vec<gtk::Widget>) {
+    let mut paneds = vec![paned.clone()];
+    let mut children = Vec::new();
+
+    let mut current = paned.clone();
+    loop {
+        children.extend(current.start_child());
+
+        let Some(end) = current.end_child() else {
+            break;
+        };
+        match end.downcast::<gtk::Paned>() {
+            Ok(nested) if nested.widget_name() == NESTED_PANED_NAME => {
+                paneds.push(nested.clone());
+                current = nested;
+            }
+            Ok(end) => {
+                children.push(end.upcast());
+                break;
+            }
+            Err(end) => {
+                children.push(end);
+                break;
+            }
+        }
+    }
+
+    (paneds, children)
+}
+
+/// Creates a [`gtk::Paned`] that is nested into `paned` and
+/// shares its orientation, handle and resize and shrink behavior.
+fn nested_paned(
+    paned: &gtk::Paned,
+    start: Option<&gtk::Widget>,
+    end: Option<&gtk::Widget>,
+) -> gtk::Paned {
+    let nested = gtk::Paned::builder()
+        .name(NESTED_PANED_NAME)
+        .orientation(paned.orientation())
+        .wide_handle(paned.is_wide_handle())
+        .resize_start_child(paned.resizes_start_child())
+        .resize_end_child(paned.resizes_end_child())
+        .shrink_start_child(paned.shrinks_start_child())
+        .shrink_end_child(paned.shrinks_end_child())
+        .build();
+    nested.set_start_child(start);
+    nested.set_end_child(end);
+    nested
+}
+
+/// Inserts an element at `index` by splicing a single nested [`gtk::Paned`] into the chain.
+fn insert_paned_child(paned: &gtk::Paned, index: usize, widget: &gtk::Widget) {
+    let (paneds, children) = paned_chain(paned);
+
+    match children.len() {
+        0 => paned.set_start_child(Some(widget)),
+        1 if index == 0 => {
+            paned.set_start_child(None::<&gtk::Widget>);
+            paned.set_end_child(Some(&children[0]));
+            paned.set_start_child(Some(widget));
+        }
+        1 => paned.set_end_child(Some(widget)),
+        len if index >= len => {
+            // The last element moves into a new paned next to the appended one.
+            let last = &paneds[len - 2];
+            last.set_end_child(None::<&gtk::Widget>);
+            let nested = nested_paned(paned, Some(&children[len - 1]), Some(widget));
+            last.set_end_child(Some(&nested));
+        }
+        _ if index == 0 => {
+            let end = paned.end_child();
+            paned.set_start_child(None::<&gtk::Widget>);
+            paned.set_end_child(None::<&gtk::Widget>);
+            let nested = nested_paned(paned, Some(&children[0]), end.as_ref());
+            paned.set_start_child(Some(widget));
+            paned.set_end_child(Some(&nested));
+        }
+        _ => {
+            // The new paned takes the place of everything after the previous element.
+            let previous = &paneds[index - 1];
+            let end = previous.end_child();
+            previous.set_end_child(None::<&gtk::Widget>);
+            let nested = nested_paned(paned, Some(widget), end.as_ref());
+            previous.set_end_child(Some(&nested));
+        }
+    }
+}
+
+/// Removes an element and the nested [`gtk::Paned`] that displayed it.
+/// Returns the position of the element if it was found.
+fn remove_paned_child(paned: &gtk::Paned, widget: &gtk::Widget) -> Option<usize> {
+    let (paneds, children) = paned_chain(paned);
+    let index = children.iter().position(|child| child == widget)?;
+
+    match children.len() {
+        1 => paned.set_start_child(None::<&gtk::Widget>),
+        2 if index == 0 => {
+            let end = paned.end_child();
+            paned.set_start_child(None::<&gtk::Widget>);
+            paned.set_end_child(None::<&gtk::Widget>);
+            paned.set_start_child(end.as_ref());
+        }
+        2 => paned.set_end_child(None::<&gtk::Widget>),
+        len if index == len - 1 => {
+            // The previous element replaces the last nested paned.
+            let last = &paneds[len - 2];
+            last.set_start_child(None::<&gtk::Widget>);
+            last.set_end_child(None::<&gtk::Widget>);
+            paneds[len - 3].set_end_child(Some(&children[len - 2]));
+        }
+        _ if index == 0 => {
+            // The content of the first nested paned moves into the root paned.
+            let next = &paneds[1];
+            let (start, end) = (next.start_child(), next.end_child());
+            next.set_start_child(None::<&gtk::Widget>);
+            next.set_end_child(None::<&gtk::Widget>);
+            paned.set_start_child(start.as_ref());
+            paned.set_end_child(end.as_ref());
+        }
+        _ => {
+            let current = &paneds[index];
+            let end = current.end_child();
+            current.set_start_child(None::<&gtk::Widget>);
+            current.set_end_child(None::<&gtk::Widget>);
+            paneds[index - 1].set_end_child(end.as_ref());
+        }
+    }
+
+    Some(index)
+}
+
+/// Returns the position after `other` or the number of elements if `other` wasn't found.
+fn paned_index_after(paned: &gtk::Paned, other: &gtk::Widget) -> usize {
+    let (_, children) = paned_chain(paned);
+    children
+        .iter()
+        .position(|child| child == other)
+        .map_or(children.len(), |index| index + 1)
+}
+
+/// Elements are split by nested [`gtk::Paned`]s if there are more than two of them.
+impl FactoryView for gtk::Paned {
+    type Children = gtk::Widget;
+    type ReturnedWidget = gtk::Widget;
+    type Position = ();
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        remove_paned_child(self, widget);
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        _position: &(),
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        insert_paned_child(self, usize::MAX, widget);
+        widget.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        _position: &(),
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        insert_paned_child(self, 0, widget);
+        widget.clone()
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        _position: &(),
+        other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        insert_paned_child(self, paned_index_after(self, other), widget);
+        widget.clone()
+    }
+
+    fn factory_move_after(&self, widget: &Self::ReturnedWidget, other: &Self::ReturnedWidget) {
+        if remove_paned_child(self, widget).is_some() {
+            insert_paned_child(self, paned_index_after(self, other), widget);
+        }
+    }
+
+    fn factory_move_start(&self, widget: &Self::ReturnedWidget) {
+        if remove_paned_child(self, widget).is_some() {
+            insert_paned_child(self, 0, widget);
+        }
+    }
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+}
+
+/// Returns the slot of the [`gtk::CenterBox`] that displays `widget`.
+fn center_box_slot(
+    center_box: &gtk::CenterBox,
+    widget: &gtk::Widget,
+) -> Option<positions::CenterBoxPosition> {
+    if center_box.start_widget().as_ref() == Some(widget) {
+        Some(positions::CenterBoxPosition::Start)
+    } else if center_box.center_widget().as_ref() == Some(widget) {
+        Some(positions::CenterBoxPosition::Center)
+    } else if center_box.end_widget().as_ref() == Some(widget) {
+        Some(positions::CenterBoxPosition::End)
+    } else {
+        None
+    }
+}
+
+/// **The elements are append-only.**
+///
+/// [`gtk::CenterBox`] has a start, a center and an end slot that hold one element each,
+/// so adding an element to an occupied [`CenterBoxPosition`] slot replaces the previous one.
+/// Prepending and inserting elements adds them like appending, moving elements has no effect
+/// and [`FactoryView::factory_update_position`] only moves the element if its slot changed.
+/// Only use this view for factories that never reorder their elements.
+///
+/// [`CenterBoxPosition`]: positions::CenterBoxPosition
+impl FactoryView for gtk::CenterBox {
+    type Children = gtk::Widget;
+    type ReturnedWidget = gtk::Widget;
+    type Position = positions::CenterBoxPosition;
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        match center_box_slot(self, widget) {
+            Some(positions::CenterBoxPosition::Start) => {
+                self.set_start_widget(None::<&gtk::Widget>);
+            }
+            Some(positions::CenterBoxPosition::Center) => {
+                self.set_center_widget(None::<&gtk::Widget>);
+            }
+            Some(positions::CenterBoxPosition::End) => self.set_end_widget(None::<&gtk::Widget>),
+            None => (),
+        }
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        match position {
+            positions::CenterBoxPosition::Start => self.set_start_widget(Some(widget)),
+            positions::CenterBoxPosition::Center => self.set_center_widget(Some(widget)),
+            positions::CenterBoxPosition::End => self.set_end_widget(Some(widget)),
+        }
+        widget.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+        _other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_move_after(&self, _widget: &Self::ReturnedWidget, _other: &Self::ReturnedWidget) {}
+
+    fn factory_move_start(&self, _widget: &Self::ReturnedWidget) {}
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+
+    fn factory_update_position(&self, widget: &Self::ReturnedWidget, position: &Self::Position) {
+        // Re-adding the widget would unrealize it, so only move it to a different slot.
+        if center_box_slot(self, widget) != Some(*position) {
+            self.factory_remove(widget);
+            self.factory_append(widget, position);
+        }
+    }
+}
+
+/// The elements are added as overlays, the main child can be set independently.
+///
+/// Overlays are drawn in the order of the elements, so later elements are drawn on top.
+impl FactoryView for gtk::Overlay {
+    type Children = gtk::Widget;
+    type ReturnedWidget = gtk::Widget;
+    type Position = ();
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        self.remove_overlay(widget);
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        _position: &(),
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        self.add_overlay(widget);
+        widget.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        _position: &(),
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        self.add_overlay(widget);
+        self.factory_move_start(widget);
+        widget.clone()
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        _position: &(),
+        other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        self.add_overlay(widget);
+        self.factory_move_after(widget, other);
+        widget.clone()
+    }
+
+    fn factory_move_after(&self, widget: &Self::ReturnedWidget, other: &Self::ReturnedWidget) {
+        widget.insert_after(self, Some(other));
+    }
+
+    fn factory_move_start(&self, widget: &Self::ReturnedWidget) {
+        // The main child is always drawn below the overlays.
+        widget.insert_after(self, self.child().as_ref());
+    }
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+}
+
+/// Returns the group of a widget packed into a [`gtk::HeaderBar`] or [`gtk::ActionBar`].
+///
+/// Both pack their widgets into a start and an end box, which are
+/// the start and end widgets of an internal [`gtk::CenterBox`].
+fn pack_group(widget: &gtk::Widget) -> Option<positions::PackPosition> {
+    let group = widget.parent()?;
+    let center_box = group.parent()?.downcast::<gtk::CenterBox>().ok()?;
+    if center_box.start_widget().as_ref() == Some(&group) {
+        Some(positions::PackPosition::Start)
+    } else if center_box.end_widget().as_ref() == Some(&group) {
+        Some(positions::PackPosition::End)
+    } else {
+        None
+    }
+}
+
+/// **The elements are append-only.**
+///
+/// [`gtk::HeaderBar`] only allows packing widgets at the start or the end,
+/// so each element is packed after the elements already in its [`PackPosition`] group.
+/// Prepending and inserting elements packs them like appending, moving elements has no effect
+/// and [`FactoryView::factory_update_position`] packs the element again after the elements
+/// of its new group if the group changed. Only use this view for factories that never reorder their elements.
+///
+/// [`PackPosition`]: positions::PackPosition
+impl FactoryView for gtk::HeaderBar {
+    type Children = gtk::Widget;
+    type ReturnedWidget = gtk::Widget;
+    type Position = positions::PackPosition;
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        self.remove(widget);
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        match position {
+            positions::PackPosition::Start => self.pack_start(widget),
+            positions::PackPosition::End => self.pack_end(widget),
+        }
+        widget.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+        _other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_move_after(&self, _widget: &Self::ReturnedWidget, _other: &Self::ReturnedWidget) {}
+
+    fn factory_move_start(&self, _widget: &Self::ReturnedWidget) {}
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+
+    fn factory_update_position(&self, widget: &Self::ReturnedWidget, position: &Self::Position) {
+        // Packing the widget again would unrealize it, so only move it to a different group.
+        if pack_group(widget) != Some(*position) {
+            self.factory_remove(widget);
+            self.factory_append(widget, position);
+        }
+    }
+}
+
+/// **The elements are append-only.**
+///
+/// [`gtk::ActionBar`] only allows packing widgets at the start or the end,
+/// so each element is packed after the elements already in its [`PackPosition`] group.
+/// Prepending and inserting elements packs them like appending, moving elements has no effect
+/// and [`FactoryView::factory_update_position`] packs the element again after the elements
+/// of its new group if the group changed. Only use this view for factories that never reorder their elements.
+///
+/// [`PackPosition`]: positions::PackPosition
+impl FactoryView for gtk::ActionBar {
+    type Children = gtk::Widget;
+    type ReturnedWidget = gtk::Widget;
+    type Position = positions::PackPosition;
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        self.remove(widget);
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        match position {
+            positions::PackPosition::Start => self.pack_start(widget),
+            positions::PackPosition::End => self.pack_end(widget),
+        }
+        widget.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+        _other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_move_after(&self, _widget: &Self::ReturnedWidget, _other: &Self::ReturnedWidget) {}
+
+    fn factory_move_start(&self, _widget: &Self::ReturnedWidget) {}
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+
+    fn factory_update_position(&self, widget: &Self::ReturnedWidget, position: &Self::Position) {
+        // Packing the widget again would unrealize it, so only move it to a different group.
+        if pack_group(widget) != Some(*position) {
+            self.factory_remove(widget);
+            self.factory_append(widget, position);
+        }
+    }
+}
+
 // impl FactoryView<gtk::TreeViewColumn> for gtk::TreeView {
 //     type Position = ();
 //     type Root = gtk::TreeViewColumn;
@@ -1,7 +1,7 @@
 #![allow(deprecated)]
 use adw::prelude::*;
 
-use crate::factory::FactoryView;
+use crate::factory::{FactoryView, positions};
 
 impl FactoryView for adw::TabView {
     type Children = gtk::Widget;
@@ -300,6 +300,412 @@ impl FactoryView for adw::Leaflet {
     }
 }
 
+/// Pages can't be reordered, so new elements are always added at the end.
+impl FactoryView for adw::ViewStack {
+    type Children = gtk::Widget;
+    type ReturnedWidget = adw::ViewStackPage;
+    type Position = positions::ViewStackPosition;
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        self.remove(&widget.child());
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        let page = self.add(widget.as_ref());
+        self.factory_update_position(&page, position);
+        page
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+        _other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_move_after(&self, _widget: &Self::ReturnedWidget, _other: &Self::ReturnedWidget) {}
+
+    fn factory_move_start(&self, _widget: &Self::ReturnedWidget) {}
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.child()
+    }
+
+    fn factory_update_position(&self, widget: &Self::ReturnedWidget, position: &Self::Position) {
+        widget.set_name(position.name.as_deref());
+        widget.set_title(position.title.as_deref());
+        widget.set_icon_name(position.icon_name.as_deref());
+    }
+}
+
+/// Returns the slot of the [`adw::Flap`] that displays `widget`.
+fn flap_position(flap: &adw::Flap, widget: &gtk::Widget) -> Option<positions::SplitPosition> {
+    if flap.flap().as_ref() == Some(widget) {
+        Some(positions::SplitPosition::Sidebar)
+    } else if flap.content().as_ref() == Some(widget) {
+        Some(positions::SplitPosition::Content)
+    } else {
+        None
+    }
+}
+
+impl FactoryView for adw::Flap {
+    type Children = gtk::Widget;
+    type ReturnedWidget = gtk::Widget;
+    type Position = positions::SplitPosition;
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        match flap_position(self, widget) {
+            Some(positions::SplitPosition::Sidebar) => self.set_flap(None::<&gtk::Widget>),
+            Some(positions::SplitPosition::Content) => self.set_content(None::<&gtk::Widget>),
+            None => (),
+        }
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        match position {
+            positions::SplitPosition::Sidebar => self.set_flap(Some(widget)),
+            positions::SplitPosition::Content => self.set_content(Some(widget)),
+        }
+        widget.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+        _other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_move_after(&self, _widget: &Self::ReturnedWidget, _other: &Self::ReturnedWidget) {}
+
+    fn factory_move_start(&self, _widget: &Self::ReturnedWidget) {}
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+
+    fn factory_update_position(&self, widget: &Self::ReturnedWidget, position: &Self::Position) {
+        // Re-adding the widget would unrealize it, so only move it to a different slot.
+        if flap_position(self, widget) != Some(*position) {
+            self.factory_remove(widget);
+            self.factory_append(widget, position);
+        }
+    }
+}
+
+#[cfg(all(feature = "libadwaita", feature = "gnome_45"))]
+fn navigation_stack(view: &adw::NavigationView) -> Vec<adw::NavigationPage> {
+    view.navigation_stack()
+        .iter::<adw::NavigationPage>()
+        .filter_map(Result::ok)
+        .collect()
+}
+
+/// Replaces the navigation stack without animation.
+#[cfg(all(feature = "libadwaita", feature = "gnome_45"))]
+fn edit_navigation_stack(
+    view: &adw::NavigationView,
+    f: impl FnOnce(&mut Vec<adw::NavigationPage>),
+) {
+    let mut pages = navigation_stack(view);
+    f(&mut pages);
+    view.replace(&pages);
+}
+
+/// The elements form the navigation stack, so the last element is visible.
+///
+/// Appending and removing the last element are animated like pushing and popping pages.
+///
+/// **Pages popped by the user aren't removed from the factory.**
+/// The back button, shortcuts and gestures of [`adw::NavigationView`] pop pages
+/// without notifying the factory, so their elements stay in the collection until they are removed.
+/// Either disable [`can-pop`] on the pages and navigate back by removing elements,
+/// or connect to [`popped`] and remove the element of the popped page.
+/// Removing or moving an element whose page was already popped leaves the navigation stack unchanged.
+///
+/// [`can-pop`]: adw::NavigationPage::set_can_pop
+/// [`popped`]: adw::NavigationView::connect_popped
+#[cfg(all(feature = "libadwaita", feature = "gnome_45"))]
+impl FactoryView for adw::NavigationView {
+    type Children = adw::NavigationPage;
+    type ReturnedWidget = adw::NavigationPage;
+    type Position = ();
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        let pages = navigation_stack(self);
+        if !pages.contains(widget) {
+            // The page was already popped by the user.
+            return;
+        }
+
+        if pages.len() > 1 && pages.last() == Some(widget) {
+            self.pop();
+        } else {
+            edit_navigation_stack(self, |pages| pages.retain(|page| page != widget));
+        }
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        _position: &(),
+    ) -> Self::ReturnedWidget {
+        let page = widget.as_ref();
+        self.push(page);
+        page.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        _position: &(),
+    ) -> Self::ReturnedWidget {
+        let page = widget.as_ref();
+        edit_navigation_stack(self, |pages| pages.insert(0, page.clone()));
+        page.clone()
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &(),
+        other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        let page = widget.as_ref();
+        if navigation_stack(self).last() == Some(other) {
+            return self.factory_append(page, position);
+        }
+
+        edit_navigation_stack(self, |pages| {
+            let index = pages.iter().position(|page| page == other);
+            let index = index.map_or(pages.len(), |index| index + 1);
+            pages.insert(index, page.clone());
+        });
+        page.clone()
+    }
+
+    fn factory_move_after(&self, widget: &Self::ReturnedWidget, other: &Self::ReturnedWidget) {
+        if !navigation_stack(self).contains(widget) {
+            return;
+        }
+
+        edit_navigation_stack(self, |pages| {
+            pages.retain(|page| page != widget);
+            let index = pages.iter().position(|page| page == other);
+            let index = index.map_or(pages.len(), |index| index + 1);
+            pages.insert(index, widget.clone());
+        });
+    }
+
+    fn factory_move_start(&self, widget: &Self::ReturnedWidget) {
+        if !navigation_stack(self).contains(widget) {
+            return;
+        }
+
+        edit_navigation_stack(self, |pages| {
+            pages.retain(|page| page != widget);
+            pages.insert(0, widget.clone());
+        });
+    }
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+}
+
+/// Returns the slot of the [`adw::OverlaySplitView`] that displays `widget`.
+#[cfg(all(feature = "libadwaita", feature = "gnome_45"))]
+fn overlay_split_view_position(
+    view: &adw::OverlaySplitView,
+    widget: &gtk::Widget,
+) -> Option<positions::SplitPosition> {
+    if view.sidebar().as_ref() == Some(widget) {
+        Some(positions::SplitPosition::Sidebar)
+    } else if view.content().as_ref() == Some(widget) {
+        Some(positions::SplitPosition::Content)
+    } else {
+        None
+    }
+}
+
+#[cfg(all(feature = "libadwaita", feature = "gnome_45"))]
+impl FactoryView for adw::OverlaySplitView {
+    type Children = gtk::Widget;
+    type ReturnedWidget = gtk::Widget;
+    type Position = positions::SplitPosition;
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        match overlay_split_view_position(self, widget) {
+            Some(positions::SplitPosition::Sidebar) => self.set_sidebar(None::<&gtk::Widget>),
+            Some(positions::SplitPosition::Content) => self.set_content(None::<&gtk::Widget>),
+            None => (),
+        }
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        match position {
+            positions::SplitPosition::Sidebar => self.set_sidebar(Some(widget)),
+            positions::SplitPosition::Content => self.set_content(Some(widget)),
+        }
+        widget.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+        _other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_move_after(&self, _widget: &Self::ReturnedWidget, _other: &Self::ReturnedWidget) {}
+
+    fn factory_move_start(&self, _widget: &Self::ReturnedWidget) {}
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+
+    fn factory_update_position(&self, widget: &Self::ReturnedWidget, position: &Self::Position) {
+        // Re-adding the widget would unrealize it, so only move it to a different slot.
+        if overlay_split_view_position(self, widget) != Some(*position) {
+            self.factory_remove(widget);
+            self.factory_append(widget, position);
+        }
+    }
+}
+
+/// Returns the bar of the [`adw::ToolbarView`] that contains `widget`.
+///
+/// The top and bottom bars are the children of the view
+/// with the `top-bar` and `bottom-bar` style classes.
+#[cfg(all(feature = "libadwaita", feature = "gnome_45"))]
+fn toolbar_position(
+    view: &adw::ToolbarView,
+    widget: &gtk::Widget,
+) -> Option<positions::ToolbarPosition> {
+    let mut bar = widget.parent()?;
+    while bar.parent().as_ref() != Some(view.upcast_ref()) {
+        bar = bar.parent()?;
+    }
+
+    if bar.has_css_class("top-bar") {
+        Some(positions::ToolbarPosition::Top)
+    } else if bar.has_css_class("bottom-bar") {
+        Some(positions::ToolbarPosition::Bottom)
+    } else {
+        None
+    }
+}
+
+/// **The elements are append-only.**
+///
+/// [`adw::ToolbarView`] only appends top and bottom bars, so prepending and inserting
+/// elements adds them like appending, moving elements has no effect
+/// and [`FactoryView::factory_update_position`] adds the element again after the bars
+/// of its new position if the position changed. Only use this view for factories that
+/// never reorder their elements.
+#[cfg(all(feature = "libadwaita", feature = "gnome_45"))]
+impl FactoryView for adw::ToolbarView {
+    type Children = gtk::Widget;
+    type ReturnedWidget = gtk::Widget;
+    type Position = positions::ToolbarPosition;
+
+    fn factory_remove(&self, widget: &Self::ReturnedWidget) {
+        self.remove(widget);
+    }
+
+    fn factory_append(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        let widget = widget.as_ref();
+        match position {
+            positions::ToolbarPosition::Top => self.add_top_bar(widget),
+            positions::ToolbarPosition::Bottom => self.add_bottom_bar(widget),
+        }
+        widget.clone()
+    }
+
+    fn factory_prepend(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_insert_after(
+        &self,
+        widget: impl AsRef<Self::Children>,
+        position: &Self::Position,
+        _other: &Self::ReturnedWidget,
+    ) -> Self::ReturnedWidget {
+        self.factory_append(widget, position)
+    }
+
+    fn factory_move_after(&self, _widget: &Self::ReturnedWidget, _other: &Self::ReturnedWidget) {}
+
+    fn factory_move_start(&self, _widget: &Self::ReturnedWidget) {}
+
+    fn returned_widget_to_child(returned_widget: &Self::ReturnedWidget) -> Self::Children {
+        returned_widget.clone()
+    }
+
+    fn factory_update_position(&self, widget: &Self::ReturnedWidget, position: &Self::Position) {
+        // Re-adding the widget would unrealize it, so only move it to a different slot.
+        if toolbar_position(self, widget) != Some(*position) {
+            self.factory_remove(widget);
+            self.factory_append(widget, position);
+        }
+    }
+}
+
 #[cfg(all(feature = "libadwaita", feature = "gnome_48"))]
 impl FactoryView for adw::WrapBox {
     type Children = gtk::Widget;
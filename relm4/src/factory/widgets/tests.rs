@@ -1,8 +1,11 @@
 use crate::{
     RelmIterChildrenExt, WidgetRef,
-    factory::{FactoryView, positions::GridPosition},
+    factory::{
+        FactoryView, SelectableFactoryView,
+        positions::{CenterBoxPosition, GridPosition, NotebookPosition, PackPosition},
+    },
 };
-use gtk::prelude::{FlowBoxChildExt, ListBoxRowExt};
+use gtk::prelude::{Cast, FlowBoxChildExt, IsA, ListBoxRowExt, OrientableExt, WidgetExt};
 
 /// Assert the exact ordering of widget children. The container must implement `RelmIterChildrenExt`.
 macro_rules! assert_children {
@@ -247,6 +250,442 @@ fn flow_box_factory_view() {
     assert_children!(flow_box: None);
 }
 
+/// Returns the children of a widget that doesn't implement `RelmIterChildrenExt`.
+fn widget_children(widget: &impl IsA<gtk::Widget>) -> Vec<gtk::Widget> {
+    let mut children = Vec::new();
+    let mut child = widget.first_child();
+    while let Some(current) = child {
+        child = current.next_sibling();
+        children.push(current);
+    }
+    children
+}
+
+fn notebook_pages(notebook: &gtk::Notebook) -> Vec<gtk::Widget> {
+    (0..notebook.n_pages())
+        .filter_map(|page_num| notebook.nth_page(Some(page_num)))
+        .collect()
+}
+
+#[gtk::test]
+fn notebook_factory_view() {
+    let notebook = gtk::Notebook::default();
+
+    let widget1 = gtk::Label::default();
+    let widget2 = gtk::Switch::default();
+    let widget3 = gtk::Entry::default();
+
+    let position = |label: &str| NotebookPosition {
+        label: Some(label.to_owned()),
+    };
+
+    let page2 = notebook.factory_append(&widget2, &position("Page 2"));
+    let page3 = notebook.factory_insert_after(&widget3, &position("Page 3"), &page2);
+    let page1 = notebook.factory_prepend(&widget1, &NotebookPosition::default());
+
+    assert_eq!(gtk::Notebook::returned_widget_to_child(&page1), widget1);
+    assert_eq!(gtk::Notebook::returned_widget_to_child(&page2), widget2);
+    assert_eq!(gtk::Notebook::returned_widget_to_child(&page3), widget3);
+
+    assert_eq!(
+        notebook_pages(&notebook),
+        [page1.clone(), page2.clone(), page3.clone()]
+    );
+    assert_eq!(notebook.tab_label_text(&page2).as_deref(), Some("Page 2"));
+    assert_eq!(notebook.tab_label_text(&page3).as_deref(), Some("Page 3"));
+
+    notebook.factory_update_position(&page2, &position("Second page"));
+    assert_eq!(
+        notebook.tab_label_text(&page2).as_deref(),
+        Some("Second page")
+    );
+
+    // Removing the label restores the default label of GTK.
+    notebook.factory_update_position(&page3, &NotebookPosition::default());
+    assert_ne!(notebook.tab_label_text(&page3).as_deref(), Some("Page 3"));
+
+    notebook.factory_move_after(&page3, &page1);
+    assert_eq!(
+        notebook_pages(&notebook),
+        [page1.clone(), page3.clone(), page2.clone()]
+    );
+
+    notebook.factory_move_after(&page1, &page2);
+    assert_eq!(
+        notebook_pages(&notebook),
+        [page3.clone(), page2.clone(), page1.clone()]
+    );
+
+    notebook.factory_move_start(&page2);
+    assert_eq!(
+        notebook_pages(&notebook),
+        [page2.clone(), page3.clone(), page1.clone()]
+    );
+
+    notebook.factory_move_start(&page1);
+    assert_eq!(
+        notebook_pages(&notebook),
+        [page1.clone(), page2.clone(), page3.clone()]
+    );
+
+    notebook.factory_remove(&page3);
+    assert_eq!(notebook_pages(&notebook), [page1.clone(), page2.clone()]);
+
+    notebook.factory_remove(&page2);
+    assert_eq!(notebook_pages(&notebook), std::slice::from_ref(&page1));
+
+    notebook.factory_remove(&page1);
+    assert!(notebook_pages(&notebook).is_empty());
+}
+
+/// Returns the elements of a [`gtk::Paned`] and checks that
+/// the nested [`gtk::Paned`]s share the behavior of the root.
+fn paned_children(paned: &gtk::Paned) -> Vec<gtk::Widget> {
+    let mut children = Vec::new();
+    let mut current = paned.clone();
+    loop {
+        children.extend(current.start_child());
+        match current.end_child().map(|end| end.downcast::<gtk::Paned>()) {
+            Some(Ok(nested)) => {
+                assert_eq!(nested.orientation(), paned.orientation());
+                assert_eq!(nested.is_wide_handle(), paned.is_wide_handle());
+                assert_eq!(nested.resizes_start_child(), paned.resizes_start_child());
+                assert_eq!(nested.resizes_end_child(), paned.resizes_end_child());
+                assert_eq!(nested.shrinks_start_child(), paned.shrinks_start_child());
+                assert_eq!(nested.shrinks_end_child(), paned.shrinks_end_child());
+                current = nested;
+            }
+            Some(Err(end)) => {
+                children.push(end);
+                break;
+            }
+            None => break,
+        }
+    }
+    children
+}
+
+#[gtk::test]
+fn paned_factory_view() {
+    let paned = gtk::Paned::new(gtk::Orientation::Vertical);
+    paned.set_wide_handle(true);
+    paned.set_resize_start_child(false);
+    paned.set_shrink_end_child(false);
+
+    let widget1 = gtk::Label::default();
+    let widget2 = gtk::Switch::default();
+    let widget3 = gtk::Entry::default();
+    let widget4 = gtk::Button::default();
+    let widget5 = gtk::Label::default();
+
+    let child2 = paned.factory_append(&widget2, &());
+    assert_eq!(paned.start_child().as_ref(), Some(&child2));
+    assert_eq!(paned.end_child(), None);
+
+    let child3 = paned.factory_insert_after(&widget3, &(), &child2);
+    assert_eq!(paned.start_child().as_ref(), Some(&child2));
+    assert_eq!(paned.end_child().as_ref(), Some(&child3));
+
+    let child1 = paned.factory_prepend(&widget1, &());
+    assert_eq!(paned.start_child().as_ref(), Some(&child1));
+
+    let nested: gtk::Paned = paned.end_child().unwrap().downcast().unwrap();
+    assert_eq!(nested.start_child().as_ref(), Some(&child2));
+    assert_eq!(nested.end_child().as_ref(), Some(&child3));
+
+    // Inserting in the middle splices a new paned after the previous element.
+    let child4 = paned.factory_insert_after(&widget4, &(), &child2);
+    assert_eq!(
+        paned_children(&paned),
+        [&child1, &child2, &child4, &child3].map(Clone::clone)
+    );
+    assert_eq!(paned.end_child().as_ref(), Some(nested.upcast_ref()));
+    assert_eq!(nested.start_child().as_ref(), Some(&child2));
+
+    let child5 = paned.factory_append(&widget5, &());
+    assert_eq!(
+        paned_children(&paned),
+        [&child1, &child2, &child4, &child3, &child5].map(Clone::clone)
+    );
+
+    paned.factory_move_after(&child1, &child4);
+    assert_eq!(
+        paned_children(&paned),
+        [&child2, &child4, &child1, &child3, &child5].map(Clone::clone)
+    );
+
+    paned.factory_move_start(&child5);
+    assert_eq!(
+        paned_children(&paned),
+        [&child5, &child2, &child4, &child1, &child3].map(Clone::clone)
+    );
+
+    paned.factory_move_after(&child2, &child3);
+    assert_eq!(
+        paned_children(&paned),
+        [&child5, &child4, &child1, &child3, &child2].map(Clone::clone)
+    );
+
+    paned.factory_remove(&child1);
+    assert_eq!(
+        paned_children(&paned),
+        [&child5, &child4, &child3, &child2].map(Clone::clone)
+    );
+
+    paned.factory_remove(&child2);
+    assert_eq!(
+        paned_children(&paned),
+        [&child5, &child4, &child3].map(Clone::clone)
+    );
+
+    paned.factory_remove(&child5);
+    assert_eq!(paned.start_child().as_ref(), Some(&child4));
+    assert_eq!(paned.end_child().as_ref(), Some(&child3));
+    assert_eq!(nested.parent(), None);
+
+    paned.factory_remove(&child4);
+    assert_eq!(paned.start_child().as_ref(), Some(&child3));
+    assert_eq!(paned.end_child(), None);
+
+    paned.factory_remove(&child3);
+    assert_eq!(paned.start_child(), None);
+    assert_eq!(paned.end_child(), None);
+}
+
+#[gtk::test]
+fn center_box_factory_view() {
+    let center_box = gtk::CenterBox::default();
+
+    let widget1 = gtk::Label::default();
+    let widget2 = gtk::Switch::default();
+    let widget3 = gtk::Entry::default();
+
+    let child1 = center_box.factory_append(&widget1, &CenterBoxPosition::Start);
+    let child2 = center_box.factory_prepend(&widget2, &CenterBoxPosition::Center);
+    let child3 = center_box.factory_insert_after(&widget3, &CenterBoxPosition::End, &child1);
+
+    assert_eq!(center_box.start_widget().as_ref(), Some(&child1));
+    assert_eq!(center_box.center_widget().as_ref(), Some(&child2));
+    assert_eq!(center_box.end_widget().as_ref(), Some(&child3));
+
+    // Updating to the same slot keeps the widget in place.
+    center_box.factory_update_position(&child2, &CenterBoxPosition::Center);
+    assert_eq!(center_box.center_widget().as_ref(), Some(&child2));
+
+    center_box.factory_remove(&child1);
+    center_box.factory_update_position(&child3, &CenterBoxPosition::Start);
+    assert_eq!(center_box.start_widget().as_ref(), Some(&child3));
+    assert_eq!(center_box.center_widget().as_ref(), Some(&child2));
+    assert_eq!(center_box.end_widget(), None);
+
+    center_box.factory_remove(&child2);
+    center_box.factory_remove(&child3);
+    assert_eq!(center_box.start_widget(), None);
+    assert_eq!(center_box.center_widget(), None);
+}
+
+#[gtk::test]
+fn overlay_factory_view() {
+    let overlay = gtk::Overlay::default();
+    let main_child = gtk::Label::default();
+    overlay.set_child(Some(&main_child));
+
+    let widget1 = gtk::Label::default();
+    let widget2 = gtk::Switch::default();
+    let widget3 = gtk::Entry::default();
+
+    let child2 = overlay.factory_append(&widget2, &());
+    let child3 = overlay.factory_insert_after(&widget3, &(), &child2);
+    let child1 = overlay.factory_prepend(&widget1, &());
+
+    let main_child = main_child.upcast::<gtk::Widget>();
+    assert_eq!(
+        widget_children(&overlay),
+        [
+            main_child.clone(),
+            child1.clone(),
+            child2.clone(),
+            child3.clone()
+        ]
+    );
+
+    overlay.factory_move_after(&child1, &child3);
+    assert_eq!(
+        widget_children(&overlay),
+        [
+            main_child.clone(),
+            child2.clone(),
+            child3.clone(),
+            child1.clone()
+        ]
+    );
+
+    overlay.factory_move_start(&child1);
+    assert_eq!(
+        widget_children(&overlay),
+        [
+            main_child.clone(),
+            child1.clone(),
+            child2.clone(),
+            child3.clone()
+        ]
+    );
+
+    overlay.factory_remove(&child2);
+    overlay.factory_remove(&child1);
+    overlay.factory_remove(&child3);
+    assert_eq!(widget_children(&overlay), [main_child]);
+}
+
+/// Checks that the elements of a view that packs them into
+/// [`PackPosition`] groups are only ever appended to their group.
+fn pack_factory_view<V>(view: &V)
+where
+    V: FactoryView<Children = gtk::Widget, ReturnedWidget = gtk::Widget, Position = PackPosition>
+        + IsA<gtk::Widget>,
+{
+    let widget1 = gtk::Label::default();
+    let widget2 = gtk::Switch::default();
+    let widget3 = gtk::Entry::default();
+    let widget4 = gtk::Button::default();
+    let widget5 = gtk::Label::default();
+
+    let child1 = view.factory_append(&widget1, &PackPosition::Start);
+    let child2 = view.factory_append(&widget2, &PackPosition::End);
+    assert!(child1.is_ancestor(view));
+    assert!(child2.is_ancestor(view));
+
+    // Prepending and inserting packs the elements after the others of their group.
+    let child3 = view.factory_prepend(&widget3, &PackPosition::Start);
+    let child4 = view.factory_insert_after(&widget4, &PackPosition::Start, &child1);
+    assert_eq!(child1.next_sibling().as_ref(), Some(&child3));
+    assert_eq!(child3.next_sibling().as_ref(), Some(&child4));
+
+    // Moving elements has no effect.
+    view.factory_move_start(&child4);
+    view.factory_move_after(&child1, &child4);
+    assert_eq!(child1.next_sibling().as_ref(), Some(&child3));
+    assert_eq!(child3.next_sibling().as_ref(), Some(&child4));
+
+    // Updating the position packs the element after the others of its new group.
+    view.factory_update_position(&child1, &PackPosition::End);
+    assert!(child1.is_ancestor(view));
+    assert_eq!(child1.parent(), child2.parent());
+    assert_ne!(child1.parent(), child3.parent());
+
+    view.factory_update_position(&child1, &PackPosition::Start);
+    assert_eq!(child4.next_sibling().as_ref(), Some(&child1));
+
+    let child5 = view.factory_append(&widget5, &PackPosition::Start);
+    assert_eq!(child1.next_sibling().as_ref(), Some(&child5));
+
+    // Updating to the same group keeps the element in place.
+    view.factory_update_position(&child3, &PackPosition::Start);
+    assert_eq!(child3.next_sibling().as_ref(), Some(&child4));
+
+    for child in [&child1, &child2, &child3, &child4, &child5] {
+        view.factory_remove(child);
+        assert!(!child.is_ancestor(view));
+    }
+}
+
+#[gtk::test]
+fn header_bar_factory_view() {
+    pack_factory_view(&gtk::HeaderBar::default());
+}
+
+#[gtk::test]
+fn action_bar_factory_view() {
+    pack_factory_view(&gtk::ActionBar::default());
+}
+
+/// Returns the selection state of `widgets` and the number of selected widgets.
+fn selection<V: SelectableFactoryView>(
+    view: &V,
+    widgets: &[&V::ReturnedWidget],
+) -> (Vec<bool>, usize) {
+    let selected = widgets
+        .iter()
+        .map(|widget| view.factory_is_selected(widget))
+        .collect();
+    (selected, view.factory_selected().len())
+}
+
+#[gtk::test]
+fn list_box_selection() {
+    let list_box = gtk::ListBox::default();
+    list_box.factory_set_selection_mode(gtk::SelectionMode::Multiple);
+
+    let row1 = list_box.factory_append(gtk::Label::default(), &());
+    let row2 = list_box.factory_append(gtk::Label::default(), &());
+    let row3 = list_box.factory_append(gtk::Label::default(), &());
+    let rows = [&row1, &row2, &row3];
+    assert_eq!(selection(&list_box, &rows), (vec![false, false, false], 0));
+
+    list_box.factory_select(&row3);
+    list_box.factory_select(&row1);
+    assert_eq!(selection(&list_box, &rows), (vec![true, false, true], 2));
+
+    list_box.factory_unselect(&row1);
+    assert_eq!(selection(&list_box, &rows), (vec![false, false, true], 1));
+    assert_eq!(list_box.factory_selected(), std::slice::from_ref(&row3));
+
+    list_box.factory_select_all();
+    assert_eq!(selection(&list_box, &rows), (vec![true, true, true], 3));
+
+    list_box.factory_remove(&row2);
+    assert_eq!(list_box.factory_selected().len(), 2);
+
+    list_box.factory_unselect_all();
+    assert_eq!(selection(&list_box, &rows), (vec![false, false, false], 0));
+
+    list_box.factory_set_selection_mode(gtk::SelectionMode::Single);
+    list_box.factory_select(&row1);
+    list_box.factory_select(&row3);
+    assert_eq!(selection(&list_box, &rows), (vec![false, false, true], 1));
+}
+
+#[gtk::test]
+fn flow_box_selection() {
+    let flow_box = gtk::FlowBox::default();
+    flow_box.factory_set_selection_mode(gtk::SelectionMode::Multiple);
+
+    let child1 = flow_box.factory_append(gtk::Label::default(), &());
+    let child2 = flow_box.factory_append(gtk::Label::default(), &());
+    let child3 = flow_box.factory_append(gtk::Label::default(), &());
+    let children = [&child1, &child2, &child3];
+    assert_eq!(
+        selection(&flow_box, &children),
+        (vec![false, false, false], 0)
+    );
+
+    flow_box.factory_select(&child3);
+    flow_box.factory_select(&child1);
+    assert_eq!(
+        selection(&flow_box, &children),
+        (vec![true, false, true], 2)
+    );
+
+    flow_box.factory_unselect(&child1);
+    assert_eq!(
+        selection(&flow_box, &children),
+        (vec![false, false, true], 1)
+    );
+    assert_eq!(flow_box.factory_selected(), std::slice::from_ref(&child3));
+
+    flow_box.factory_select_all();
+    assert_eq!(selection(&flow_box, &children), (vec![true, true, true], 3));
+
+    flow_box.factory_remove(&child2);
+    assert_eq!(flow_box.factory_selected().len(), 2);
+
+    flow_box.factory_unselect_all();
+    assert_eq!(
+        selection(&flow_box, &children),
+        (vec![false, false, false], 0)
+    );
+}
+
 #[gtk::test]
 #[cfg(feature = "libadwaita")]
 fn tab_view_factory_view() {
@@ -280,6 +719,218 @@ fn tab_view_factory_view() {
     assert_children!(tab_view: widget2, widget3, widget1);
 }
 
+#[gtk::test]
+#[cfg(feature = "libadwaita")]
+fn view_stack_factory_view() {
+    use crate::factory::positions::ViewStackPosition;
+
+    let view_stack = adw::ViewStack::default();
+
+    let widget1 = gtk::Label::default();
+    let widget2 = gtk::Switch::default();
+
+    let position = ViewStackPosition {
+        name: Some("first".to_owned()),
+        title: Some("First".to_owned()),
+        icon_name: Some("go-home-symbolic".to_owned()),
+    };
+    let page1 = view_stack.factory_append(&widget1, &position);
+    let page2 = view_stack.factory_prepend(&widget2, &ViewStackPosition::default());
+
+    assert_eq!(adw::ViewStack::returned_widget_to_child(&page1), widget1);
+    assert_eq!(adw::ViewStack::returned_widget_to_child(&page2), widget2);
+
+    assert_eq!(page1.name().as_deref(), Some("first"));
+    assert_eq!(page1.title().as_deref(), Some("First"));
+    assert_eq!(page1.icon_name().as_deref(), Some("go-home-symbolic"));
+    assert_eq!(
+        view_stack.child_by_name("first").as_ref(),
+        Some(widget1.as_ref())
+    );
+
+    view_stack.factory_update_position(
+        &page2,
+        &ViewStackPosition {
+            title: Some("Second".to_owned()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(page2.title().as_deref(), Some("Second"));
+
+    view_stack.factory_remove(&page1);
+    assert_eq!(view_stack.child_by_name("first"), None);
+
+    view_stack.factory_remove(&page2);
+    assert_eq!(view_stack.visible_child(), None);
+}
+
+#[gtk::test]
+#[cfg(feature = "libadwaita")]
+#[allow(deprecated)]
+fn flap_factory_view() {
+    use crate::factory::positions::SplitPosition;
+
+    let flap = adw::Flap::default();
+
+    let widget1 = gtk::Label::default();
+    let widget2 = gtk::Switch::default();
+
+    let child1 = flap.factory_append(&widget1, &SplitPosition::Sidebar);
+    let child2 = flap.factory_prepend(&widget2, &SplitPosition::Content);
+    assert_eq!(flap.flap().as_ref(), Some(&child1));
+    assert_eq!(flap.content().as_ref(), Some(&child2));
+
+    flap.factory_remove(&child1);
+    flap.factory_update_position(&child2, &SplitPosition::Sidebar);
+    assert_eq!(flap.flap().as_ref(), Some(&child2));
+    assert_eq!(flap.content(), None);
+
+    flap.factory_remove(&child2);
+    assert_eq!(flap.flap(), None);
+}
+
+#[gtk::test]
+#[cfg(feature = "libadwaita")]
+#[cfg(feature = "gnome_45")]
+fn navigation_view_factory_view() {
+    use gtk::gio::prelude::ListModelExtManual;
+
+    let navigation_view = adw::NavigationView::default();
+    let navigation_stack = || -> Vec<adw::NavigationPage> {
+        navigation_view
+            .navigation_stack()
+            .iter()
+            .map(Result::unwrap)
+            .collect()
+    };
+
+    let page1 = adw::NavigationPage::new(&gtk::Label::default(), "Page 1");
+    let page2 = adw::NavigationPage::new(&gtk::Switch::default(), "Page 2");
+    let page3 = adw::NavigationPage::new(&gtk::Entry::default(), "Page 3");
+
+    let returned2 = navigation_view.factory_append(&page2, &());
+    let returned3 = navigation_view.factory_insert_after(&page3, &(), &returned2);
+    let returned1 = navigation_view.factory_prepend(&page1, &());
+
+    assert_eq!(
+        adw::NavigationView::returned_widget_to_child(&returned1),
+        page1
+    );
+    assert_eq!(
+        navigation_stack(),
+        [page1.clone(), page2.clone(), page3.clone()]
+    );
+    assert_eq!(navigation_view.visible_page().as_ref(), Some(&page3));
+
+    navigation_view.factory_move_after(&page1, &page3);
+    assert_eq!(
+        navigation_stack(),
+        [page2.clone(), page3.clone(), page1.clone()]
+    );
+
+    navigation_view.factory_move_start(&page1);
+    assert_eq!(
+        navigation_stack(),
+        [page1.clone(), page2.clone(), page3.clone()]
+    );
+
+    navigation_view.factory_remove(&page2);
+    assert_eq!(navigation_stack(), [page1.clone(), page3.clone()]);
+
+    navigation_view.factory_remove(&page3);
+    assert_eq!(navigation_stack(), [page1.clone()]);
+
+    navigation_view.factory_remove(&page1);
+    assert!(navigation_stack().is_empty());
+}
+
+#[gtk::test]
+#[cfg(feature = "libadwaita")]
+#[cfg(feature = "gnome_45")]
+fn navigation_view_popped_page() {
+    use gtk::gio::prelude::ListModelExtManual;
+
+    let navigation_view = adw::NavigationView::default();
+    let navigation_stack = || -> Vec<adw::NavigationPage> {
+        navigation_view
+            .navigation_stack()
+            .iter()
+            .map(Result::unwrap)
+            .collect()
+    };
+
+    let page1 = adw::NavigationPage::new(&gtk::Label::default(), "Page 1");
+    let page2 = adw::NavigationPage::new(&gtk::Switch::default(), "Page 2");
+    let page3 = adw::NavigationPage::new(&gtk::Entry::default(), "Page 3");
+
+    navigation_view.factory_append(&page1, &());
+    navigation_view.factory_append(&page2, &());
+    navigation_view.factory_append(&page3, &());
+
+    // Navigating back pops the page without the factory knowing about it.
+    navigation_view.pop();
+    assert_eq!(navigation_stack(), [page1.clone(), page2.clone()]);
+
+    // The popped page isn't pushed again.
+    navigation_view.factory_move_start(&page3);
+    navigation_view.factory_move_after(&page3, &page1);
+    assert_eq!(navigation_stack(), [page1.clone(), page2.clone()]);
+
+    navigation_view.factory_remove(&page3);
+    assert_eq!(navigation_stack(), [page1.clone(), page2.clone()]);
+    assert_eq!(navigation_view.visible_page().as_ref(), Some(&page2));
+}
+
+#[gtk::test]
+#[cfg(feature = "libadwaita")]
+#[cfg(feature = "gnome_45")]
+fn overlay_split_view_factory_view() {
+    use crate::factory::positions::SplitPosition;
+
+    let split_view = adw::OverlaySplitView::default();
+
+    let widget1 = gtk::Label::default();
+    let widget2 = gtk::Switch::default();
+
+    let child1 = split_view.factory_append(&widget1, &SplitPosition::Sidebar);
+    let child2 = split_view.factory_insert_after(&widget2, &SplitPosition::Content, &child1);
+    assert_eq!(split_view.sidebar().as_ref(), Some(&child1));
+    assert_eq!(split_view.content().as_ref(), Some(&child2));
+
+    split_view.factory_remove(&child2);
+    split_view.factory_update_position(&child1, &SplitPosition::Content);
+    assert_eq!(split_view.sidebar(), None);
+    assert_eq!(split_view.content().as_ref(), Some(&child1));
+
+    split_view.factory_remove(&child1);
+    assert_eq!(split_view.content(), None);
+}
+
+#[gtk::test]
+#[cfg(feature = "libadwaita")]
+#[cfg(feature = "gnome_45")]
+fn toolbar_view_factory_view() {
+    use crate::factory::positions::ToolbarPosition;
+
+    let toolbar_view = adw::ToolbarView::default();
+
+    let widget1 = adw::HeaderBar::default();
+    let widget2 = gtk::ActionBar::default();
+
+    let child1 = toolbar_view.factory_append(&widget1, &ToolbarPosition::Top);
+    let child2 = toolbar_view.factory_append(&widget2, &ToolbarPosition::Bottom);
+    assert!(child1.is_ancestor(&toolbar_view));
+    assert!(child2.is_ancestor(&toolbar_view));
+
+    toolbar_view.factory_update_position(&child1, &ToolbarPosition::Bottom);
+    assert!(child1.is_ancestor(&toolbar_view));
+
+    toolbar_view.factory_remove(&child1);
+    toolbar_view.factory_remove(&child2);
+    assert!(!child1.is_ancestor(&toolbar_view));
+    assert!(!child2.is_ancestor(&toolbar_view));
+}
+
 #[gtk::test]
 #[cfg(feature = "libadwaita")]
 #[cfg(feature = "gnome_48")]
@@ -61,6 +61,44 @@ pub trait FactoryView: IsA<gtk::Widget> {
     fn factory_update_position(&self, _widget: &Self::ReturnedWidget, _position: &Self::Position) {}
 }
 
+/// A [`FactoryView`] whose elements can be selected, like
+/// [`gtk::ListBox`] and [`gtk::FlowBox`].
+///
+/// The selection is handled by the container widget itself.
+/// With [`gtk::SelectionMode::Multiple`], clicking an element
+/// while holding Ctrl toggles its selection and holding Shift
+/// selects a range of elements.
+pub trait SelectableFactoryView: FactoryView<ReturnedWidget: IsA<gtk::glib::Object>> {
+    /// Sets how the elements can be selected.
+    fn factory_set_selection_mode(&self, mode: gtk::SelectionMode);
+
+    /// Returns the selected widgets.
+    ///
+    /// This includes selected widgets that weren't added by a factory.
+    fn factory_selected(&self) -> Vec<Self::ReturnedWidget>;
+
+    /// Returns [`true`] if an element is selected.
+    fn factory_is_selected(&self, widget: &Self::ReturnedWidget) -> bool;
+
+    /// Selects an element.
+    fn factory_select(&self, widget: &Self::ReturnedWidget);
+
+    /// Unselects an element.
+    fn factory_unselect(&self, widget: &Self::ReturnedWidget);
+
+    /// Selects all elements if the selection mode allows it.
+    fn factory_select_all(&self);
+
+    /// Unselects all elements.
+    fn factory_unselect_all(&self);
+
+    /// Calls `f` every time the selection changed.
+    fn factory_connect_selection_changed<F: Fn(&Self) + 'static>(
+        &self,
+        f: F,
+    ) -> gtk::glib::SignalHandlerId;
+}
+
 /// Returns the position of an element inside a
 /// container like [`gtk::Grid`] where the position isn't
 /// clearly defined by the index.
@@ -34,6 +34,8 @@
 mod app;
 mod channel;
 mod extensions;
+#[cfg(feature = "serde")]
+mod file_util;
 pub(crate) mod late_initialization;
 mod runtime_util;
 
@@ -41,10 +43,12 @@ pub mod abstractions;
 pub mod actions;
 pub mod binding;
 pub mod component;
+pub mod context;
 pub mod factory;
 pub mod loading_widgets;
 #[doc(hidden)]
 pub mod macro_helper;
+pub mod notifications;
 pub mod shared_state;
 pub mod typed_view;
 
@@ -67,6 +71,7 @@ use once_cell::sync::{Lazy, OnceCell};
 use runtime_util::{GuardedReceiver, RuntimeSenders, ShutdownOnDrop};
 use std::cell::Cell;
 use std::future::Future;
+use std::sync::Mutex;
 use tokio::runtime::Runtime;
 
 /// Defines how many threads that Relm4 should use for background tasks.
@@ -111,6 +116,8 @@ thread_local! {
     static MAIN_APPLICATION: Cell<Option<gtk::Application>> = Cell::default();
 }
 
+static EXIT_CODE: Mutex<Option<gtk::glib::ExitCode>> = Mutex::new(None);
+
 fn set_main_application(app: impl IsA<gtk::Application>) {
     MAIN_APPLICATION.with(move |cell| cell.set(Some(app.upcast())));
 }
@@ -148,6 +155,33 @@ pub fn main_application() -> gtk::Application {
     })
 }
 
+/// Sets the exit code that will be returned by [`RelmApp::run()`]
+/// and [`RelmApp::run_async()`] once the application is closed.
+///
+/// This is useful for applications that are used in scripts and
+/// need to indicate failure or cancellation with a non-zero exit status.
+/// Calling this function doesn't quit the application.
+/// To do that, call [`ApplicationExt::quit()`][gtk::prelude::ApplicationExt::quit]
+/// on [`main_application()`].
+///
+/// This global setter can be called from anywhere, for example from a worker
+/// or a deeply nested component. If the root component decides the exit code,
+/// prefer [`RelmApp::run_with_exit_code()`], which quits the application
+/// with the exit code produced by the output of the root component.
+///
+/// ```
+/// use relm4::gtk::glib::ExitCode;
+///
+/// relm4::set_exit_code(ExitCode::FAILURE);
+/// ```
+pub fn set_exit_code(exit_code: impl Into<gtk::glib::ExitCode>) {
+    *EXIT_CODE.lock().unwrap() = Some(exit_code.into());
+}
+
+fn take_exit_code() -> Option<gtk::glib::ExitCode> {
+    EXIT_CODE.lock().unwrap().take()
+}
+
 #[cfg(feature = "libadwaita")]
 #[cfg_attr(docsrs, doc(cfg(feature = "libadwaita")))]
 /// Returns the global [`adw::Application`] that's used internally
@@ -0,0 +1,393 @@
+//! Type safe desktop notifications.
+//!
+//! Notifications are sent through the [`main_application()`](crate::main_application)
+//! and their buttons can either activate typed actions or send messages to components.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+
+use gtk::gio;
+use gtk::prelude::{ActionMapExt, ApplicationExt, IsA, ToVariant};
+
+use crate::Sender;
+use crate::actions::{ActionName, EmptyType};
+
+thread_local! {
+    /// The generation and the actions registered for the message buttons
+    /// of sent notifications, stored by notification ID.
+    static SENT_NOTIFICATIONS: RefCell<HashMap<String, (usize, Vec<String>)>> =
+        RefCell::default();
+    static COUNTER: Cell<usize> = const { Cell::new(0) };
+}
+
+type MessageCallback = Box<dyn Fn()>;
+
+/// Returns the name of an action registered at application level.
+fn app_action_name<Name: ActionName>() -> String {
+    format!("app.{}", Name::NAME)
+}
+
+fn next_id() -> usize {
+    COUNTER.with(|counter| {
+        let id = counter.get();
+        counter.set(id + 1);
+        id
+    })
+}
+
+fn unique_action_name() -> String {
+    format!("relm4-notification-{}", next_id())
+}
+
+/// Registers the actions of the message buttons of a notification with the given ID
+/// and removes the actions of the notification it replaces.
+///
+/// Returns the generation of the notification.
+fn register_notification(id: &str, message_actions: Vec<(String, MessageCallback)>) -> usize {
+    let app = crate::main_application();
+
+    // Actions of a replaced notification can't be activated anymore.
+    remove_notification(id, None);
+
+    let generation = next_id();
+    let mut names = Vec::with_capacity(message_actions.len());
+    for (name, callback) in message_actions {
+        let action = gio::SimpleAction::new(&name, None);
+        let id = id.to_owned();
+        action.connect_activate(move |_, _| {
+            callback();
+
+            // The notification is gone after it was clicked,
+            // so the actions can be removed as well.
+            let id = id.clone();
+            crate::spawn_local(async move {
+                remove_notification(&id, Some(generation));
+            });
+        });
+        app.add_action(&action);
+        names.push(name);
+    }
+
+    SENT_NOTIFICATIONS.with(|sent| sent.borrow_mut().insert(id.to_owned(), (generation, names)));
+    generation
+}
+
+/// Removes the actions registered for the notification with the given ID.
+///
+/// If `generation` is set, nothing is removed if the notification was
+/// replaced by a newer one in the meantime.
+/// Returns [`true`] if the notification was still registered.
+fn remove_notification(id: &str, generation: Option<usize>) -> bool {
+    let names = SENT_NOTIFICATIONS.with(|sent| {
+        let mut sent = sent.borrow_mut();
+        match sent.get(id) {
+            Some((current, _)) if generation.is_none_or(|generation| generation == *current) => {
+                sent.remove(id).map(|(_, names)| names)
+            }
+            _ => None,
+        }
+    });
+
+    if let Some(names) = &names {
+        let app = crate::main_application();
+        for name in names {
+            app.remove_action(name);
+        }
+    }
+    names.is_some()
+}
+
+/// A type safe wrapper around [`gio::Notification`].
+///
+/// Buttons and the default action can either activate actions
+/// defined with [`ActionName`] or send messages to a component.
+///
+/// Actions used in notifications must be registered at application level
+/// with [`RelmActionGroup::register_for_main_application()`].
+///
+/// [`RelmActionGroup::register_for_main_application()`]: crate::actions::RelmActionGroup::register_for_main_application
+///
+/// # Example
+///
+/// ```no_run
+/// # #[derive(Debug, Clone)]
+/// # enum AppMsg { Open }
+/// # let (sender, _receiver) = relm4::channel::<AppMsg>();
+/// use relm4::notifications::{self, Notification};
+///
+/// let mut notification = Notification::new("Download finished");
+/// notification.set_body("example.txt was downloaded");
+/// notification.add_button_with_message("Open", &sender, AppMsg::Open);
+///
+/// // Store the handle as long as the notification should be shown.
+/// let handle = notification.send("download-finished");
+///
+/// // Remove the notification again.
+/// handle.withdraw();
+/// ```
+pub struct Notification {
+    notification: gio::Notification,
+    message_actions: Vec<(String, MessageCallback)>,
+}
+
+impl fmt::Debug for Notification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notification")
+            .field("notification", &self.notification)
+            .field("message_actions", &self.message_actions.len())
+            .finish()
+    }
+}
+
+impl Notification {
+    /// Create a new notification with a title.
+    #[must_use]
+    pub fn new(title: &str) -> Self {
+        Self {
+            notification: gio::Notification::new(title),
+            message_actions: Vec::new(),
+        }
+    }
+
+    /// Set the title of the notification.
+    pub fn set_title(&mut self, title: &str) {
+        self.notification.set_title(title);
+    }
+
+    /// Set the body of the notification.
+    pub fn set_body(&mut self, body: &str) {
+        self.notification.set_body(Some(body));
+    }
+
+    /// Set the icon of the notification.
+    pub fn set_icon(&mut self, icon: &impl IsA<gio::Icon>) {
+        self.notification.set_icon(icon);
+    }
+
+    /// Set the priority of the notification.
+    pub fn set_priority(&mut self, priority: gio::NotificationPriority) {
+        self.notification.set_priority(priority);
+    }
+
+    /// Add a button that activates an application action.
+    pub fn add_button<Name: ActionName>(&mut self, label: &str)
+    where
+        Name::Target: EmptyType,
+    {
+        self.notification
+            .add_button(label, &app_action_name::<Name>());
+    }
+
+    /// Add a button that activates an application action with a target value.
+    pub fn add_button_with_target_value<Name: ActionName>(
+        &mut self,
+        label: &str,
+        target: &Name::Target,
+    ) where
+        Name::Target: ToVariant,
+    {
+        self.notification.add_button_with_target_value(
+            label,
+            &app_action_name::<Name>(),
+            Some(&target.to_variant()),
+        );
+    }
+
+    /// Add a button that sends a message when clicked.
+    pub fn add_button_with_message<Msg>(&mut self, label: &str, sender: &Sender<Msg>, msg: Msg)
+    where
+        Msg: Clone + 'static,
+    {
+        let name = self.add_message_action(sender, msg);
+        self.notification.add_button(label, &format!("app.{name}"));
+    }
+
+    /// Set the application action that is activated when the notification is clicked.
+    pub fn set_default_action<Name: ActionName>(&mut self)
+    where
+        Name::Target: EmptyType,
+    {
+        self.notification
+            .set_default_action(&app_action_name::<Name>());
+    }
+
+    /// Set the application action and its target value that is activated
+    /// when the notification is clicked.
+    pub fn set_default_action_with_target_value<Name: ActionName>(&mut self, target: &Name::Target)
+    where
+        Name::Target: ToVariant,
+    {
+        self.notification.set_default_action_and_target_value(
+            &app_action_name::<Name>(),
+            Some(&target.to_variant()),
+        );
+    }
+
+    /// Set a message that is sent when the notification is clicked.
+    pub fn set_default_message<Msg>(&mut self, sender: &Sender<Msg>, msg: Msg)
+    where
+        Msg: Clone + 'static,
+    {
+        let name = self.add_message_action(sender, msg);
+        self.notification.set_default_action(&format!("app.{name}"));
+    }
+
+    /// Returns the inner [`gio::Notification`].
+    ///
+    /// This method is meant for low level control.
+    /// Only use it if you know exactly what you are doing.
+    #[must_use]
+    pub fn gio_notification(&self) -> &gio::Notification {
+        &self.notification
+    }
+
+    /// Send the notification through the [`main_application()`](crate::main_application).
+    ///
+    /// If a notification with the same ID was sent before, it will be replaced.
+    /// The notification is withdrawn when the returned handle is dropped.
+    pub fn send(self, id: &str) -> NotificationHandle {
+        let Self {
+            notification,
+            message_actions,
+        } = self;
+
+        let generation = register_notification(id, message_actions);
+        crate::main_application().send_notification(Some(id), &notification);
+
+        NotificationHandle {
+            id: id.to_owned(),
+            generation: Some(generation),
+        }
+    }
+
+    fn add_message_action<Msg>(&mut self, sender: &Sender<Msg>, msg: Msg) -> String
+    where
+        Msg: Clone + 'static,
+    {
+        let name = unique_action_name();
+        let sender = sender.clone();
+        self.message_actions
+            .push((name.clone(), Box::new(move || sender.emit(msg.clone()))));
+        name
+    }
+}
+
+/// A handle to a notification sent with [`Notification::send()`].
+///
+/// Dropping the handle withdraws the notification and removes the actions
+/// of its message buttons, unless the notification was replaced by another
+/// notification with the same ID in the meantime.
+#[derive(Debug)]
+#[must_use = "Dropping the handle withdraws the notification"]
+pub struct NotificationHandle {
+    id: String,
+    /// [`None`] if the handle was detached.
+    generation: Option<usize>,
+}
+
+impl NotificationHandle {
+    /// Returns the ID of the notification.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Withdraw the notification.
+    ///
+    /// This is the same as dropping the handle.
+    pub fn withdraw(self) {}
+
+    /// Keep the notification after the handle is dropped.
+    ///
+    /// The actions of its message buttons are removed once the notification
+    /// is clicked, replaced or withdrawn with [`withdraw()`].
+    pub fn detach(mut self) {
+        self.generation = None;
+    }
+}
+
+impl Drop for NotificationHandle {
+    fn drop(&mut self) {
+        if let Some(generation) = self.generation
+            && remove_notification(&self.id, Some(generation))
+        {
+            crate::main_application().withdraw_notification(&self.id);
+        }
+    }
+}
+
+/// Withdraw a notification that was sent with the given ID.
+pub fn withdraw(id: &str) {
+    crate::main_application().withdraw_notification(id);
+    remove_notification(id, None);
+}
+
+#[cfg(test)]
+mod test {
+    use gtk::glib::MainContext;
+    use gtk::prelude::{ActionGroupExt, ActionMapExt};
+
+    use super::{Notification, register_notification, remove_notification};
+
+    fn action_names(notification: &Notification) -> Vec<String> {
+        notification
+            .message_actions
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    fn is_registered(name: &str) -> bool {
+        crate::main_application().lookup_action(name).is_some()
+    }
+
+    #[gtk::test]
+    fn replace_and_withdraw() {
+        let (sender, _receiver) = crate::channel::<u8>();
+
+        let mut notification = Notification::new("First");
+        notification.add_button_with_message("One", &sender, 1);
+        notification.set_default_message(&sender, 2);
+        let first_names = action_names(&notification);
+        assert_eq!(first_names.len(), 2);
+
+        let first = register_notification("replace", notification.message_actions);
+        assert!(first_names.iter().all(|name| is_registered(name)));
+
+        // Replacing the notification removes the old actions.
+        let mut notification = Notification::new("Second");
+        notification.add_button_with_message("Three", &sender, 3);
+        let second_names = action_names(&notification);
+        let second = register_notification("replace", notification.message_actions);
+        assert_ne!(first, second);
+        assert!(!first_names.iter().any(|name| is_registered(name)));
+        assert!(second_names.iter().all(|name| is_registered(name)));
+
+        // Outdated handles don't remove the actions of the new notification.
+        assert!(!remove_notification("replace", Some(first)));
+        assert!(second_names.iter().all(|name| is_registered(name)));
+
+        assert!(remove_notification("replace", Some(second)));
+        assert!(!second_names.iter().any(|name| is_registered(name)));
+        assert!(!remove_notification("replace", None));
+    }
+
+    #[gtk::test]
+    fn message_button() {
+        let (sender, receiver) = crate::channel::<u8>();
+
+        let mut notification = Notification::new("Message");
+        notification.add_button_with_message("Send", &sender, 7);
+        let names = action_names(&notification);
+        register_notification("message", notification.message_actions);
+
+        crate::main_application().activate_action(&names[0], None);
+        assert_eq!(receiver.recv_sync(), Some(7));
+
+        // The actions are removed after the notification was clicked.
+        MainContext::default().iteration(false);
+        assert!(!is_registered(&names[0]));
+        assert!(!remove_notification("message", None));
+    }
+}
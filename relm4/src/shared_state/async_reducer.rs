@@ -1,11 +1,16 @@
 use std::future::Future;
+use std::ops::Deref;
 use std::sync::{Arc, RwLock};
 
-use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
 
 use crate::{RUNTIME, Sender};
 
-use super::SubscriberFn;
+use super::middleware::{MiddlewareInit, after_reduce, before_reduce};
+use super::{
+    Changes, Observable, Reduction, SubscriberFn, Subscribers, Subscription, selector_subscriber,
+};
 
 /// A trait that implements an async reducer function.
 ///
@@ -19,43 +24,65 @@ pub trait AsyncReducible: Send {
 
     /// Process the input message and update the state asynchronously.
     ///
-    /// Return [`true`] to notify all subscribers.
-    /// Return [`false`] to ignore all subscribers.
+    /// Return [`Reduction::notify()`] or `true.into()` to notify all subscribers.
+    /// Return [`Reduction::ignore()`] or `false.into()` to ignore all subscribers.
     ///
-    /// For example, it makes sense to return [`false`] to indicate
+    /// For example, it makes sense to ignore the subscribers to indicate
     /// that the message had no (noteworthy) effect on the data and
     /// the subscribers don't need to be notified.
-    fn reduce(&mut self, input: Self::Input) -> impl Future<Output = bool> + Send;
+    ///
+    /// Side effects can be added to the reduction as [`Effect`](super::Effect)s.
+    /// They are executed in the background after the input was processed
+    /// and can send new inputs to the reducer.
+    fn reduce(&mut self, input: Self::Input)
+    -> impl Future<Output = Reduction<Self::Input>> + Send;
 }
 
 struct AsyncReducerInner<Data: AsyncReducible> {
     sender: Sender<Data::Input>,
-    subscribers: Arc<RwLock<Vec<SubscriberFn<Data>>>>,
+    /// Is [`None`] only while the data is initialized.
+    data: Arc<Mutex<Option<Data>>>,
+    subscribers: Arc<RwLock<Subscribers<Data>>>,
 }
 
-impl<Data> Default for AsyncReducerInner<Data>
+impl<Data> AsyncReducerInner<Data>
 where
     Data: AsyncReducible + 'static,
 {
-    fn default() -> Self {
+    fn new(middleware: Option<MiddlewareInit<Data, Data::Input>>) -> Self {
         let (sender, receiver) = crate::channel();
-        let subscribers: Arc<RwLock<Vec<SubscriberFn<Data>>>> = Arc::default();
+        let data: Arc<Mutex<Option<Data>>> = Arc::default();
+        let subscribers: Arc<RwLock<Subscribers<Data>>> = Arc::default();
 
+        // Lock the data until it's initialized, so nobody can read it before.
+        let mut init_guard = data.clone().try_lock_owned().unwrap();
+
+        let rt_data = data.clone();
         let rt_subscribers = subscribers.clone();
+        // Effects only hold a weak sender, so they don't keep the runtime alive.
+        let rt_sender = sender.0.downgrade();
         RUNTIME.spawn(async move {
-            let mut data = Data::init().await;
+            *init_guard = Some(Data::init().await);
+            drop(init_guard);
+
+            let mut middleware = middleware.map(|init| init()).unwrap_or_default();
             while let Some(input) = receiver.recv().await {
-                if data.reduce(input).await {
-                    rt_subscribers
-                        .write()
-                        .unwrap()
-                        .retain(|subscriber| subscriber(&data));
-                }
+                // Subscribers are notified while the data is locked, so subscribers
+                // that receive the current value on subscription don't miss updates.
+                let mut guard = rt_data.lock().await;
+                let data = guard.as_mut().unwrap();
+                let Some(input) = before_reduce(&mut middleware, data, input) else {
+                    continue;
+                };
+                let mut reduction = data.reduce(input).await;
+                after_reduce(&mut middleware, data, &mut reduction);
+                reduction.finish(data, &rt_subscribers, &rt_sender);
             }
         });
 
         Self {
             sender,
+            data,
             subscribers,
         }
     }
@@ -68,6 +95,7 @@ where
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AsyncReducerInner")
             .field("sender", &self.sender)
+            .field("data", &self.data)
             .field("subscribers", &self.subscribers.try_read().map(|s| s.len()))
             .finish()
     }
@@ -91,6 +119,7 @@ where
 ///
 /// ```
 /// use relm4::{AsyncReducer, AsyncReducible};
+/// use relm4::shared_state::Reduction;
 ///
 /// struct CounterReducer(u8);
 ///
@@ -106,7 +135,7 @@ where
 ///         Self(0)
 ///     }
 ///
-///     async fn reduce(&mut self, input: Self::Input) -> bool {
+///     async fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
 ///         match input {
 ///             CounterInput::Increment => {
 ///                 self.0 += 1;
@@ -115,7 +144,7 @@ where
 ///                 self.0 -= 1;
 ///             }
 ///         }
-///         true
+///         Reduction::notify()
 ///     }
 /// }
 ///
@@ -129,7 +158,7 @@ where
 ///
 /// // Create a channel and subscribe to changes.
 /// let (sender, receiver) = relm4::channel();
-/// REDUCER.subscribe(&sender, |data| data.0);
+/// let _subscription = REDUCER.subscribe(&sender, |data| data.0);
 ///
 /// // Count up to 2.
 /// REDUCER.emit(CounterInput::Increment);
@@ -137,7 +166,8 @@ where
 /// ```
 #[derive(Debug)]
 pub struct AsyncReducer<Data: AsyncReducible> {
-    inner: Lazy<AsyncReducerInner<Data>>,
+    inner: OnceCell<AsyncReducerInner<Data>>,
+    middleware: Option<MiddlewareInit<Data, Data::Input>>,
 }
 
 impl<Data> Default for AsyncReducer<Data>
@@ -159,67 +189,210 @@ where
     #[must_use]
     pub const fn new() -> Self {
         Self {
-            inner: Lazy::new(AsyncReducerInner::default),
+            inner: OnceCell::new(),
+            middleware: None,
+        }
+    }
+
+    /// Create a new [`AsyncReducer`] variable that applies [`Middleware`](super::Middleware)
+    /// around its reducer function.
+    ///
+    /// The middleware is created by the given function
+    /// when the data is initialized on the first access.
+    ///
+    /// See [`Reducer::with_middleware()`](super::Reducer::with_middleware) for an example.
+    #[must_use]
+    pub const fn with_middleware(middleware: MiddlewareInit<Data, Data::Input>) -> Self {
+        Self {
+            inner: OnceCell::new(),
+            middleware: Some(middleware),
         }
     }
 
+    fn inner(&self) -> &AsyncReducerInner<Data> {
+        self.inner
+            .get_or_init(|| AsyncReducerInner::new(self.middleware))
+    }
+
     /// Subscribe to an [`AsyncReducer`].
     /// Any subscriber will be notified with a message every time
     /// you modify the reducer (by calling [`Self::emit()`]).
-    pub fn subscribe<Msg, F>(&self, sender: &Sender<Msg>, f: F)
+    ///
+    /// The subscriber is removed once the returned [`Subscription`] is dropped.
+    /// Call [`Subscription::detach()`] to keep it until the receiver is dropped instead.
+    pub fn subscribe<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
     where
         F: Fn(&Data) -> Msg + 'static + Send + Sync,
         Msg: Send + 'static,
     {
         let sender = sender.clone();
-        self.inner
-            .subscribers
-            .write()
-            .unwrap()
-            .push(Box::new(move |data: &Data| {
-                let msg = f(data);
-                sender.send(msg).is_ok()
-            }));
+        self.add_subscriber(Box::new(move |data: &Data| {
+            let msg = f(data);
+            sender.send(msg).is_ok()
+        }))
     }
 
     /// An alternative version of [`subscribe()`](Self::subscribe()) that only send a message if
     /// the closure returns [`Some`].
-    pub fn subscribe_optional<Msg, F>(&self, sender: &Sender<Msg>, f: F)
+    pub fn subscribe_optional<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
     where
         F: Fn(&Data) -> Option<Msg> + 'static + Send + Sync,
         Msg: Send + 'static,
     {
         let sender = sender.clone();
-        self.inner
+        self.add_subscriber(Box::new(move |data: &Data| {
+            if let Some(msg) = f(data) {
+                sender.send(msg).is_ok()
+            } else {
+                true
+            }
+        }))
+    }
+
+    /// Subscribe to a part of an [`AsyncReducer`].
+    ///
+    /// The `selector` closure picks the part of the data this subscriber
+    /// is interested in. A message is only sent if the selected value
+    /// differs from the previously selected value.
+    ///
+    /// This waits until the data is initialized and no input is processed.
+    pub async fn subscribe_selector<Msg, Selected, S, F>(
+        &self,
+        sender: &Sender<Msg>,
+        selector: S,
+        f: F,
+    ) -> Subscription
+    where
+        S: Fn(&Data) -> Selected + 'static + Send + Sync,
+        F: Fn(&Selected) -> Msg + 'static + Send + Sync,
+        Selected: PartialEq + Send + 'static,
+        Msg: Send + 'static,
+    {
+        // Keep the data locked, so no update can happen in between.
+        let data = self.read().await;
+        let initial = selector(&data);
+        let subscription =
+            self.add_subscriber(selector_subscriber(sender, Some(initial), selector, f));
+        drop(data);
+        subscription
+    }
+
+    /// Subscribe to specific fields of the [`Observable`] data of an [`AsyncReducer`].
+    ///
+    /// A message is only sent if at least one of the given fields
+    /// was changed since the subscribers were notified the last time.
+    ///
+    /// This waits until the data is initialized and no input is processed.
+    pub async fn subscribe_fields<Msg, F>(
+        &self,
+        sender: &Sender<Msg>,
+        fields: Changes,
+        f: F,
+    ) -> Subscription
+    where
+        Data: Observable + 'static,
+        F: Fn(&Data) -> Msg + 'static + Send + Sync,
+        Msg: Send + 'static,
+    {
+        let inner = self.inner();
+        // The runtime locks the data before the subscribers.
+        let mut guard = inner.data.lock().await;
+        let data = guard.as_mut().expect("AsyncReducer data is initialized");
+        let id = inner
             .subscribers
             .write()
             .unwrap()
-            .push(Box::new(move |data: &Data| {
-                if let Some(msg) = f(data) {
-                    sender.send(msg).is_ok()
-                } else {
-                    true
-                }
-            }));
+            .add_field_subscriber(data, sender, fields, f);
+        drop(guard);
+        Subscription::new(&inner.subscribers, id)
+    }
+
+    /// Subscribe to an [`AsyncReducer`] and immediately receive a message
+    /// for the current data.
+    ///
+    /// Afterwards, the subscriber will be notified like with [`Self::subscribe()`].
+    /// This is useful for components that are initialized after the last change
+    /// of the reducer.
+    ///
+    /// This waits until the data is initialized and no input is processed.
+    pub async fn subscribe_with_current<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
+    where
+        F: Fn(&Data) -> Msg + 'static + Send + Sync,
+        Msg: Send + 'static,
+    {
+        // Keep the data locked, so no update can happen in between.
+        let data = self.read().await;
+        // The receiver might be dropped already. In this case,
+        // the subscriber will be removed on the next update.
+        let _ = sender.send(f(&data));
+        let subscription = self.subscribe(sender, f);
+        drop(data);
+        subscription
+    }
+
+    /// Get immutable access to the current data.
+    ///
+    /// This waits until the data is initialized and no input is processed.
+    /// The returned RAII guard prevents the reducer from updating its data
+    /// until it is dropped. Therefore, the guard should only be held for a short time.
+    pub async fn read(&self) -> AsyncReducerReadGuard<'_, Data> {
+        let guard = self.inner().data.lock().await;
+        AsyncReducerReadGuard {
+            inner: MutexGuard::map(guard, |data| {
+                data.as_mut().expect("AsyncReducer data is initialized")
+            }),
+        }
+    }
+
+    /// Get a copy of the current data.
+    ///
+    /// This waits until the data is initialized and no input is processed.
+    pub async fn snapshot(&self) -> Data
+    where
+        Data: Clone,
+    {
+        self.read().await.clone()
+    }
+
+    fn add_subscriber(&self, subscriber: SubscriberFn<Data>) -> Subscription {
+        let id = self.inner().subscribers.write().unwrap().add(subscriber);
+        Subscription::new(&self.inner().subscribers, id)
     }
 
     /// Sends a message to the reducer to update its state asynchronously.
     ///
-    /// If the [`AsyncReducible::reduce()`] method returns [`true`],
-    /// all subscribers will be notified.
+    /// If the [`Reduction`] returned by the [`AsyncReducible::reduce()`] method
+    /// notifies the subscribers, all subscribers will be notified.
     pub fn emit(&self, input: Data::Input) {
         assert!(
-            self.inner.sender.send(input).is_ok(),
+            self.inner().sender.send(input).is_ok(),
             "AsyncReducer runtime was dropped. Maybe a subscriber or the update function panicked?"
         );
     }
 }
 
+/// A guard that immutably dereferences the data of an [`AsyncReducer`].
+///
+/// The reducer can't update its data while this guard exists.
+#[derive(Debug)]
+pub struct AsyncReducerReadGuard<'a, Data> {
+    inner: MappedMutexGuard<'a, Data>,
+}
+
+impl<Data> Deref for AsyncReducerReadGuard<'_, Data> {
+    type Target = Data;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::time::Duration;
 
     use super::{AsyncReducer, AsyncReducible};
+    use crate::shared_state::{Effect, Reduction};
 
     struct CounterReducer(u8);
 
@@ -235,7 +408,7 @@ mod test {
             Self(0)
         }
 
-        async fn reduce(&mut self, input: Self::Input) -> bool {
+        async fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
             match input {
                 CounterInput::Increment => {
                     self.0 += 1;
@@ -244,7 +417,7 @@ mod test {
                     self.0 -= 1;
                 }
             }
-            true
+            Reduction::notify()
         }
     }
 
@@ -259,7 +432,7 @@ mod test {
 
         let (sender, receiver) = crate::channel();
 
-        REDUCER.subscribe(&sender, |data| data.0);
+        let _subscription = REDUCER.subscribe(&sender, |data| data.0);
 
         REDUCER.emit(CounterInput::Increment);
         assert_eq!(receiver.recv_sync().unwrap(), 4);
@@ -268,4 +441,130 @@ mod test {
 
         assert_eq!(receiver.recv_sync().unwrap(), 3);
     }
+
+    #[derive(Default)]
+    struct PairReducer(u8, u8);
+
+    enum PairInput {
+        IncrementFirst,
+        IncrementSecond,
+    }
+
+    impl AsyncReducible for PairReducer {
+        type Input = PairInput;
+
+        async fn init() -> Self {
+            Self::default()
+        }
+
+        async fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
+            match input {
+                PairInput::IncrementFirst => self.0 += 1,
+                PairInput::IncrementSecond => self.1 += 1,
+            }
+            Reduction::notify()
+        }
+    }
+
+    static PAIR_REDUCER: AsyncReducer<PairReducer> = AsyncReducer::new();
+
+    #[test]
+    fn subscribe_selector() {
+        let (sender, receiver) = crate::channel();
+
+        let _subscription = crate::RUNTIME.block_on(PAIR_REDUCER.subscribe_selector(
+            &sender,
+            |data| data.1,
+            |value| *value,
+        ));
+
+        // The selected value is unchanged, so no message is sent.
+        PAIR_REDUCER.emit(PairInput::IncrementFirst);
+        PAIR_REDUCER.emit(PairInput::IncrementSecond);
+        assert_eq!(receiver.recv_sync().unwrap(), 1);
+
+        // Changes of the first value are ignored.
+        PAIR_REDUCER.emit(PairInput::IncrementFirst);
+        PAIR_REDUCER.emit(PairInput::IncrementSecond);
+        assert_eq!(receiver.recv_sync().unwrap(), 2);
+    }
+
+    struct EffectReducer(u8);
+
+    enum EffectInput {
+        Increment,
+        IncrementTwiceLater,
+    }
+
+    impl AsyncReducible for EffectReducer {
+        type Input = EffectInput;
+
+        async fn init() -> Self {
+            Self(0)
+        }
+
+        async fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
+            match input {
+                EffectInput::Increment => {
+                    self.0 += 1;
+                    Reduction::notify()
+                }
+                EffectInput::IncrementTwiceLater => {
+                    Reduction::ignore().with_effect(Effect::new(|sender| async move {
+                        sender.emit(EffectInput::Increment);
+                        sender.emit(EffectInput::Increment);
+                    }))
+                }
+            }
+        }
+    }
+
+    static EFFECT_REDUCER: AsyncReducer<EffectReducer> = AsyncReducer::new();
+
+    #[test]
+    fn effects() {
+        let (sender, receiver) = crate::channel();
+
+        let _subscription = EFFECT_REDUCER.subscribe(&sender, |data| data.0);
+
+        EFFECT_REDUCER.emit(EffectInput::IncrementTwiceLater);
+        assert_eq!(receiver.recv_sync().unwrap(), 1);
+        assert_eq!(receiver.recv_sync().unwrap(), 2);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct NameReducer(String);
+
+    impl AsyncReducible for NameReducer {
+        type Input = &'static str;
+
+        async fn init() -> Self {
+            Self("Relm4".into())
+        }
+
+        async fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
+            self.0 = input.into();
+            Reduction::notify()
+        }
+    }
+
+    static NAME_REDUCER: AsyncReducer<NameReducer> = AsyncReducer::new();
+
+    #[test]
+    fn read_current_value() {
+        crate::RUNTIME.block_on(async {
+            assert_eq!(NAME_REDUCER.read().await.0, "Relm4");
+            assert_eq!(NAME_REDUCER.snapshot().await, NameReducer("Relm4".into()));
+
+            let (sender, receiver) = crate::channel();
+            let _subscription = NAME_REDUCER
+                .subscribe_with_current(&sender, |data| data.0.clone())
+                .await;
+            assert_eq!(receiver.recv().await.unwrap(), "Relm4");
+
+            NAME_REDUCER.emit("GTK");
+            assert_eq!(receiver.recv().await.unwrap(), "GTK");
+            assert_eq!(NAME_REDUCER.read().await.0, "GTK");
+        });
+    }
 }
@@ -0,0 +1,164 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::RwLock;
+
+use crate::Sender;
+
+use super::Subscribers;
+
+type EffectFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+type EffectFn<Input> = Box<dyn FnOnce(Sender<Input>) -> EffectFuture + Send>;
+
+/// A side effect that is executed by the runtime of a reducer
+/// after an input was processed.
+///
+/// Effects run in the background, so they don't block the reducer.
+/// They can send new inputs back to the reducer using the [`Sender`]
+/// passed to them.
+pub struct Effect<Input> {
+    func: EffectFn<Input>,
+}
+
+impl<Input> fmt::Debug for Effect<Input> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Effect").finish_non_exhaustive()
+    }
+}
+
+impl<Input: Send + 'static> Effect<Input> {
+    /// Create an effect from an asynchronous function that can send
+    /// any number of inputs back to the reducer.
+    pub fn new<F, Fut>(func: F) -> Self
+    where
+        F: FnOnce(Sender<Input>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Self {
+            func: Box::new(move |sender| Box::pin(func(sender))),
+        }
+    }
+
+    /// Create an effect from a future that sends its output
+    /// back to the reducer, if it returns [`Some`].
+    ///
+    /// Essentially, this is a simpler version of [`Self::new()`].
+    pub fn oneshot<Fut>(future: Fut) -> Self
+    where
+        Fut: Future<Output = Option<Input>> + Send + 'static,
+    {
+        Self::new(move |sender| async move {
+            if let Some(input) = future.await {
+                // The reducer might be gone already.
+                let _ = sender.send(input);
+            }
+        })
+    }
+
+    /// Create an effect from a blocking function.
+    /// The function runs on a thread-pool in the background.
+    pub fn blocking<F>(func: F) -> Self
+    where
+        F: FnOnce(Sender<Input>) + Send + 'static,
+    {
+        Self::new(move |sender| async move {
+            let _ = crate::spawn_blocking(move || func(sender)).await;
+        })
+    }
+
+    fn run(self, sender: Sender<Input>) {
+        crate::spawn((self.func)(sender));
+    }
+}
+
+/// The result of processing an input in a reducer.
+///
+/// It decides whether subscribers are notified and which
+/// [`Effect`]s are executed afterwards.
+///
+/// Returning a [`bool`] converts into a reduction without effects.
+#[must_use]
+pub struct Reduction<Input> {
+    notify: bool,
+    effects: Vec<Effect<Input>>,
+}
+
+impl<Input> fmt::Debug for Reduction<Input> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reduction")
+            .field("notify", &self.notify)
+            .field("effects", &self.effects.len())
+            .finish()
+    }
+}
+
+impl<Input> From<bool> for Reduction<Input> {
+    fn from(notify: bool) -> Self {
+        Self {
+            notify,
+            effects: Vec::new(),
+        }
+    }
+}
+
+impl<Input> Reduction<Input> {
+    /// Notify all subscribers.
+    pub fn notify() -> Self {
+        Self::from(true)
+    }
+
+    /// Don't notify any subscribers.
+    ///
+    /// For example, it makes sense to use this to indicate
+    /// that the message had no (noteworthy) effect on the data and
+    /// the subscribers don't need to be notified.
+    pub fn ignore() -> Self {
+        Self::from(false)
+    }
+
+    /// Add an effect that is executed after the input was processed.
+    pub fn with_effect(mut self, effect: Effect<Input>) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// Add an effect that is executed after the input was processed.
+    pub fn add_effect(&mut self, effect: Effect<Input>) {
+        self.effects.push(effect);
+    }
+
+    /// Returns [`true`] if the subscribers will be notified.
+    #[must_use]
+    pub fn notifies_subscribers(&self) -> bool {
+        self.notify
+    }
+
+    /// Set whether the subscribers will be notified.
+    pub fn set_notify(&mut self, notify: bool) {
+        self.notify = notify;
+    }
+
+    /// Notify the subscribers if requested and start all effects.
+    pub(super) fn finish<Data>(
+        self,
+        data: &mut Data,
+        subscribers: &RwLock<Subscribers<Data>>,
+        sender: &flume::WeakSender<Input>,
+    ) where
+        Input: Send + 'static,
+    {
+        let Self { notify, effects } = self;
+
+        if notify {
+            subscribers.write().unwrap().notify(data);
+        }
+
+        if !effects.is_empty()
+            && let Some(sender) = sender.upgrade()
+        {
+            for effect in effects {
+                effect.run(Sender(sender.clone()));
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::any;
+use std::fmt::{self, Debug};
+
+use super::Reduction;
+
+/// A list of middleware that is applied in order.
+pub(super) type MiddlewareStack<Data, Input> = Vec<Box<dyn Middleware<Data, Input>>>;
+
+/// A function that creates the middleware of a reducer.
+pub(super) type MiddlewareInit<Data, Input> = fn() -> MiddlewareStack<Data, Input>;
+
+/// Middleware wraps the reducer function of [`Reducer`](super::Reducer)
+/// and [`AsyncReducer`](super::AsyncReducer).
+///
+/// It can inspect, modify or reject inputs before they are processed
+/// and inspect the data and modify the [`Reduction`] afterwards,
+/// for example to add [`Effect`](super::Effect)s.
+///
+/// Several middleware can be combined with
+/// [`Reducer::with_middleware()`](super::Reducer::with_middleware).
+/// They are applied in the order they were passed.
+pub trait Middleware<Data, Input>: Send {
+    /// Called before the input is processed.
+    ///
+    /// Return [`None`] to reject the input.
+    fn before(&mut self, data: &Data, input: Input) -> Option<Input> {
+        let _ = data;
+        Some(input)
+    }
+
+    /// Called after the input was processed.
+    fn after(&mut self, data: &Data, reduction: &mut Reduction<Input>) {
+        let _ = (data, reduction);
+    }
+}
+
+pub(super) fn before_reduce<Data, Input>(
+    middleware: &mut MiddlewareStack<Data, Input>,
+    data: &Data,
+    input: Input,
+) -> Option<Input> {
+    middleware
+        .iter_mut()
+        .try_fold(input, |input, middleware| middleware.before(data, input))
+}
+
+pub(super) fn after_reduce<Data, Input>(
+    middleware: &mut MiddlewareStack<Data, Input>,
+    data: &Data,
+    reduction: &mut Reduction<Input>,
+) {
+    for middleware in middleware {
+        middleware.after(data, reduction);
+    }
+}
+
+/// Middleware that logs every input and the resulting data
+/// with [`tracing::debug`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LoggingMiddleware;
+
+impl<Data, Input> Middleware<Data, Input> for LoggingMiddleware
+where
+    Data: Debug,
+    Input: Debug,
+{
+    fn before(&mut self, _data: &Data, input: Input) -> Option<Input> {
+        tracing::debug!("{} received input: {:?}", any::type_name::<Data>(), input);
+        Some(input)
+    }
+
+    fn after(&mut self, data: &Data, reduction: &mut Reduction<Input>) {
+        tracing::debug!(
+            "{} updated (notify: {}): {:?}",
+            any::type_name::<Data>(),
+            reduction.notifies_subscribers(),
+            data
+        );
+    }
+}
+
+/// Middleware that rejects inputs if the validation function returns [`false`].
+pub struct ValidationMiddleware<F> {
+    validate: F,
+}
+
+impl<F> Debug for ValidationMiddleware<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidationMiddleware")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F> ValidationMiddleware<F> {
+    /// Create a new middleware from a validation function.
+    pub const fn new(validate: F) -> Self {
+        Self { validate }
+    }
+}
+
+impl<Data, Input, F> Middleware<Data, Input> for ValidationMiddleware<F>
+where
+    F: FnMut(&Data, &Input) -> bool + Send,
+{
+    fn before(&mut self, data: &Data, input: Input) -> Option<Input> {
+        if (self.validate)(data, &input) {
+            Some(input)
+        } else {
+            tracing::debug!("{} rejected input", any::type_name::<Data>());
+            None
+        }
+    }
+}
+
+/// Middleware that calls the persistence function with the
+/// updated data whenever the subscribers are notified.
+///
+/// The function runs inside the runtime of the reducer,
+/// so expensive operations should be moved into an
+/// [`Effect`](super::Effect) by cloning the data.
+pub struct PersistenceMiddleware<F> {
+    persist: F,
+}
+
+impl<F> Debug for PersistenceMiddleware<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistenceMiddleware")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F> PersistenceMiddleware<F> {
+    /// Create a new middleware from a persistence function.
+    pub const fn new(persist: F) -> Self {
+        Self { persist }
+    }
+}
+
+impl<Data, Input, F> Middleware<Data, Input> for PersistenceMiddleware<F>
+where
+    F: FnMut(&Data) + Send,
+{
+    fn after(&mut self, data: &Data, reduction: &mut Reduction<Input>) {
+        if reduction.notifies_subscribers() {
+            (self.persist)(data);
+        }
+    }
+}
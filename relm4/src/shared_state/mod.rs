@@ -1,11 +1,145 @@
 //! Shared state that can be accessed by many components.
 
 mod async_reducer;
+mod effect;
+mod middleware;
+mod observable;
+#[cfg(feature = "serde")]
+mod persistent;
 mod reducer;
 mod state;
+mod subscription;
+
+use std::sync::Mutex;
+
+use crate::Sender;
 
 type SubscriberFn<Data> = Box<dyn Fn(&Data) -> bool + 'static + Send + Sync>;
 
-pub use async_reducer::{AsyncReducer, AsyncReducible};
-pub use reducer::{Reducer, Reducible};
+pub use async_reducer::{AsyncReducer, AsyncReducerReadGuard, AsyncReducible};
+pub use effect::{Effect, Reduction};
+pub use middleware::{LoggingMiddleware, Middleware, PersistenceMiddleware, ValidationMiddleware};
+pub use observable::{Changes, Observable};
+#[cfg(feature = "serde")]
+pub(crate) use persistent::flush_all as flush_persistent_states;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub use persistent::{PersistentState, PersistentStorage};
+pub use reducer::{Reducer, ReducerReadGuard, Reducible};
 pub use state::{SharedState, SharedStateReadGuard, SharedStateWriteGuard};
+pub use subscription::Subscription;
+
+/// Derive macro for the [`Observable`] trait.
+#[cfg(feature = "macros")]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+pub use relm4_macros::Observable;
+
+/// The subscribers of a shared state type.
+///
+/// Each subscriber has a unique ID, so it can be removed by its [`Subscription`].
+struct Subscribers<Data> {
+    next_id: usize,
+    subscribers: Vec<(usize, SubscriberFn<Data>)>,
+    /// Resets the changes of [`Observable`] data after notifying the subscribers.
+    reset_changes: Option<fn(&mut Data)>,
+}
+
+impl<Data> Default for Subscribers<Data> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            subscribers: Vec::new(),
+            reset_changes: None,
+        }
+    }
+}
+
+impl<Data> Subscribers<Data> {
+    /// Add a subscriber and return its ID.
+    fn add(&mut self, subscriber: SubscriberFn<Data>) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.subscribers.push((id, subscriber));
+        id
+    }
+
+    fn remove(&mut self, id: usize) {
+        self.subscribers
+            .retain(|(subscriber_id, _)| *subscriber_id != id);
+    }
+
+    fn notify(&mut self, data: &mut Data) {
+        // Remove all elements which had their senders dropped.
+        self.subscribers.retain(|(_, subscriber)| subscriber(data));
+
+        if let Some(reset_changes) = self.reset_changes {
+            reset_changes(data);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.subscribers.len()
+    }
+}
+
+impl<Data: Observable> Subscribers<Data> {
+    /// Add a subscriber that is only notified if one of the given fields changed.
+    ///
+    /// `data` must be locked while the subscribers are locked,
+    /// so no update can happen in between.
+    fn add_field_subscriber<Msg, F>(
+        &mut self,
+        data: &mut Data,
+        sender: &Sender<Msg>,
+        fields: Changes,
+        f: F,
+    ) -> usize
+    where
+        F: Fn(&Data) -> Msg + 'static + Send + Sync,
+        Msg: Send + 'static,
+    {
+        // Once subscribers depend on the changes, they must be reset after each notification.
+        // Changes from before weren't reset, so they must not notify the new subscriber.
+        if self.reset_changes.is_none() {
+            data.reset_changes();
+            self.reset_changes = Some(Data::reset_changes);
+        }
+
+        let sender = sender.clone();
+        self.add(Box::new(move |data: &Data| {
+            if data.changed(fields) {
+                sender.send(f(data)).is_ok()
+            } else {
+                true
+            }
+        }))
+    }
+}
+
+/// Create a subscriber that only sends a message if the selected value changed.
+fn selector_subscriber<Data, Selected, Msg, S, F>(
+    sender: &Sender<Msg>,
+    initial: Option<Selected>,
+    selector: S,
+    f: F,
+) -> SubscriberFn<Data>
+where
+    S: Fn(&Data) -> Selected + 'static + Send + Sync,
+    F: Fn(&Selected) -> Msg + 'static + Send + Sync,
+    Selected: PartialEq + Send + 'static,
+    Msg: Send + 'static,
+{
+    let sender = sender.clone();
+    let previous = Mutex::new(initial);
+    Box::new(move |data: &Data| {
+        let selected = selector(data);
+        let mut previous = previous.lock().unwrap();
+        if previous.as_ref() == Some(&selected) {
+            true
+        } else {
+            let msg = f(&selected);
+            *previous = Some(selected);
+            sender.send(msg).is_ok()
+        }
+    })
+}
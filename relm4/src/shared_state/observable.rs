@@ -0,0 +1,172 @@
+use std::ops::{BitOr, BitOrAssign};
+
+/// A set of fields of an [`Observable`] type that changed.
+///
+/// Each field is represented by a single bit,
+/// so sets of fields can be combined with `|`.
+/// The sets of individual fields are returned by associated functions
+/// named after the fields, generated by `#[derive(Observable)]`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Changes(u64);
+
+impl Changes {
+    /// No fields.
+    pub const NONE: Self = Self(0);
+
+    /// All fields.
+    pub const ALL: Self = Self(u64::MAX);
+
+    /// The set that only contains the field with the given index.
+    ///
+    /// This is used by `#[derive(Observable)]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is 64 or larger.
+    #[must_use]
+    pub const fn field(index: u32) -> Self {
+        assert!(
+            index < u64::BITS,
+            "Observable types support at most 64 fields"
+        );
+        Self(1 << index)
+    }
+
+    /// Returns [`true`] if no field is contained.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns [`true`] if at least one field is contained in both sets.
+    #[must_use]
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns [`true`] if all fields of `other` are contained in this set.
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Add all fields of `other` to this set.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitOr for Changes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Changes {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+/// A type that tracks which of its fields changed.
+///
+/// This trait is usually implemented with `#[derive(Observable)]`,
+/// which generates getters and setters for each field that record the changes.
+/// The subscribers added with `subscribe_fields`, for example
+/// [`SharedState::subscribe_fields()`](super::SharedState::subscribe_fields),
+/// are only notified if one of their fields changed.
+/// The changes are reset after the subscribers were notified.
+///
+/// # Example
+///
+/// ```
+/// use relm4::shared_state::{Changes, Observable};
+/// use relm4::SharedState;
+///
+/// #[derive(Default, Observable)]
+/// struct Document {
+///     title: String,
+///     text: String,
+///     #[observable(changes)]
+///     changes: Changes,
+/// }
+///
+/// static DOCUMENT: SharedState<Document> = SharedState::new();
+///
+/// let (sender, receiver) = relm4::channel();
+/// let _subscription = DOCUMENT.subscribe_fields(&sender, Document::title(), |document| {
+///     document.get_title().clone()
+/// });
+///
+/// // Doesn't send a message.
+/// DOCUMENT.write().set_text("Lorem ipsum".into());
+///
+/// DOCUMENT.write().set_title("Draft".into());
+/// assert_eq!(receiver.recv_sync().unwrap(), "Draft");
+/// ```
+///
+/// # Attributes
+///
+/// + `#[observable(changes)]` marks the field of type [`Changes`] that stores the changes.
+///   It's required exactly once.
+/// + `#[observable(skip)]` doesn't generate any methods for a field.
+/// + `#[observable(no_eq)]` marks a field as changed on every call of its setter,
+///   even if the value is equal to the previous value.
+///   By default, setters require [`PartialEq`] and only mark changed values.
+///
+/// For each remaining field `foo`, the derive macro generates `Self::foo()` which
+/// returns the [`Changes`] of the field and the methods `get_foo`, `get_mut_foo`,
+/// `set_foo` and `update_foo`.
+///
+/// Only changes made through these methods are tracked,
+/// so fields that should be observed are usually private.
+///
+/// If the type is serialized, the field that stores the changes
+/// should be skipped with `#[serde(skip)]`.
+pub trait Observable {
+    /// Returns the fields that changed since the changes were reset.
+    fn changes(&self) -> Changes;
+
+    /// Overwrite the changes.
+    fn set_changes(&mut self, changes: Changes);
+
+    /// Returns [`true`] if at least one of the given fields changed.
+    fn changed(&self, fields: Changes) -> bool {
+        self.changes().intersects(fields)
+    }
+
+    /// Mark all fields as changed.
+    fn mark_all_changed(&mut self) {
+        self.set_changes(Changes::ALL);
+    }
+
+    /// Reset the changes.
+    fn reset_changes(&mut self) {
+        self.set_changes(Changes::NONE);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Changes;
+
+    #[test]
+    fn changes() {
+        let first = Changes::field(0);
+        let second = Changes::field(1);
+
+        let mut changes = Changes::NONE;
+        assert!(changes.is_empty());
+
+        changes |= first;
+        assert!(changes.intersects(first | second));
+        assert!(!changes.contains(first | second));
+        assert!(!changes.intersects(second));
+
+        changes.insert(second);
+        assert!(changes.contains(first | second));
+        assert!(Changes::ALL.contains(changes));
+    }
+}
@@ -0,0 +1,580 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError, Weak};
+use std::time::Duration;
+
+use gtk::prelude::SettingsExt;
+use gtk::{gio, glib};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::Sender;
+
+use super::{
+    Changes, Observable, SharedStateReadGuard, SharedStateWriteGuard, SubscriberFn, Subscribers,
+    Subscription, selector_subscriber,
+};
+
+/// The storage backend of a [`PersistentState`].
+#[derive(Debug, Clone, Copy)]
+pub enum PersistentStorage {
+    /// Store the data as JSON file at the path returned by the function.
+    File(fn() -> PathBuf),
+    /// Store the data as JSON string in a [`gio::Settings`] key.
+    ///
+    /// The key must have the type `s`.
+    Settings {
+        /// The ID of the settings schema.
+        schema_id: &'static str,
+        /// The key inside of the schema.
+        key: &'static str,
+    },
+}
+
+impl PersistentStorage {
+    fn load<Data: DeserializeOwned>(&self) -> Option<Data> {
+        let bytes = match self {
+            Self::File(path) => match fs::read(path()) {
+                Ok(bytes) => bytes,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+                Err(err) => {
+                    tracing::error!("Couldn't read persistent state file: {}", err);
+                    return None;
+                }
+            },
+            Self::Settings { schema_id, key } => {
+                // Only read once, so a temporary instance is fine on any thread.
+                let value = lookup_settings(schema_id)?.string(key);
+                if value.is_empty() {
+                    return None;
+                }
+                value.as_bytes().to_vec()
+            }
+        };
+
+        serde_json::from_slice(&bytes)
+            .map_err(|err| {
+                tracing::error!("Couldn't parse persistent state: {}", err);
+            })
+            .ok()
+    }
+}
+
+thread_local! {
+    /// [`gio::Settings`] aren't thread safe, so they are only written
+    /// from the default main context and cached there.
+    static SETTINGS: RefCell<HashMap<&'static str, gio::Settings>> = RefCell::default();
+}
+
+/// The persistent states that were accessed, so pending changes can be saved on shutdown.
+static PERSISTENT_STATES: Mutex<Vec<Weak<dyn Flush>>> = Mutex::new(Vec::new());
+
+/// Saves the pending changes of all persistent states.
+pub(crate) fn flush_all() {
+    let states: Vec<_> = PERSISTENT_STATES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect();
+
+    for state in states {
+        state.flush();
+    }
+}
+
+trait Flush: Send + Sync {
+    fn flush(self: Arc<Self>);
+}
+
+/// Returns new settings for the given schema or [`None`] if the schema isn't installed.
+fn lookup_settings(schema_id: &'static str) -> Option<gio::Settings> {
+    let schema =
+        gio::SettingsSchemaSource::default().and_then(|source| source.lookup(schema_id, true));
+
+    if schema.is_some() {
+        Some(gio::Settings::new(schema_id))
+    } else {
+        tracing::error!("Couldn't find settings schema `{}`", schema_id);
+        None
+    }
+}
+
+/// Returns the cached settings for the given schema.
+///
+/// Must only be called from the default main context.
+fn main_context_settings(schema_id: &'static str) -> Option<gio::Settings> {
+    SETTINGS.with(|cache| {
+        if let Some(settings) = cache.borrow().get(schema_id) {
+            return Some(settings.clone());
+        }
+
+        let settings = lookup_settings(schema_id)?;
+        cache.borrow_mut().insert(schema_id, settings.clone());
+        Some(settings)
+    })
+}
+
+struct PersistentStateInner<Data> {
+    data: RwLock<Data>,
+    subscribers: Arc<RwLock<Subscribers<Data>>>,
+    storage: PersistentStorage,
+    delay: Duration,
+    /// Incremented on every write to debounce saving.
+    generation: AtomicUsize,
+    /// Set if the data was changed, but not saved yet.
+    save_pending: AtomicBool,
+    /// Set while a timer waits for writes to settle.
+    timer_running: AtomicBool,
+    /// Prevents concurrent writes to the storage.
+    save_lock: Mutex<()>,
+}
+
+impl<Data> PersistentStateInner<Data>
+where
+    Data: Serialize + Send + Sync + 'static,
+{
+    /// Save the data once no write happened for the duration of the delay.
+    fn schedule_save(self: &Arc<Self>) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.save_pending.store(true, Ordering::SeqCst);
+
+        // Only one timer waits for saving at a time.
+        if self.timer_running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let inner = self.clone();
+        crate::spawn(async move {
+            loop {
+                let generation = inner.generation.load(Ordering::SeqCst);
+                tokio::time::sleep(inner.delay).await;
+                if generation == inner.generation.load(Ordering::SeqCst) {
+                    break;
+                }
+            }
+
+            // Writes from now on start a new timer.
+            inner.timer_running.store(false, Ordering::SeqCst);
+            // Writing the storage blocks.
+            crate::spawn_blocking(move || inner.flush());
+        });
+    }
+
+    fn save(self: Arc<Self>) {
+        match self.storage {
+            PersistentStorage::File(path) => {
+                let _lock = self.save_lock.lock().unwrap();
+                if let Some(bytes) = self.serialize()
+                    && let Err(err) = crate::file_util::write_atomically(&path(), &bytes)
+                {
+                    tracing::error!("Couldn't save persistent state: {}", err);
+                }
+            }
+            PersistentStorage::Settings { schema_id, key } => {
+                // The data is serialized when the settings are written,
+                // so saves that are dispatched out of order still store the latest data.
+                glib::MainContext::default().invoke(move || {
+                    let Some(settings) = main_context_settings(schema_id) else {
+                        return;
+                    };
+                    if let Some(bytes) = self.serialize() {
+                        let value = String::from_utf8_lossy(&bytes);
+                        if let Err(err) = settings.set_string(key, &value) {
+                            tracing::error!("Couldn't save persistent state: {}", err);
+                        }
+                        gio::Settings::sync();
+                    }
+                });
+            }
+        }
+    }
+
+    fn serialize(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec_pretty(&*self.data.read().unwrap())
+            .map_err(|err| {
+                tracing::error!("Couldn't serialize persistent state: {}", err);
+            })
+            .ok()
+    }
+}
+
+/// A [`SharedState`](super::SharedState) that is loaded from and saved to
+/// a file or a [`gio::Settings`] key.
+///
+/// The data is loaded lazily on the first access.
+/// If nothing was stored yet or the stored data can't be read,
+/// the [`Default`] value is used.
+///
+/// After each write, the data is saved in the background once no
+/// further writes happened for a short delay.
+/// Files are written atomically, so a crash never leaves a corrupted file behind.
+/// Pending changes are saved when the state is dropped and, for static variables,
+/// once [`RelmApp::run()`](crate::RelmApp::run) returns.
+/// Call [`Self::flush()`] to save them earlier, for example if your application
+/// doesn't use [`RelmApp`](crate::RelmApp).
+///
+/// [`gio::Settings`] aren't thread safe, so they are always written
+/// from the default [`glib::MainContext`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use serde::{Deserialize, Serialize};
+/// use relm4::gtk::glib;
+/// use relm4::shared_state::{PersistentState, PersistentStorage};
+///
+/// #[derive(Default, Serialize, Deserialize)]
+/// struct Preferences {
+///     dark_mode: bool,
+/// }
+///
+/// static PREFERENCES: PersistentState<Preferences> =
+///     PersistentState::new(PersistentStorage::File(|| {
+///         glib::user_config_dir().join("my-app").join("preferences.json")
+///     }));
+///
+/// PREFERENCES.write().dark_mode = true;
+///
+/// // Save pending changes immediately.
+/// PREFERENCES.flush();
+/// ```
+///
+/// # Panics
+///
+/// Like [`SharedState`](super::SharedState), this type uses a [`RwLock`] internally.
+/// If you use [`Self::read()`] and [`Self::write()`] in the same scope
+/// your code might be stuck in a deadlock or panic.
+pub struct PersistentState<Data> {
+    storage: PersistentStorage,
+    delay: Duration,
+    inner: OnceCell<Arc<PersistentStateInner<Data>>>,
+}
+
+impl<Data: std::fmt::Debug> std::fmt::Debug for PersistentState<Data> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistentState")
+            .field("storage", &self.storage)
+            .field("delay", &self.delay)
+            .field("data", &self.inner.get().map(|inner| &inner.data))
+            .finish()
+    }
+}
+
+impl<Data> Flush for PersistentStateInner<Data>
+where
+    Data: Serialize + Send + Sync + 'static,
+{
+    /// Save the data if there are pending changes.
+    fn flush(self: Arc<Self>) {
+        if self.save_pending.swap(false, Ordering::SeqCst) {
+            self.save();
+        }
+    }
+}
+
+impl<Data> PersistentState<Data>
+where
+    Data: Serialize + DeserializeOwned + Default + Send + Sync + 'static,
+{
+    /// The default delay used to debounce saving the data.
+    pub const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+
+    /// Create a new [`PersistentState`] variable.
+    ///
+    /// The data will be loaded lazily on the first access.
+    #[must_use]
+    pub const fn new(storage: PersistentStorage) -> Self {
+        Self {
+            storage,
+            delay: Self::DEFAULT_DELAY,
+            inner: OnceCell::new(),
+        }
+    }
+
+    /// Set the delay used to debounce saving the data.
+    ///
+    /// By default, this is [`Self::DEFAULT_DELAY`].
+    #[must_use]
+    pub const fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn inner(&self) -> &Arc<PersistentStateInner<Data>> {
+        self.inner.get_or_init(|| {
+            let inner = Arc::new(PersistentStateInner {
+                data: RwLock::new(self.storage.load().unwrap_or_default()),
+                subscribers: Arc::default(),
+                storage: self.storage,
+                delay: self.delay,
+                generation: AtomicUsize::new(0),
+                save_pending: AtomicBool::new(false),
+                timer_running: AtomicBool::new(false),
+                save_lock: Mutex::new(()),
+            });
+
+            let mut states = PERSISTENT_STATES.lock().unwrap();
+            states.retain(|state| state.strong_count() > 0);
+            let flush: Arc<dyn Flush> = inner.clone();
+            states.push(Arc::downgrade(&flush));
+
+            inner
+        })
+    }
+
+    /// Subscribe to a persistent state type.
+    /// Any subscriber will be notified with a message every time
+    /// you modify the state using [`Self::write()`].
+    ///
+    /// The subscriber is removed once the returned [`Subscription`] is dropped.
+    pub fn subscribe<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
+    where
+        F: Fn(&Data) -> Msg + 'static + Send + Sync,
+        Msg: Send + 'static,
+    {
+        let sender = sender.clone();
+        self.add_subscriber(Box::new(move |data: &Data| {
+            let msg = f(data);
+            sender.send(msg).is_ok()
+        }))
+    }
+
+    /// An alternative version of [`subscribe()`](Self::subscribe()) that only send a message if
+    /// the closure returns [`Some`].
+    pub fn subscribe_optional<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
+    where
+        F: Fn(&Data) -> Option<Msg> + 'static + Send + Sync,
+        Msg: Send + 'static,
+    {
+        let sender = sender.clone();
+        self.add_subscriber(Box::new(move |data: &Data| {
+            if let Some(msg) = f(data) {
+                sender.send(msg).is_ok()
+            } else {
+                true
+            }
+        }))
+    }
+
+    /// Subscribe to a part of the persistent state.
+    ///
+    /// See [`SharedState::subscribe_selector()`](super::SharedState::subscribe_selector).
+    pub fn subscribe_selector<Msg, Selected, S, F>(
+        &self,
+        sender: &Sender<Msg>,
+        selector: S,
+        f: F,
+    ) -> Subscription
+    where
+        S: Fn(&Data) -> Selected + 'static + Send + Sync,
+        F: Fn(&Selected) -> Msg + 'static + Send + Sync,
+        Selected: PartialEq + Send + 'static,
+        Msg: Send + 'static,
+    {
+        let initial = selector(&self.read());
+        self.add_subscriber(selector_subscriber(sender, Some(initial), selector, f))
+    }
+
+    /// Subscribe to specific fields of [`Observable`] data.
+    ///
+    /// See [`SharedState::subscribe_fields()`](super::SharedState::subscribe_fields).
+    pub fn subscribe_fields<Msg, F>(
+        &self,
+        sender: &Sender<Msg>,
+        fields: Changes,
+        f: F,
+    ) -> Subscription
+    where
+        Data: Observable,
+        F: Fn(&Data) -> Msg + 'static + Send + Sync,
+        Msg: Send + 'static,
+    {
+        let inner = self.inner();
+        let mut subscribers = inner.subscribers.write().unwrap();
+        let mut data = inner.data.write().unwrap();
+        let id = subscribers.add_field_subscriber(&mut data, sender, fields, f);
+        Subscription::new(&inner.subscribers, id)
+    }
+
+    fn add_subscriber(&self, subscriber: SubscriberFn<Data>) -> Subscription {
+        let inner = self.inner();
+        let id = inner.subscribers.write().unwrap().add(subscriber);
+        Subscription::new(&inner.subscribers, id)
+    }
+
+    /// Get immutable access to the data.
+    ///
+    /// See [`SharedState::read()`](super::SharedState::read).
+    pub fn read(&self) -> SharedStateReadGuard<'_, Data> {
+        SharedStateReadGuard {
+            inner: self.inner().data.read().unwrap(),
+        }
+    }
+
+    /// Get immutable access to the data.
+    ///
+    /// Similar to [`read`](Self::read), but doesn't block so this function simply
+    /// returns an [`Err`] if the data is already locked (or poisoned).
+    pub fn try_read(
+        &self,
+    ) -> Result<SharedStateReadGuard<'_, Data>, TryLockError<RwLockReadGuard<'_, Data>>> {
+        Ok(SharedStateReadGuard {
+            inner: self.inner().data.try_read()?,
+        })
+    }
+
+    /// Get mutable access to the data.
+    ///
+    /// Returns a RAII guard which will **notify all subscribers**,
+    /// schedule saving the data and release this thread’s
+    /// shared access once it is dropped.
+    ///
+    /// See [`SharedState::write()`](super::SharedState::write).
+    pub fn write(&self) -> SharedStateWriteGuard<'_, Data> {
+        let inner = self.inner();
+        let subscribers = inner.subscribers.write().unwrap();
+        let data = inner.data.write().unwrap();
+
+        SharedStateWriteGuard {
+            data,
+            subscribers,
+            on_drop: Some(Box::new(move || inner.schedule_save())),
+        }
+    }
+
+    /// Get mutable access to the data.
+    ///
+    /// Similar to [`write`](Self::write), but doesn't block so this function simply
+    /// returns an [`Err`] if the data is already locked (or poisoned).
+    pub fn try_write(
+        &self,
+    ) -> Result<SharedStateWriteGuard<'_, Data>, TryLockError<RwLockWriteGuard<'_, Data>>> {
+        let inner = self.inner();
+        let data = inner.data.try_write()?;
+        let subscribers = inner.subscribers.write().unwrap();
+
+        Ok(SharedStateWriteGuard {
+            data,
+            subscribers,
+            on_drop: Some(Box::new(move || inner.schedule_save())),
+        })
+    }
+
+    /// Save the data immediately if there are pending changes.
+    ///
+    /// This blocks the current thread until the data is saved.
+    /// Settings are saved by the default [`glib::MainContext`], so if it's
+    /// running on another thread, they are saved asynchronously.
+    pub fn flush(&self) {
+        if let Some(inner) = self.inner.get() {
+            Arc::clone(inner).flush();
+        }
+    }
+}
+
+impl<Data> Drop for PersistentState<Data> {
+    fn drop(&mut self) {
+        // Static variables are never dropped, pending changes
+        // of those are saved by `flush_all()` on shutdown.
+        if let Some(inner) = self.inner.get() {
+            let ptr = Arc::as_ptr(inner).cast::<()>();
+            let state = PERSISTENT_STATES
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|state| state.as_ptr().cast::<()>() == ptr)
+                .and_then(Weak::upgrade);
+
+            if let Some(state) = state {
+                state.flush();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{PersistentState, PersistentStorage};
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Preferences {
+        counter: u8,
+    }
+
+    fn path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "relm4-persistent-state-{}.json",
+            std::process::id()
+        ))
+    }
+
+    static STATE: PersistentState<Preferences> =
+        PersistentState::new(PersistentStorage::File(path)).with_delay(Duration::from_millis(10));
+
+    static RELOADED_STATE: PersistentState<Preferences> =
+        PersistentState::new(PersistentStorage::File(path));
+
+    #[test]
+    fn persistent_state() {
+        let _ = std::fs::remove_file(path());
+
+        assert_eq!(STATE.read().counter, 0);
+
+        let (sender, receiver) = crate::channel();
+        let _subscription = STATE.subscribe(&sender, |data| data.counter);
+
+        STATE.write().counter += 1;
+        STATE.write().counter += 1;
+        assert_eq!(receiver.recv_sync().unwrap(), 1);
+        assert_eq!(receiver.recv_sync().unwrap(), 2);
+
+        STATE.flush();
+        assert_eq!(RELOADED_STATE.read().counter, 2);
+
+        STATE.write().counter += 1;
+        STATE.flush();
+        let stored: Preferences = serde_json::from_slice(&std::fs::read(path()).unwrap()).unwrap();
+        assert_eq!(stored.counter, 3);
+
+        std::fs::remove_file(path()).unwrap();
+    }
+
+    fn debounced_path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "relm4-persistent-state-debounced-{}.json",
+            std::process::id()
+        ))
+    }
+
+    const DELAY: Duration = Duration::from_millis(10);
+
+    static DEBOUNCED_STATE: PersistentState<Preferences> =
+        PersistentState::new(PersistentStorage::File(debounced_path)).with_delay(DELAY);
+
+    #[test]
+    fn debounced_save() {
+        let _ = std::fs::remove_file(debounced_path());
+
+        DEBOUNCED_STATE.write().counter = 1;
+        DEBOUNCED_STATE.write().counter = 2;
+
+        // Saved in the background once no write happened for the delay.
+        std::thread::sleep(DELAY * 20);
+        let stored: Preferences =
+            serde_json::from_slice(&std::fs::read(debounced_path()).unwrap()).unwrap();
+        assert_eq!(stored.counter, 2);
+
+        std::fs::remove_file(debounced_path()).unwrap();
+    }
+}
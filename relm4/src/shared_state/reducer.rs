@@ -1,10 +1,14 @@
-use std::sync::{Arc, RwLock};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 
-use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
 
 use crate::{RUNTIME, Sender};
 
-use super::SubscriberFn;
+use super::middleware::{MiddlewareInit, after_reduce, before_reduce};
+use super::{
+    Changes, Observable, Reduction, SubscriberFn, Subscribers, Subscription, selector_subscriber,
+};
 
 /// A trait that implements a reducer function.
 ///
@@ -18,45 +22,58 @@ pub trait Reducible {
 
     /// Process the input message and update the state.
     ///
-    /// Return [`true`] to notify all subscribers.
-    /// Return [`false`] to ignore all subscribers.
+    /// Return [`Reduction::notify()`] or `true.into()` to notify all subscribers.
+    /// Return [`Reduction::ignore()`] or `false.into()` to ignore all subscribers.
     ///
-    /// For example, it makes sense to return [`false`] to indicate
+    /// For example, it makes sense to ignore the subscribers to indicate
     /// that the message had no (noteworthy) effect on the data and
     /// the subscribers don't need to be notified.
-    fn reduce(&mut self, input: Self::Input) -> bool;
+    ///
+    /// Side effects, such as saving the data to disk, can be added to the
+    /// reduction as [`Effect`](super::Effect)s.
+    /// They are executed in the background after the input was processed
+    /// and can send new inputs to the reducer.
+    fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input>;
 }
 
 struct ReducerInner<Data: Reducible> {
     sender: Sender<Data::Input>,
-    subscribers: Arc<RwLock<Vec<SubscriberFn<Data>>>>,
+    data: Arc<Mutex<Data>>,
+    subscribers: Arc<RwLock<Subscribers<Data>>>,
 }
 
-impl<Data> Default for ReducerInner<Data>
+impl<Data> ReducerInner<Data>
 where
     Data: Reducible + Send + 'static,
     Data::Input: Send,
 {
-    fn default() -> Self {
+    fn new(middleware: Option<MiddlewareInit<Data, Data::Input>>) -> Self {
         let (sender, receiver) = crate::channel();
-        let subscribers: Arc<RwLock<Vec<SubscriberFn<Data>>>> = Arc::default();
+        let data = Arc::new(Mutex::new(Data::init()));
+        let subscribers: Arc<RwLock<Subscribers<Data>>> = Arc::default();
 
+        let rt_data = data.clone();
         let rt_subscribers = subscribers.clone();
+        // Effects only hold a weak sender, so they don't keep the runtime alive.
+        let rt_sender = sender.0.downgrade();
         RUNTIME.spawn(async move {
-            let mut data = Data::init();
+            let mut middleware = middleware.map(|init| init()).unwrap_or_default();
             while let Some(input) = receiver.recv().await {
-                if data.reduce(input) {
-                    // Remove all elements which had their senders dropped.
-                    rt_subscribers
-                        .write()
-                        .unwrap()
-                        .retain(|subscriber| subscriber(&data));
-                }
+                // Subscribers are notified while the data is locked, so subscribers
+                // that receive the current value on subscription don't miss updates.
+                let mut data = rt_data.lock().unwrap();
+                let Some(input) = before_reduce(&mut middleware, &data, input) else {
+                    continue;
+                };
+                let mut reduction = data.reduce(input);
+                after_reduce(&mut middleware, &data, &mut reduction);
+                reduction.finish(&mut *data, &rt_subscribers, &rt_sender);
             }
         });
 
         Self {
             sender,
+            data,
             subscribers,
         }
     }
@@ -69,6 +86,7 @@ where
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ReducerInner")
             .field("sender", &self.sender)
+            .field("data", &self.data)
             .field("subscribers", &self.subscribers.try_read().map(|s| s.len()))
             .finish()
     }
@@ -89,6 +107,7 @@ where
 ///
 /// ```
 /// use relm4::{Reducer, Reducible};
+/// use relm4::shared_state::Reduction;
 ///
 /// struct CounterReducer(u8);
 ///
@@ -104,7 +123,7 @@ where
 ///         Self(0)
 ///     }
 ///
-///     fn reduce(&mut self, input: Self::Input) -> bool {
+///     fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
 ///         match input {
 ///             CounterInput::Increment => {
 ///                 self.0 += 1;
@@ -113,7 +132,7 @@ where
 ///                 self.0 -= 1;
 ///             }
 ///         }
-///         true
+///         Reduction::notify()
 ///     }
 /// }
 ///
@@ -127,7 +146,7 @@ where
 ///
 /// // Create a channel and subscribe to changes.
 /// let (sender, receiver) = relm4::channel();
-/// REDUCER.subscribe(&sender, |data| data.0);
+/// let _subscription = REDUCER.subscribe(&sender, |data| data.0);
 ///
 /// // Count up to 2.
 /// REDUCER.emit(CounterInput::Increment);
@@ -135,7 +154,8 @@ where
 /// ```
 #[derive(Debug)]
 pub struct Reducer<Data: Reducible> {
-    inner: Lazy<ReducerInner<Data>>,
+    inner: OnceCell<ReducerInner<Data>>,
+    middleware: Option<MiddlewareInit<Data, Data::Input>>,
 }
 
 impl<Data> Default for Reducer<Data>
@@ -159,67 +179,280 @@ where
     #[must_use]
     pub const fn new() -> Self {
         Self {
-            inner: Lazy::new(ReducerInner::default),
+            inner: OnceCell::new(),
+            middleware: None,
+        }
+    }
+
+    /// Create a new [`Reducer`] variable that applies [`Middleware`](super::Middleware)
+    /// around its reducer function.
+    ///
+    /// The middleware is created by the given function
+    /// when the data is initialized on the first access.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use relm4::{Reducer, Reducible};
+    /// use relm4::shared_state::{LoggingMiddleware, Reduction, ValidationMiddleware};
+    ///
+    /// #[derive(Debug)]
+    /// struct CounterReducer(u8);
+    ///
+    /// #[derive(Debug)]
+    /// enum CounterInput {
+    ///     Increment,
+    ///     Decrement,
+    /// }
+    ///
+    /// impl Reducible for CounterReducer {
+    ///     type Input = CounterInput;
+    ///
+    ///     fn init() -> Self {
+    ///         Self(0)
+    ///     }
+    ///
+    ///     fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
+    ///         match input {
+    ///             CounterInput::Increment => self.0 += 1,
+    ///             CounterInput::Decrement => self.0 -= 1,
+    ///         }
+    ///         Reduction::notify()
+    ///     }
+    /// }
+    ///
+    /// static REDUCER: Reducer<CounterReducer> = Reducer::with_middleware(|| {
+    ///     vec![
+    ///         Box::new(LoggingMiddleware),
+    ///         // Prevent the counter from underflowing.
+    ///         Box::new(ValidationMiddleware::new(|data: &CounterReducer, input: &CounterInput| {
+    ///             data.0 > 0 || !matches!(input, CounterInput::Decrement)
+    ///         })),
+    ///     ]
+    /// });
+    /// ```
+    #[must_use]
+    pub const fn with_middleware(middleware: MiddlewareInit<Data, Data::Input>) -> Self {
+        Self {
+            inner: OnceCell::new(),
+            middleware: Some(middleware),
         }
     }
 
+    fn inner(&self) -> &ReducerInner<Data> {
+        self.inner
+            .get_or_init(|| ReducerInner::new(self.middleware))
+    }
+
     /// Subscribe to a [`Reducer`].
     /// Any subscriber will be notified with a message every time
     /// you modify the reducer (by calling [`Self::emit()`]).
-    pub fn subscribe<Msg, F>(&self, sender: &Sender<Msg>, f: F)
+    ///
+    /// The subscriber is removed once the returned [`Subscription`] is dropped.
+    /// Call [`Subscription::detach()`] to keep it until the receiver is dropped instead.
+    pub fn subscribe<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
     where
         F: Fn(&Data) -> Msg + 'static + Send + Sync,
         Msg: Send + 'static,
     {
         let sender = sender.clone();
-        self.inner
-            .subscribers
-            .write()
-            .unwrap()
-            .push(Box::new(move |data: &Data| {
-                let msg = f(data);
-                sender.send(msg).is_ok()
-            }));
+        self.add_subscriber(Box::new(move |data: &Data| {
+            let msg = f(data);
+            sender.send(msg).is_ok()
+        }))
     }
 
     /// An alternative version of [`subscribe()`](Self::subscribe()) that only send a message if
     /// the closure returns [`Some`].
-    pub fn subscribe_optional<Msg, F>(&self, sender: &Sender<Msg>, f: F)
+    pub fn subscribe_optional<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
     where
         F: Fn(&Data) -> Option<Msg> + 'static + Send + Sync,
         Msg: Send + 'static,
     {
         let sender = sender.clone();
-        self.inner
+        self.add_subscriber(Box::new(move |data: &Data| {
+            if let Some(msg) = f(data) {
+                sender.send(msg).is_ok()
+            } else {
+                true
+            }
+        }))
+    }
+
+    /// Subscribe to a part of a [`Reducer`].
+    ///
+    /// The `selector` closure picks the part of the data this subscriber
+    /// is interested in. A message is only sent if the selected value
+    /// differs from the previously selected value.
+    ///
+    /// # Panics
+    ///
+    /// This function might panic or dead lock when called inside of a subscriber
+    /// or if the reducer function panicked before.
+    pub fn subscribe_selector<Msg, Selected, S, F>(
+        &self,
+        sender: &Sender<Msg>,
+        selector: S,
+        f: F,
+    ) -> Subscription
+    where
+        S: Fn(&Data) -> Selected + 'static + Send + Sync,
+        F: Fn(&Selected) -> Msg + 'static + Send + Sync,
+        Selected: PartialEq + Send + 'static,
+        Msg: Send + 'static,
+    {
+        // Keep the data locked, so no update can happen in between.
+        let data = self.read();
+        let initial = selector(&data);
+        let subscription =
+            self.add_subscriber(selector_subscriber(sender, Some(initial), selector, f));
+        drop(data);
+        subscription
+    }
+
+    /// Subscribe to specific fields of the [`Observable`] data of a [`Reducer`].
+    ///
+    /// A message is only sent if at least one of the given fields
+    /// was changed since the subscribers were notified the last time.
+    pub fn subscribe_fields<Msg, F>(
+        &self,
+        sender: &Sender<Msg>,
+        fields: Changes,
+        f: F,
+    ) -> Subscription
+    where
+        Data: Observable + 'static,
+        F: Fn(&Data) -> Msg + 'static + Send + Sync,
+        Msg: Send + 'static,
+    {
+        let inner = self.inner();
+        // The runtime locks the data before the subscribers.
+        let mut data = inner.data.lock().unwrap();
+        let id = inner
             .subscribers
             .write()
             .unwrap()
-            .push(Box::new(move |data: &Data| {
-                if let Some(msg) = f(data) {
-                    sender.send(msg).is_ok()
-                } else {
-                    true
-                }
-            }));
+            .add_field_subscriber(&mut data, sender, fields, f);
+        Subscription::new(&inner.subscribers, id)
+    }
+
+    /// Subscribe to a [`Reducer`] and immediately receive a message
+    /// for the current data.
+    ///
+    /// Afterwards, the subscriber will be notified like with [`Self::subscribe()`].
+    /// This is useful for components that are initialized after the last change
+    /// of the reducer.
+    ///
+    /// # Panics
+    ///
+    /// This function might panic or dead lock when called inside of a subscriber
+    /// or if the reducer function panicked before.
+    pub fn subscribe_with_current<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
+    where
+        F: Fn(&Data) -> Msg + 'static + Send + Sync,
+        Msg: Send + 'static,
+    {
+        // Keep the data locked, so no update can happen in between.
+        let data = self.read();
+        // The receiver might be dropped already. In this case,
+        // the subscriber will be removed on the next update.
+        let _ = sender.send(f(&data));
+        let subscription = self.subscribe(sender, f);
+        drop(data);
+        subscription
+    }
+
+    /// Get immutable access to the current data.
+    ///
+    /// Returns a RAII guard that prevents the reducer from updating
+    /// its data until it is dropped. Therefore, the guard should only
+    /// be held for a short time.
+    ///
+    /// # Panics
+    ///
+    /// This function might panic or dead lock when called inside of a subscriber
+    /// or while the current thread holds another guard of this reducer.
+    /// It also panics if the reducer function panicked before.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use relm4::{Reducer, Reducible};
+    /// # use relm4::shared_state::Reduction;
+    /// # struct CounterReducer(u8);
+    /// # impl Reducible for CounterReducer {
+    /// #     type Input = ();
+    /// #     fn init() -> Self { Self(0) }
+    /// #     fn reduce(&mut self, _input: ()) -> Reduction<()> {
+    /// #         self.0 += 1;
+    /// #         Reduction::notify()
+    /// #     }
+    /// # }
+    /// static REDUCER: Reducer<CounterReducer> = Reducer::new();
+    ///
+    /// assert_eq!(REDUCER.read().0, 0);
+    /// ```
+    pub fn read(&self) -> ReducerReadGuard<'_, Data> {
+        ReducerReadGuard {
+            inner: self.inner().data.lock().unwrap(),
+        }
+    }
+
+    /// Get a copy of the current data.
+    ///
+    /// # Panics
+    ///
+    /// See [`Self::read()`].
+    #[must_use]
+    pub fn snapshot(&self) -> Data
+    where
+        Data: Clone,
+    {
+        self.read().clone()
+    }
+
+    fn add_subscriber(&self, subscriber: SubscriberFn<Data>) -> Subscription {
+        let id = self.inner().subscribers.write().unwrap().add(subscriber);
+        Subscription::new(&self.inner().subscribers, id)
     }
 
     /// Sends a message to the reducer to update its state.
     ///
-    /// If the [`Reducible::reduce()`] method returns [`true`],
-    /// all subscribers will be notified.
+    /// If the [`Reduction`] returned by the [`Reducible::reduce()`] method
+    /// notifies the subscribers, all subscribers will be notified.
     pub fn emit(&self, input: Data::Input) {
         assert!(
-            self.inner.sender.send(input).is_ok(),
+            self.inner().sender.send(input).is_ok(),
             "Reducer runtime was dropped. Maybe a subscriber or the update function panicked?"
         );
     }
 }
 
+/// A guard that immutably dereferences the data of a [`Reducer`].
+///
+/// The reducer can't update its data while this guard exists.
+#[derive(Debug)]
+pub struct ReducerReadGuard<'a, Data> {
+    inner: MutexGuard<'a, Data>,
+}
+
+impl<Data> Deref for ReducerReadGuard<'_, Data> {
+    type Target = Data;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::sync::atomic::{AtomicU8, Ordering};
     use std::time::Duration;
 
     use super::{Reducer, Reducible};
+    use crate::shared_state::{
+        Effect, LoggingMiddleware, PersistenceMiddleware, Reduction, ValidationMiddleware,
+    };
 
     struct CounterReducer(u8);
 
@@ -235,7 +468,7 @@ mod test {
             Self(0)
         }
 
-        fn reduce(&mut self, input: Self::Input) -> bool {
+        fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
             match input {
                 CounterInput::Increment => {
                     self.0 += 1;
@@ -244,7 +477,7 @@ mod test {
                     self.0 -= 1;
                 }
             }
-            true
+            Reduction::notify()
         }
     }
 
@@ -260,7 +493,7 @@ mod test {
 
         let (sender, receiver) = crate::channel();
 
-        REDUCER.subscribe(&sender, |data| data.0);
+        let _subscription = REDUCER.subscribe(&sender, |data| data.0);
 
         // Count up to 4 with receiver.
         REDUCER.emit(CounterInput::Increment);
@@ -271,4 +504,144 @@ mod test {
 
         assert_eq!(receiver.recv_sync().unwrap(), 3);
     }
+
+    #[derive(Default)]
+    struct PairReducer(u8, u8);
+
+    enum PairInput {
+        IncrementFirst,
+        IncrementSecond,
+    }
+
+    impl Reducible for PairReducer {
+        type Input = PairInput;
+
+        fn init() -> Self {
+            Self::default()
+        }
+
+        fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
+            match input {
+                PairInput::IncrementFirst => self.0 += 1,
+                PairInput::IncrementSecond => self.1 += 1,
+            }
+            Reduction::notify()
+        }
+    }
+
+    static PAIR_REDUCER: Reducer<PairReducer> = Reducer::new();
+
+    #[test]
+    fn subscribe_selector() {
+        let (sender, receiver) = crate::channel();
+
+        let _subscription = PAIR_REDUCER.subscribe_selector(&sender, |data| data.1, |value| *value);
+
+        // The selected value is unchanged, so no message is sent.
+        PAIR_REDUCER.emit(PairInput::IncrementFirst);
+        PAIR_REDUCER.emit(PairInput::IncrementSecond);
+        assert_eq!(receiver.recv_sync().unwrap(), 1);
+
+        // Changes of the first value are ignored.
+        PAIR_REDUCER.emit(PairInput::IncrementFirst);
+        PAIR_REDUCER.emit(PairInput::IncrementSecond);
+        assert_eq!(receiver.recv_sync().unwrap(), 2);
+    }
+
+    #[derive(Debug)]
+    struct EffectReducer(u8);
+
+    #[derive(Debug)]
+    enum EffectInput {
+        Increment,
+        IncrementLater,
+        Reset,
+    }
+
+    impl Reducible for EffectReducer {
+        type Input = EffectInput;
+
+        fn init() -> Self {
+            Self(0)
+        }
+
+        fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
+            match input {
+                EffectInput::Increment => {
+                    self.0 += 1;
+                    Reduction::notify()
+                }
+                EffectInput::IncrementLater => Reduction::ignore()
+                    .with_effect(Effect::oneshot(async { Some(EffectInput::Increment) })),
+                EffectInput::Reset => {
+                    self.0 = 0;
+                    Reduction::notify()
+                }
+            }
+        }
+    }
+
+    static PERSISTED: AtomicU8 = AtomicU8::new(0);
+
+    static EFFECT_REDUCER: Reducer<EffectReducer> = Reducer::with_middleware(|| {
+        vec![
+            Box::new(LoggingMiddleware),
+            Box::new(ValidationMiddleware::new(
+                |_: &EffectReducer, input: &EffectInput| !matches!(input, EffectInput::Reset),
+            )),
+            Box::new(PersistenceMiddleware::new(|data: &EffectReducer| {
+                PERSISTED.store(data.0, Ordering::SeqCst);
+            })),
+        ]
+    });
+
+    #[test]
+    fn effects_and_middleware() {
+        let (sender, receiver) = crate::channel();
+
+        let _subscription = EFFECT_REDUCER.subscribe(&sender, |data| data.0);
+
+        // The effect sends the increment input back to the reducer.
+        EFFECT_REDUCER.emit(EffectInput::IncrementLater);
+        assert_eq!(receiver.recv_sync().unwrap(), 1);
+        assert_eq!(PERSISTED.load(Ordering::SeqCst), 1);
+
+        // Resetting is rejected by the validation middleware.
+        EFFECT_REDUCER.emit(EffectInput::Reset);
+        EFFECT_REDUCER.emit(EffectInput::Increment);
+        assert_eq!(receiver.recv_sync().unwrap(), 2);
+        assert_eq!(PERSISTED.load(Ordering::SeqCst), 2);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct NameReducer(String);
+
+    impl Reducible for NameReducer {
+        type Input = &'static str;
+
+        fn init() -> Self {
+            Self("Relm4".into())
+        }
+
+        fn reduce(&mut self, input: Self::Input) -> Reduction<Self::Input> {
+            self.0 = input.into();
+            Reduction::notify()
+        }
+    }
+
+    static NAME_REDUCER: Reducer<NameReducer> = Reducer::new();
+
+    #[test]
+    fn read_current_value() {
+        assert_eq!(NAME_REDUCER.read().0, "Relm4");
+        assert_eq!(NAME_REDUCER.snapshot(), NameReducer("Relm4".into()));
+
+        let (sender, receiver) = crate::channel();
+        let _subscription = NAME_REDUCER.subscribe_with_current(&sender, |data| data.0.clone());
+        assert_eq!(receiver.recv_sync().unwrap(), "Relm4");
+
+        NAME_REDUCER.emit("GTK");
+        assert_eq!(receiver.recv_sync().unwrap(), "GTK");
+        assert_eq!(NAME_REDUCER.read().0, "GTK");
+    }
 }
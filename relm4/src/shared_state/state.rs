@@ -1,13 +1,13 @@
 use std::{
     ops::{Deref, DerefMut},
-    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError},
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError},
 };
 
 use once_cell::sync::Lazy;
 
 use crate::Sender;
 
-use super::SubscriberFn;
+use super::{Changes, Observable, SubscriberFn, Subscribers, Subscription, selector_subscriber};
 
 /// A type that allows you to share information across your
 /// application easily.
@@ -20,7 +20,7 @@ use super::SubscriberFn;
 /// your code might be stuck in a deadlock or panic.
 pub struct SharedState<Data> {
     data: Lazy<RwLock<Data>>,
-    subscribers: Lazy<RwLock<Vec<SubscriberFn<Data>>>>,
+    subscribers: Lazy<Arc<RwLock<Subscribers<Data>>>>,
 }
 
 impl<Data: std::fmt::Debug> std::fmt::Debug for SharedState<Data> {
@@ -61,13 +61,16 @@ where
     pub const fn new() -> Self {
         Self {
             data: Lazy::new(RwLock::default),
-            subscribers: Lazy::new(RwLock::default),
+            subscribers: Lazy::new(Arc::default),
         }
     }
 
     /// Subscribe to a shared state type.
     /// Any subscriber will be notified with a message every time
-    /// you modify the shared state using [`Self::get_mut()`].
+    /// you modify the shared state using [`Self::write()`].
+    ///
+    /// The subscriber is removed once the returned [`Subscription`] is dropped.
+    /// Call [`Subscription::detach()`] to keep it until the receiver is dropped instead.
     ///
     /// ```
     /// use relm4::SharedState;
@@ -77,7 +80,7 @@ where
     ///
     /// // Every time we modify the data, we will receive
     /// // the updated value as a message.
-    /// STATE.subscribe(&sender, |data| *data);
+    /// let _subscription = STATE.subscribe(&sender, |data| *data);
     ///
     /// {
     ///     let mut data = STATE.write();
@@ -86,39 +89,111 @@ where
     ///
     /// assert_eq!(receiver.recv_sync().unwrap(), 1);
     /// ```
-    pub fn subscribe<Msg, F>(&self, sender: &Sender<Msg>, f: F)
+    pub fn subscribe<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
     where
+        Data: 'static,
         F: Fn(&Data) -> Msg + 'static + Send + Sync,
         Msg: Send + 'static,
     {
         let sender = sender.clone();
-        self.subscribers
-            .write()
-            .unwrap()
-            .push(Box::new(move |data: &Data| {
-                let msg = f(data);
-                sender.send(msg).is_ok()
-            }));
+        self.add_subscriber(Box::new(move |data: &Data| {
+            let msg = f(data);
+            sender.send(msg).is_ok()
+        }))
     }
 
     /// An alternative version of [`subscribe()`](Self::subscribe()) that only send a message if
     /// the closure returns [`Some`].
-    pub fn subscribe_optional<Msg, F>(&self, sender: &Sender<Msg>, f: F)
+    pub fn subscribe_optional<Msg, F>(&self, sender: &Sender<Msg>, f: F) -> Subscription
     where
+        Data: 'static,
         F: Fn(&Data) -> Option<Msg> + 'static + Send + Sync,
         Msg: Send + 'static,
     {
         let sender = sender.clone();
-        self.subscribers
-            .write()
-            .unwrap()
-            .push(Box::new(move |data: &Data| {
-                if let Some(msg) = f(data) {
-                    sender.send(msg).is_ok()
-                } else {
-                    true
-                }
-            }));
+        self.add_subscriber(Box::new(move |data: &Data| {
+            if let Some(msg) = f(data) {
+                sender.send(msg).is_ok()
+            } else {
+                true
+            }
+        }))
+    }
+
+    /// Subscribe to a part of the shared state.
+    ///
+    /// The `selector` closure picks the part of the data this subscriber
+    /// is interested in. A message is only sent if the selected value
+    /// differs from the previously selected value, which is initially
+    /// the value selected from the current data.
+    ///
+    /// ```
+    /// use relm4::SharedState;
+    ///
+    /// #[derive(Default)]
+    /// struct Data {
+    ///     counter: u8,
+    ///     name: String,
+    /// }
+    ///
+    /// static STATE: SharedState<Data> = SharedState::new();
+    ///
+    /// let (sender, receiver) = relm4::channel();
+    ///
+    /// // Only receive a message if the counter changes.
+    /// let _subscription = STATE.subscribe_selector(&sender, |data| data.counter, |counter| *counter);
+    ///
+    /// // Doesn't send a message.
+    /// STATE.write().name = "Relm4".into();
+    ///
+    /// STATE.write().counter += 1;
+    /// assert_eq!(receiver.recv_sync().unwrap(), 1);
+    /// ```
+    pub fn subscribe_selector<Msg, Selected, S, F>(
+        &self,
+        sender: &Sender<Msg>,
+        selector: S,
+        f: F,
+    ) -> Subscription
+    where
+        Data: 'static,
+        S: Fn(&Data) -> Selected + 'static + Send + Sync,
+        F: Fn(&Selected) -> Msg + 'static + Send + Sync,
+        Selected: PartialEq + Send + 'static,
+        Msg: Send + 'static,
+    {
+        let initial = selector(&self.read());
+        self.add_subscriber(selector_subscriber(sender, Some(initial), selector, f))
+    }
+
+    /// Subscribe to specific fields of [`Observable`] data.
+    ///
+    /// A message is only sent if at least one of the given fields
+    /// was changed since the subscribers were notified the last time.
+    /// See [`Observable`] for an example.
+    pub fn subscribe_fields<Msg, F>(
+        &self,
+        sender: &Sender<Msg>,
+        fields: Changes,
+        f: F,
+    ) -> Subscription
+    where
+        Data: Observable + 'static,
+        F: Fn(&Data) -> Msg + 'static + Send + Sync,
+        Msg: Send + 'static,
+    {
+        let mut subscribers = self.subscribers.write().unwrap();
+        let mut data = self.data.write().unwrap();
+        let id = subscribers.add_field_subscriber(&mut data, sender, fields, f);
+        Subscription::new(&self.subscribers, id)
+    }
+
+    fn add_subscriber(&self, subscriber: SubscriberFn<Data>) -> Subscription
+    where
+        Data: 'static,
+    {
+        let id = self.subscribers.write().unwrap().add(subscriber);
+        Subscription::new(&self.subscribers, id)
     }
 
     /// Get immutable access to the shared data.
@@ -202,7 +277,11 @@ where
         let subscribers = self.subscribers.write().unwrap();
         let data = self.data.write().unwrap();
 
-        SharedStateWriteGuard { data, subscribers }
+        SharedStateWriteGuard {
+            data,
+            subscribers,
+            on_drop: None,
+        }
     }
 
     /// Get mutable access to the shared data.
@@ -215,7 +294,11 @@ where
         let data = self.data.try_write()?;
         let subscribers = self.subscribers.write().unwrap();
 
-        Ok(SharedStateWriteGuard { data, subscribers })
+        Ok(SharedStateWriteGuard {
+            data,
+            subscribers,
+            on_drop: None,
+        })
     }
 
     /// Get mutable access to the shared data.
@@ -270,7 +353,7 @@ where
 #[derive(Debug)]
 /// A guard that immutably dereferences `Data`.
 pub struct SharedStateReadGuard<'a, Data> {
-    inner: RwLockReadGuard<'a, Data>,
+    pub(super) inner: RwLockReadGuard<'a, Data>,
 }
 
 impl<Data> Deref for SharedStateReadGuard<'_, Data> {
@@ -284,8 +367,10 @@ impl<Data> Deref for SharedStateReadGuard<'_, Data> {
 /// A guard that mutably dereferences `Data`.
 /// Once dropped all subscribers of the [`SharedState`] will be notified.
 pub struct SharedStateWriteGuard<'a, Data> {
-    data: RwLockWriteGuard<'a, Data>,
-    subscribers: RwLockWriteGuard<'a, Vec<SubscriberFn<Data>>>,
+    pub(super) data: RwLockWriteGuard<'a, Data>,
+    pub(super) subscribers: RwLockWriteGuard<'a, Subscribers<Data>>,
+    /// Called after the subscribers were notified.
+    pub(super) on_drop: Option<Box<dyn FnOnce() + 'a>>,
 }
 
 impl<Data: std::fmt::Debug> std::fmt::Debug for SharedStateWriteGuard<'_, Data> {
@@ -314,15 +399,17 @@ impl<Data> DerefMut for SharedStateWriteGuard<'_, Data> {
 impl<Data> Drop for SharedStateWriteGuard<'_, Data> {
     // Notify subscribers
     fn drop(&mut self) {
-        let data = &*self.data;
-        // Remove all elements which had their senders dropped.
-        self.subscribers.retain(|subscriber| subscriber(data));
+        self.subscribers.notify(&mut self.data);
+        if let Some(on_drop) = self.on_drop.take() {
+            on_drop();
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::SharedState;
+    use crate::shared_state::{Changes, Observable};
 
     static STATE: SharedState<u8> = SharedState::new();
 
@@ -339,7 +426,7 @@ mod test {
 
         let (sender, receiver) = crate::channel();
 
-        STATE.subscribe(&sender, |data| *data);
+        let _subscription = STATE.subscribe(&sender, |data| *data);
 
         {
             let mut data = STATE.write();
@@ -349,4 +436,137 @@ mod test {
         assert_eq!(receiver.recv_sync().unwrap(), 2);
         assert_eq!(*STATE.read(), 2);
     }
+
+    static SELECTOR_STATE: SharedState<(u8, u8)> = SharedState::new();
+
+    #[test]
+    fn subscribe_selector() {
+        let (sender, receiver) = crate::channel();
+
+        let _subscription =
+            SELECTOR_STATE.subscribe_selector(&sender, |data| data.0, |value| *value);
+
+        // Changes of the other field and writes without changes are ignored.
+        SELECTOR_STATE.write().1 += 1;
+        drop(SELECTOR_STATE.write());
+        assert!(receiver.0.try_recv().is_err());
+
+        SELECTOR_STATE.write().0 += 1;
+        assert_eq!(receiver.recv_sync().unwrap(), 1);
+
+        SELECTOR_STATE.write().1 += 1;
+        assert!(receiver.0.try_recv().is_err());
+    }
+
+    static SUBSCRIPTION_STATE: SharedState<u8> = SharedState::new();
+
+    #[test]
+    fn subscription() {
+        let (sender, receiver) = crate::channel();
+
+        let subscription = SUBSCRIPTION_STATE.subscribe(&sender, |data| *data);
+        *SUBSCRIPTION_STATE.write() += 1;
+        assert_eq!(receiver.recv_sync().unwrap(), 1);
+
+        drop(subscription);
+        *SUBSCRIPTION_STATE.write() += 1;
+        assert!(receiver.0.try_recv().is_err());
+        assert_eq!(SUBSCRIPTION_STATE.subscribers.read().unwrap().len(), 0);
+
+        // Detached subscriptions stay alive until the receiver is dropped.
+        SUBSCRIPTION_STATE.subscribe(&sender, |data| *data).detach();
+        *SUBSCRIPTION_STATE.write() += 1;
+        assert_eq!(receiver.recv_sync().unwrap(), 3);
+
+        drop(receiver);
+        *SUBSCRIPTION_STATE.write() += 1;
+        assert_eq!(SUBSCRIPTION_STATE.subscribers.read().unwrap().len(), 0);
+    }
+
+    static SHUTDOWN_STATE: SharedState<u8> = SharedState::new();
+
+    #[test]
+    fn subscription_drop_on_shutdown() {
+        let (sender, receiver) = crate::channel::<u8>();
+        let (shutdown_sender, shutdown_receiver) = crate::shutdown::channel();
+
+        SHUTDOWN_STATE
+            .subscribe(&sender, |data| *data)
+            .drop_on_shutdown(shutdown_receiver);
+        assert_eq!(SHUTDOWN_STATE.subscribers.read().unwrap().len(), 1);
+        drop(sender);
+
+        // The receiver is disconnected once the subscriber and
+        // with it the last sender was removed after the shutdown.
+        shutdown_sender.shutdown();
+        assert_eq!(receiver.recv_sync(), None);
+        assert_eq!(SHUTDOWN_STATE.subscribers.read().unwrap().len(), 0);
+    }
+
+    #[derive(Default)]
+    struct Fields {
+        first: u8,
+        second: u8,
+        changes: Changes,
+    }
+
+    impl Observable for Fields {
+        fn changes(&self) -> Changes {
+            self.changes
+        }
+
+        fn set_changes(&mut self, changes: Changes) {
+            self.changes = changes;
+        }
+    }
+
+    static FIELDS_STATE: SharedState<Fields> = SharedState::new();
+
+    #[test]
+    fn subscribe_fields() {
+        let (sender, receiver) = crate::channel();
+        let _subscription =
+            FIELDS_STATE.subscribe_fields(&sender, Changes::field(0), |data| data.first);
+
+        {
+            let mut data = FIELDS_STATE.write();
+            data.second += 1;
+            data.changes.insert(Changes::field(1));
+        }
+        assert!(FIELDS_STATE.read().changes().is_empty());
+        assert!(receiver.0.try_recv().is_err());
+
+        {
+            let mut data = FIELDS_STATE.write();
+            data.first += 1;
+            data.changes.insert(Changes::field(0));
+        }
+        assert_eq!(receiver.recv_sync().unwrap(), 1);
+
+        // The changes were reset, so unrelated writes don't notify the subscriber.
+        FIELDS_STATE.write().second += 1;
+        assert!(receiver.0.try_recv().is_err());
+    }
+
+    static LATE_FIELDS_STATE: SharedState<Fields> = SharedState::new();
+
+    #[test]
+    fn subscribe_fields_after_changes() {
+        // Without field subscribers, the changes aren't reset.
+        {
+            let mut data = LATE_FIELDS_STATE.write();
+            data.first += 1;
+            data.changes.insert(Changes::field(0));
+        }
+        assert!(LATE_FIELDS_STATE.read().changed(Changes::field(0)));
+
+        let (sender, receiver) = crate::channel();
+        let _subscription =
+            LATE_FIELDS_STATE.subscribe_fields(&sender, Changes::field(0), |data| data.first);
+        assert!(LATE_FIELDS_STATE.read().changes().is_empty());
+
+        // Changes from before the subscription don't notify the subscriber.
+        LATE_FIELDS_STATE.write().second += 1;
+        assert!(receiver.0.try_recv().is_err());
+    }
 }
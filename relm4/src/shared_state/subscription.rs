@@ -0,0 +1,74 @@
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use crate::ShutdownReceiver;
+
+use super::Subscribers;
+
+type UnsubscribeFn = Box<dyn FnOnce() + Send + Sync>;
+
+/// A guard that keeps a subscriber of a shared state type alive.
+///
+/// The subscriber is removed once this guard is dropped.
+/// Use [`detach()`](Self::detach) to keep the subscriber until
+/// the receiver of the messages is dropped or
+/// [`drop_on_shutdown()`](Self::drop_on_shutdown) to remove the
+/// subscriber once a component is shut down.
+///
+/// # Panics
+///
+/// Dropping this guard while holding a
+/// [`SharedStateWriteGuard`](super::SharedStateWriteGuard)
+/// of the same shared state or inside of a subscriber
+/// might panic or result in a dead lock.
+#[must_use = "The subscriber is removed once the subscription is dropped"]
+pub struct Subscription {
+    unsubscribe: Option<UnsubscribeFn>,
+}
+
+impl fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscription")
+            .field("active", &self.unsubscribe.is_some())
+            .finish()
+    }
+}
+
+impl Subscription {
+    pub(super) fn new<Data: 'static>(
+        subscribers: &Arc<RwLock<Subscribers<Data>>>,
+        id: usize,
+    ) -> Self {
+        let subscribers = Arc::downgrade(subscribers);
+        Self {
+            unsubscribe: Some(Box::new(move || {
+                if let Some(subscribers) = subscribers.upgrade() {
+                    subscribers.write().unwrap().remove(id);
+                }
+            })),
+        }
+    }
+
+    /// Keep the subscriber until the receiver of the messages is dropped.
+    pub fn detach(mut self) {
+        self.unsubscribe = None;
+    }
+
+    /// Remove the subscriber once a shutdown signal is received,
+    /// for example when the component that owns the
+    /// [`ShutdownReceiver`] is shut down.
+    pub fn drop_on_shutdown(self, shutdown: ShutdownReceiver) {
+        crate::spawn(async move {
+            shutdown.wait().await;
+            drop(self);
+        });
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+        }
+    }
+}
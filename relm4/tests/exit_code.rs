@@ -0,0 +1,40 @@
+use gtk::glib::ExitCode;
+use gtk::prelude::*;
+use relm4::{main_application, prelude::*};
+
+struct App;
+
+#[relm4::component]
+impl SimpleComponent for App {
+    type Init = ();
+    type Input = ();
+    type Output = ();
+
+    view! {
+        gtk::Window {}
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = App;
+        let widgets = view_output!();
+
+        sender.input(());
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, _msg: Self::Input, _sender: ComponentSender<Self>) {
+        relm4::set_exit_code(3);
+        main_application().quit();
+    }
+}
+
+#[test]
+fn exit_code() {
+    let app = RelmApp::new("relm4.test.exitCode");
+    assert_eq!(app.run::<App>(()), ExitCode::new(3));
+}
@@ -0,0 +1,51 @@
+use gtk::glib::ExitCode;
+use relm4::prelude::*;
+
+struct App;
+
+#[derive(Debug)]
+enum AppOutput {
+    Quit(u8),
+}
+
+impl From<AppOutput> for ExitCode {
+    fn from(output: AppOutput) -> Self {
+        match output {
+            AppOutput::Quit(code) => code.into(),
+        }
+    }
+}
+
+#[relm4::component]
+impl SimpleComponent for App {
+    type Init = ();
+    type Input = ();
+    type Output = AppOutput;
+
+    view! {
+        gtk::Window {}
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = App;
+        let widgets = view_output!();
+
+        sender.input(());
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, _msg: Self::Input, sender: ComponentSender<Self>) {
+        sender.output(AppOutput::Quit(4)).unwrap();
+    }
+}
+
+#[test]
+fn exit_code_output() {
+    let app = RelmApp::new("relm4.test.exitCodeOutput");
+    assert_eq!(app.run_with_exit_code::<App>(()), ExitCode::new(4));
+}